@@ -0,0 +1,90 @@
+//! Statically dispatched patches.
+
+//---------------------------------------------------------------------------------------------------- Use
+use crate::error::BoxError;
+
+#[allow(unused_imports)] // docs
+use crate::{Patch, Reader, Writer};
+
+//---------------------------------------------------------------------------------------------------- Apply
+/// Something that can be applied to your data `T`, like a [`Patch`].
+///
+/// A [`Writer`] is generic over the type of its `Patch`'s, `Writer<T, P>`,
+/// which defaults to [`Patch<T>`]. Each `Patch<T>` that captures state is
+/// a heap allocation and is dynamically dispatched, which is a cost
+/// paid on every [`Writer::add`] for many small modifications.
+///
+/// Any type that implements `Apply<T>` can be used instead, e.g. an
+/// `enum` of operations, or a function pointer, which are stored inline.
+///
+/// The same rules apply as with [`Patch`], i.e. it **must be deterministic**,
+/// as the `Writer` may apply it more than once. The 2 inputs are the `Writer`'s
+/// local mutable data and the [`Reader`]'s latest head data.
+///
+/// This is implemented for `Patch<T>` and all `FnMut(&mut T, &T)`.
+///
+/// ```rust
+/// # use someday::*;
+/// /// Operations on a counter.
+/// enum Op {
+///     Add(usize),
+///     Sub(usize),
+/// }
+///
+/// impl Apply<usize> for Op {
+///     fn apply(&mut self, writer: &mut usize, _: &usize) {
+///         match self {
+///             Self::Add(n) => *writer += *n,
+///             Self::Sub(n) => *writer -= *n,
+///         }
+///     }
+/// }
+///
+/// let (r, mut w) = someday::new_static::<usize, Op>(0);
+///
+/// w.add(Op::Add(10));
+/// w.add(Op::Sub(3));
+/// w.commit();
+/// w.push();
+///
+/// assert_eq!(*w.data(), 7);
+/// assert_eq!(r.head().data, 7);
+/// ```
+pub trait Apply<T> {
+    /// Apply `self` onto the `Writer`'s data.
+    ///
+    /// Errors from [`Apply::try_apply`] are ignored.
+    fn apply(&mut self, writer: &mut T, reader: &T);
+
+    /// Apply `self` onto the `Writer`'s data,
+    /// returning an error if it failed.
+    ///
+    /// This is used by [`Writer::try_commit`].
+    ///
+    /// The default implementation calls [`Apply::apply`] and never fails.
+    ///
+    /// # Errors
+    /// This should return an error if `self` could not be
+    /// applied, it must return the same error if applied twice.
+    fn try_apply(&mut self, writer: &mut T, reader: &T) -> Result<(), BoxError> {
+        self.apply(writer, reader);
+        Ok(())
+    }
+
+    /// If [`Apply::try_apply`] can fail.
+    ///
+    /// This is `false` by default.
+    fn is_try(&self) -> bool {
+        false
+    }
+}
+
+impl<T, F> Apply<T> for F
+where
+    F: FnMut(&mut T, &T),
+{
+    #[inline]
+    fn apply(&mut self, writer: &mut T, reader: &T) {
+        self(writer, reader);
+    }
+}
@@ -1,10 +1,13 @@
 //! Snapshots of data with timestamps.
 
 //---------------------------------------------------------------------------------------------------- Use
+use alloc::sync::Arc;
+#[cfg(feature = "sha2")]
+use alloc::vec::Vec;
+
+use crate::{header::CommitHeader, lineage::CommitId, Reader, Timestamp};
 #[allow(unused_imports)] // docs
-use crate::Writer;
-use crate::{Reader, Timestamp};
-use std::sync::Arc;
+use crate::{Digest, Lineage, Writer};
 
 //---------------------------------------------------------------------------------------------------- Commit
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -13,7 +16,7 @@ use std::sync::Arc;
     feature = "borsh",
     derive(borsh::BorshSerialize, borsh::BorshDeserialize)
 )]
-#[derive(Copy, Clone, Debug, Hash, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Clone, Debug, Hash, PartialEq, PartialOrd, Eq, Ord)]
 /// Owned snapshot of some data `T` and its [`Timestamp`]
 ///
 /// This is a [`Commit`] of data received from the operations
@@ -36,7 +39,7 @@ use std::sync::Arc;
 /// // The String's destructor will run here.
 /// drop(owned);
 /// ```
-pub struct Commit<T: Clone> {
+pub struct Commit<T> {
     /// Timestamp of this [`Commit`].
     ///
     /// Starts at 0, and increments by 1 every time a `commit`-like
@@ -45,28 +48,37 @@ pub struct Commit<T: Clone> {
 
     /// The generic data `T`.
     pub data: T,
+
+    /// Optional information about this [`Commit`], see [`CommitHeader`].
+    ///
+    /// This is empty (and not serialized with `serde`) by default.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "CommitHeader::is_empty")
+    )]
+    pub header: CommitHeader,
 }
 
 //---------------------------------------------------------------------------------------------------- Commit Impl
-impl<T: Clone> Commit<T> {
+impl<T> Commit<T> {
     #[inline]
     /// If there is a difference in `self` and `other`'s timestamps or data.
     ///
     /// ```rust
     /// # use someday::*;
     /// // Timestamp is different.
-    /// let commit_1 = Commit { timestamp: 0, data: "a" };
-    /// let commit_2 = Commit { timestamp: 1, data: "a" };
+    /// let commit_1 = Commit { timestamp: 0, data: "a", header: CommitHeader::new() };
+    /// let commit_2 = Commit { timestamp: 1, data: "a", header: CommitHeader::new() };
     /// assert!(commit_1.diff(&commit_2));
     ///
     /// // Data is different.
-    /// let commit_3 = Commit { timestamp: 0, data: "a" };
-    /// let commit_4 = Commit { timestamp: 0, data: "b" };
+    /// let commit_3 = Commit { timestamp: 0, data: "a", header: CommitHeader::new() };
+    /// let commit_4 = Commit { timestamp: 0, data: "b", header: CommitHeader::new() };
     /// assert!(commit_3.diff(&commit_4));
     ///
     /// // Same.
-    /// let commit_5 = Commit { timestamp: 0, data: "a" };
-    /// let commit_6 = Commit { timestamp: 0, data: "a" };
+    /// let commit_5 = Commit { timestamp: 0, data: "a", header: CommitHeader::new() };
+    /// let commit_6 = Commit { timestamp: 0, data: "a", header: CommitHeader::new() };
     /// assert!(!commit_5.diff(&commit_6));
     /// ```
     pub fn diff(&self, other: &Self) -> bool
@@ -82,13 +94,13 @@ impl<T: Clone> Commit<T> {
     /// ```rust
     /// # use someday::*;
     /// // Timestamp is different, data is same.
-    /// let commit_1 = Commit { timestamp: 0, data: "" };
-    /// let commit_2 = Commit { timestamp: 1, data: "" };
+    /// let commit_1 = Commit { timestamp: 0, data: "", header: CommitHeader::new() };
+    /// let commit_2 = Commit { timestamp: 1, data: "", header: CommitHeader::new() };
     /// assert!(commit_1.diff_timestamp(&commit_2));
     ///
     /// // Timestamp is same, data is different.
-    /// let commit_3 = Commit { timestamp: 0, data: "" };
-    /// let commit_4 = Commit { timestamp: 0, data: "a" };
+    /// let commit_3 = Commit { timestamp: 0, data: "", header: CommitHeader::new() };
+    /// let commit_4 = Commit { timestamp: 0, data: "a", header: CommitHeader::new() };
     /// assert!(!commit_3.diff_timestamp(&commit_4));
     /// ```
     pub const fn diff_timestamp(&self, other: &Self) -> bool {
@@ -101,13 +113,13 @@ impl<T: Clone> Commit<T> {
     /// ```rust
     /// # use someday::*;
     /// // Timestamp is different, data is same.
-    /// let commit_1 = Commit { timestamp: 0, data: "a" };
-    /// let commit_2 = Commit { timestamp: 1, data: "a" };
+    /// let commit_1 = Commit { timestamp: 0, data: "a", header: CommitHeader::new() };
+    /// let commit_2 = Commit { timestamp: 1, data: "a", header: CommitHeader::new() };
     /// assert!(!commit_1.diff_data(&commit_2));
     ///
     /// // Timestamp is same, data is different.
-    /// let commit_3 = Commit { timestamp: 0, data: "a" };
-    /// let commit_4 = Commit { timestamp: 0, data: "b" };
+    /// let commit_3 = Commit { timestamp: 0, data: "a", header: CommitHeader::new() };
+    /// let commit_4 = Commit { timestamp: 0, data: "b", header: CommitHeader::new() };
     /// assert!(commit_3.diff_data(&commit_4));
     /// ```
     pub fn diff_data(&self, other: &Self) -> bool
@@ -120,43 +132,217 @@ impl<T: Clone> Commit<T> {
     #[inline]
     /// If `self`'s timestamp is ahead of `other`'s timestamp.
     ///
+    /// This is always `false` if they are not [`Commit::comparable`].
+    ///
     /// ```rust
     /// # use someday::*;
-    /// let commit_1 = Commit { timestamp: 0, data: "" };
-    /// let commit_2 = Commit { timestamp: 1, data: "" };
+    /// let commit_1 = Commit { timestamp: 0, data: "", header: CommitHeader::new() };
+    /// let commit_2 = Commit { timestamp: 1, data: "", header: CommitHeader::new() };
     /// assert!(!commit_1.ahead(&commit_2));
     ///
-    /// let commit_3 = Commit { timestamp: 2, data: "" };
-    /// let commit_4 = Commit { timestamp: 1, data: "" };
+    /// let commit_3 = Commit { timestamp: 2, data: "", header: CommitHeader::new() };
+    /// let commit_4 = Commit { timestamp: 1, data: "", header: CommitHeader::new() };
     /// assert!(commit_3.ahead(&commit_4));
     ///
-    /// let commit_5 = Commit { timestamp: 2, data: "" };
-    /// let commit_6 = Commit { timestamp: 2, data: "" };
+    /// let commit_5 = Commit { timestamp: 2, data: "", header: CommitHeader::new() };
+    /// let commit_6 = Commit { timestamp: 2, data: "", header: CommitHeader::new() };
     /// assert!(!commit_5.ahead(&commit_6));
     /// ```
-    pub const fn ahead(&self, other: &Self) -> bool {
-        self.timestamp > other.timestamp
+    pub fn ahead(&self, other: &Self) -> bool {
+        self.cmp_timestamp(other) == Some(core::cmp::Ordering::Greater)
     }
 
     #[inline]
     /// If `self`'s timestamp is behind of `other`'s timestamp.
     ///
+    /// This is always `false` if they are not [`Commit::comparable`].
+    ///
     /// ```rust
     /// # use someday::*;
-    /// let commit_1 = Commit { timestamp: 0, data: "" };
-    /// let commit_2 = Commit { timestamp: 1, data: "" };
+    /// let commit_1 = Commit { timestamp: 0, data: "", header: CommitHeader::new() };
+    /// let commit_2 = Commit { timestamp: 1, data: "", header: CommitHeader::new() };
     /// assert!(commit_1.behind(&commit_2));
     ///
-    /// let commit_3 = Commit { timestamp: 2, data: "" };
-    /// let commit_4 = Commit { timestamp: 1, data: "" };
+    /// let commit_3 = Commit { timestamp: 2, data: "", header: CommitHeader::new() };
+    /// let commit_4 = Commit { timestamp: 1, data: "", header: CommitHeader::new() };
     /// assert!(!commit_3.behind(&commit_4));
     ///
-    /// let commit_5 = Commit { timestamp: 2, data: "" };
-    /// let commit_6 = Commit { timestamp: 2, data: "" };
+    /// let commit_5 = Commit { timestamp: 2, data: "", header: CommitHeader::new() };
+    /// let commit_6 = Commit { timestamp: 2, data: "", header: CommitHeader::new() };
     /// assert!(!commit_5.behind(&commit_6));
     /// ```
-    pub const fn behind(&self, other: &Self) -> bool {
-        self.timestamp < other.timestamp
+    pub fn behind(&self, other: &Self) -> bool {
+        self.cmp_timestamp(other) == Some(core::cmp::Ordering::Less)
+    }
+
+    #[must_use]
+    /// If `self` and `other`'s timestamps can be meaningfully compared.
+    ///
+    /// This returns `true` if:
+    /// - Both have no [`Lineage`] (the default, lineages are not tracked)
+    /// - Both are from the same `Lineage`
+    /// - One is a known ancestor of the other, e.g. the `Commit` a `Lineage` branched off from
+    ///
+    /// If only one has a `Lineage`, they are from unrelated lineages, or they
+    /// diverged (e.g. both lineages made commits after branching), this returns
+    /// `false` and [`Commit::ahead`] and [`Commit::behind`] return `false`.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let commit = |timestamp, lineage| Commit {
+    ///     timestamp,
+    ///     data: (),
+    ///     header: CommitHeader { lineage, ..Default::default() },
+    /// };
+    /// let root = Lineage::new();
+    /// let branch_1 = root.branch(1);
+    /// let branch_2 = root.branch(1);
+    ///
+    /// // Same lineage.
+    /// assert!(commit(1, Some(root)).comparable(&commit(5, Some(root))));
+    /// // `branch_1` is based on `root` at timestamp `1`.
+    /// assert!(commit(1, Some(root)).comparable(&commit(2, Some(branch_1))));
+    /// // Both diverged from timestamp `1`.
+    /// assert!(!commit(2, Some(root)).comparable(&commit(2, Some(branch_1))));
+    /// assert!(!commit(2, Some(branch_1)).comparable(&commit(2, Some(branch_2))));
+    /// // Unrelated, or only one is tracked.
+    /// assert!(!commit(1, Some(root)).comparable(&commit(1, Some(Lineage::new()))));
+    /// assert!(!commit(1, Some(root)).comparable(&commit(1, None)));
+    /// assert!(commit(1, None).comparable(&commit(2, None)));
+    /// ```
+    pub fn comparable(&self, other: &Self) -> bool {
+        match (&self.header.lineage, &other.header.lineage) {
+            (None, None) => true,
+            (Some(a), Some(b)) => crate::lineage::related(a, self.timestamp, b, other.timestamp),
+            _ => false,
+        }
+    }
+
+    #[must_use]
+    /// Compare `self` and `other`'s timestamps, if they are [`Commit::comparable`].
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::cmp::Ordering;
+    /// let commit = |timestamp, lineage| Commit {
+    ///     timestamp,
+    ///     data: (),
+    ///     header: CommitHeader { lineage, ..Default::default() },
+    /// };
+    /// let root = Lineage::new();
+    ///
+    /// let commit_1 = commit(1, Some(root));
+    /// let commit_2 = commit(2, Some(root.branch(1)));
+    /// let commit_3 = commit(2, Some(Lineage::new()));
+    ///
+    /// assert_eq!(commit_1.cmp_timestamp(&commit_2), Some(Ordering::Less));
+    /// assert_eq!(commit_1.cmp_timestamp(&commit_3), None);
+    /// ```
+    pub fn cmp_timestamp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        self.comparable(other)
+            .then(|| self.timestamp.cmp(&other.timestamp))
+    }
+
+    #[must_use]
+    /// Find the common ancestor of `self` and `other`, if known.
+    ///
+    /// This requires both to have a [`Lineage`] and be directly related, i.e.:
+    /// - They are from the same lineage
+    /// - One's lineage branched off the other
+    /// - Both lineages branched off the same lineage
+    ///
+    /// The returned [`CommitId`] is the latest [`Commit`] both `Commit`'s are based on.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let commit = |timestamp, lineage| Commit {
+    ///     timestamp,
+    ///     data: (),
+    ///     header: CommitHeader { lineage: Some(lineage), ..Default::default() },
+    /// };
+    /// let root = Lineage::new();
+    /// let branch = root.branch(5);
+    ///
+    /// // `branch` branched off `root` at timestamp `5`.
+    /// let ancestor = commit(8, root).common_ancestor(&commit(7, branch)).unwrap();
+    /// assert_eq!(ancestor, CommitId { lineage: root.id, timestamp: 5 });
+    ///
+    /// // Unrelated.
+    /// assert_eq!(commit(8, root).common_ancestor(&commit(7, Lineage::new())), None);
+    /// ```
+    pub fn common_ancestor(&self, other: &Self) -> Option<CommitId> {
+        match (&self.header.lineage, &other.header.lineage) {
+            (Some(a), Some(b)) => {
+                crate::lineage::common_ancestor(a, self.timestamp, b, other.timestamp)
+            }
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// The hash that uniquely identifies the [`Commit`] and its history.
+    ///
+    /// This returns the [`Digest::hash`], or `None` if there is no
+    /// [`Digest`] or it is stale (the `Commit` changed after it was computed).
+    ///
+    /// Two `Commit`'s with the same `id()` have the same data and history.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let digest = Digest { timestamp: 1, hash: [1; 32], parent: [0; 32] };
+    /// let header = CommitHeader { digest: Some(digest), ..Default::default() };
+    ///
+    /// let commit = Commit { timestamp: 1, data: (), header: header.clone() };
+    /// assert_eq!(commit.id(), Some([1; 32]));
+    ///
+    /// // Stale.
+    /// let commit = Commit { timestamp: 2, data: (), header };
+    /// assert_eq!(commit.id(), None);
+    /// ```
+    pub const fn id(&self) -> Option<[u8; 32]> {
+        match self.header.digest {
+            Some(digest) if digest.timestamp == self.timestamp => Some(digest.hash),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "sha2")]
+    #[must_use]
+    /// Check if the data matches this [`Commit`]'s [`Digest`].
+    ///
+    /// This re-computes the digest with `encode`, which must
+    /// be the same function given to `Writer::enable_digest`.
+    ///
+    /// This returns `false` if there is no valid [`Commit::id`], or if
+    /// the data or timestamp was modified (e.g. a tampered snapshot).
+    ///
+    /// The `Digest` is (de)serialized along with the `Commit`:
+    /// ```rust
+    /// # use someday::*;
+    /// fn encode(data: &String) -> Vec<u8> {
+    ///     data.as_bytes().to_vec()
+    /// }
+    ///
+    /// let (r, mut w) = someday::new(String::from("hello"));
+    /// w.enable_digest(encode);
+    /// w.add_commit_push(|w, _| w.push_str(" world"));
+    ///
+    /// let json = serde_json::to_string(&*r.head()).unwrap();
+    /// let mut commit: Commit<String> = serde_json::from_str(&json).unwrap();
+    /// assert!(commit.verify(encode));
+    /// assert_eq!(commit.id(), r.head().id());
+    ///
+    /// // Tampered.
+    /// commit.data.push('!');
+    /// assert!(!commit.verify(encode));
+    /// ```
+    pub fn verify(&self, encode: impl FnOnce(&T) -> Vec<u8>) -> bool {
+        let Some(digest) = self.header.digest else {
+            return false;
+        };
+
+        self.id().is_some()
+            && Digest::new(digest.parent, self.timestamp, &encode(&self.data)) == digest
     }
 }
 
@@ -185,9 +371,9 @@ impl<T: Clone> TryFrom<CommitRef<T>> for Commit<T> {
     }
 }
 
-impl<T> std::fmt::Display for Commit<T>
+impl<T> core::fmt::Display for Commit<T>
 where
-    T: Clone + std::fmt::Display,
+    T: Clone + core::fmt::Display,
 {
     /// ```rust
     /// # use someday::*;
@@ -196,8 +382,8 @@ where
     /// let display: String = format!("{}", r.head());
     /// assert_eq!(display, "hello");
     /// ```
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        std::fmt::Display::fmt(&self.data, f)
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(&self.data, f)
     }
 }
 
@@ -213,7 +399,7 @@ where
 pub type CommitRef<T> = Arc<Commit<T>>;
 
 //---------------------------------------------------------------------------------------------------- CommitRef Trait impl
-impl<T: Clone> From<&Reader<T>> for CommitRef<T> {
+impl<T> From<&Reader<T>> for CommitRef<T> {
     #[inline]
     /// Calls [`Reader::head`].
     ///
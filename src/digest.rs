@@ -0,0 +1,76 @@
+//! Content-addressed digests of commits.
+
+//---------------------------------------------------------------------------------------------------- Use
+use crate::Timestamp;
+#[allow(unused_imports)] // docs
+use crate::{Commit, CommitHeader, Reader, Writer};
+
+//---------------------------------------------------------------------------------------------------- Digest
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, PartialOrd, Eq, Ord)]
+/// A digest of a [`Commit`], chained with its parent's digest.
+///
+/// The `hash` is the SHA-256 of:
+/// 1. The parent `Commit`'s `hash` (32 bytes, all `0` for the first `Commit`)
+/// 2. The `Commit`'s [`Timestamp`] (8 bytes, little-endian)
+/// 3. The `Commit`'s data `T`, encoded by the function given to `Writer::enable_digest`
+///
+/// As each `hash` includes the parent's `hash`, a single `hash` identifies
+/// the entire history up to that `Commit`, see [`Commit::id`].
+///
+/// These are computed when a [`Writer`] pushes, so only pushed
+/// `Commit`'s (the ones [`Reader`]'s see) have a valid digest.
+///
+/// The digest is stored in the `Commit`'s [`CommitHeader::digest`], so it is
+/// (de)serialized along with the data, see [`Commit::verify`].
+pub struct Digest {
+    /// The [`Timestamp`] of the `Commit` this digest was computed for.
+    ///
+    /// If this does not match the `Commit`'s timestamp, the
+    /// `Commit` has changed since and the digest is stale.
+    pub timestamp: Timestamp,
+
+    /// The hash of this `Commit`.
+    pub hash: [u8; 32],
+
+    /// The hash of the parent `Commit`.
+    pub parent: [u8; 32],
+}
+
+impl Digest {
+    #[cfg(feature = "sha2")]
+    #[must_use]
+    #[allow(clippy::as_conversions, clippy::little_endian_bytes)] // fixed format
+    /// Compute the [`Digest`] of a [`Commit`] from its encoded data.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let root = Digest::new([0; 32], 0, b"hello");
+    /// let next = Digest::new(root.hash, 1, b"hello");
+    ///
+    /// assert_eq!(next.parent, root.hash);
+    /// assert_ne!(next.hash, root.hash);
+    ///
+    /// // Deterministic.
+    /// assert_eq!(root, Digest::new([0; 32], 0, b"hello"));
+    /// ```
+    pub fn new(parent: [u8; 32], timestamp: Timestamp, data: &[u8]) -> Self {
+        use sha2::Digest as _;
+
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(parent);
+        hasher.update((timestamp as u64).to_le_bytes());
+        hasher.update(data);
+
+        Self {
+            timestamp,
+            hash: hasher.finalize().into(),
+            parent,
+        }
+    }
+}
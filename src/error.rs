@@ -0,0 +1,336 @@
+//! Errors resulting from fallible [`Writer`] operations.
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::boxed::Box;
+
+use crate::Timestamp;
+#[allow(unused_imports)] // docs
+use crate::{Reader, Writer};
+
+//---------------------------------------------------------------------------------------------------- Error
+pub(crate) use core::error::Error;
+
+/// A boxed [`Error`], returned by [`Patch::Try`](crate::Patch::Try)'s and validators.
+pub(crate) type BoxError = Box<dyn Error + Send + Sync + 'static>;
+
+//---------------------------------------------------------------------------------------------------- PoisonError
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// The [`Writer`] is poisoned
+///
+/// This is returned from fallible operations such as [`Writer::try_commit`]
+/// and [`Writer::try_push`] when the `Writer` is [poisoned](Writer::is_poisoned),
+/// i.e. something panicked while the `Writer`'s local data was being modified,
+/// or a [`new_with`](crate::new_with) `Writer` gave up reclaiming the old head.
+///
+/// Use [`Writer::recover`] to make the `Writer` usable again.
+pub struct PoisonError;
+
+impl core::fmt::Display for PoisonError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("the `Writer` is poisoned")
+    }
+}
+
+impl Error for PoisonError {}
+
+//---------------------------------------------------------------------------------------------------- ConflictError
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Another [`Writer`] pushed first
+///
+/// This is returned from [`Writer::try_push`] when a [`Writer::share`]'ed
+/// `Writer` pushed first, and the [`RetryPolicy`](crate::RetryPolicy) ran
+/// out or the committed [`Patch`](crate::Patch)'s could not be re-applied.
+///
+/// The `Writer`'s commits are kept, see [`Writer::fetch`] and [`Writer::pull`].
+pub struct ConflictError;
+
+impl core::fmt::Display for ConflictError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("another `Writer` pushed first")
+    }
+}
+
+impl Error for ConflictError {}
+
+//---------------------------------------------------------------------------------------------------- ValidateError
+#[derive(Debug)]
+/// The [`Writer`]'s validator rejected the data
+///
+/// This is returned from validated operations such as
+/// [`Writer::try_commit`] and [`Writer::try_push`] when
+/// the validator set with [`Writer::set_validator`] fails.
+pub struct ValidateError {
+    /// The [`Timestamp`] of the [`Commit`](crate::Commit) that was rejected.
+    pub timestamp: Timestamp,
+    /// The error returned by the validator.
+    pub error: BoxError,
+}
+
+impl core::fmt::Display for ValidateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "commit {} failed validation: {}",
+            self.timestamp, self.error
+        )
+    }
+}
+
+impl Error for ValidateError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- PatchError
+#[derive(Debug)]
+/// A [`Patch::Try`](crate::Patch::Try) failed
+///
+/// This is returned from [`Writer::try_commit`] when one
+/// of the staged [`Patch`](crate::Patch)'s returns an error.
+pub struct PatchError {
+    /// The index of the failed `Patch` within [`Writer::staged`].
+    pub index: usize,
+    /// The [`Timestamp`] of the [`Commit`](crate::Commit) that was aborted.
+    pub timestamp: Timestamp,
+    /// The error returned by the `Patch`.
+    pub error: BoxError,
+}
+
+impl core::fmt::Display for PatchError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "patch {} of commit {} failed: {}",
+            self.index, self.timestamp, self.error
+        )
+    }
+}
+
+impl Error for PatchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- DivergenceError
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A non-deterministic [`Patch`](crate::Patch) was detected
+///
+/// This is reported when the `Writer`'s local data diverges
+/// from what its [`Reader`]'s would see, with
+/// [`Writer::enable_determinism_check`] enabled.
+pub struct DivergenceError {
+    /// The index of the non-deterministic `Patch` within the commit.
+    ///
+    /// This is `None` if the divergence was detected in
+    /// [`Writer::push`], where the `Patch` is not known.
+    pub index: Option<usize>,
+    /// The [`Timestamp`] of the [`Commit`](crate::Commit) that diverged.
+    pub timestamp: Timestamp,
+}
+
+impl core::fmt::Display for DivergenceError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.index {
+            Some(index) => write!(
+                f,
+                "patch {index} of commit {} is not deterministic",
+                self.timestamp
+            ),
+            None => write!(
+                f,
+                "commit {} diverged from the `Reader` data after reclaiming",
+                self.timestamp
+            ),
+        }
+    }
+}
+
+impl Error for DivergenceError {}
+
+//---------------------------------------------------------------------------------------------------- CommitError
+#[derive(Debug)]
+/// A [`Writer::try_commit`] failed
+///
+/// In the `Patch` and `Validate` cases, the [`Writer`]'s local data was rolled
+/// back and the staged [`Patch`](crate::Patch)'s are still in [`Writer::staged`].
+pub enum CommitError {
+    /// The [`Writer`] is poisoned, nothing was done.
+    Poison(PoisonError),
+    /// A [`Patch::Try`](crate::Patch::Try) returned an error.
+    Patch(PatchError),
+    /// The validator rejected the data.
+    Validate(ValidateError),
+    /// A non-deterministic `Patch` was detected, see [`Writer::enable_determinism_check`].
+    Diverged(DivergenceError),
+}
+
+impl core::fmt::Display for CommitError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Poison(e) => e.fmt(f),
+            Self::Patch(e) => e.fmt(f),
+            Self::Validate(e) => e.fmt(f),
+            Self::Diverged(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for CommitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Poison(e) => Some(e),
+            Self::Patch(e) => Some(e),
+            Self::Validate(e) => Some(e),
+            Self::Diverged(e) => Some(e),
+        }
+    }
+}
+
+impl From<PoisonError> for CommitError {
+    fn from(error: PoisonError) -> Self {
+        Self::Poison(error)
+    }
+}
+
+impl From<PatchError> for CommitError {
+    fn from(error: PatchError) -> Self {
+        Self::Patch(error)
+    }
+}
+
+impl From<ValidateError> for CommitError {
+    fn from(error: ValidateError) -> Self {
+        Self::Validate(error)
+    }
+}
+
+impl From<DivergenceError> for CommitError {
+    fn from(error: DivergenceError) -> Self {
+        Self::Diverged(error)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- PushError
+#[derive(Debug)]
+/// A [`Writer::try_push`] failed
+pub enum PushError {
+    /// The [`Writer`] is poisoned, nothing was done.
+    Poison(PoisonError),
+    /// The validator rejected the data, the [`Writer`] was rolled back.
+    Validate(ValidateError),
+    /// Another [`Writer`] pushed first, the `Writer` was not rolled back.
+    Conflict(ConflictError),
+}
+
+impl core::fmt::Display for PushError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Poison(e) => e.fmt(f),
+            Self::Validate(e) => e.fmt(f),
+            Self::Conflict(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for PushError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Poison(e) => Some(e),
+            Self::Validate(e) => Some(e),
+            Self::Conflict(e) => Some(e),
+        }
+    }
+}
+
+impl From<PoisonError> for PushError {
+    fn from(error: PoisonError) -> Self {
+        Self::Poison(error)
+    }
+}
+
+impl From<ValidateError> for PushError {
+    fn from(error: ValidateError) -> Self {
+        Self::Validate(error)
+    }
+}
+
+impl From<ConflictError> for PushError {
+    fn from(error: ConflictError) -> Self {
+        Self::Conflict(error)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- RebaseError
+#[derive(Debug)]
+/// A [`Writer::rebase_onto`] failed
+///
+/// In all cases, nothing was changed.
+pub enum RebaseError {
+    /// The [`Writer`] is poisoned.
+    Poison(PoisonError),
+    /// The data was replaced since the last push, e.g. by [`Writer::overwrite`]
+    /// or a [`Transaction`](crate::Transaction), which cannot be replayed on top
+    /// of a different base, see [`Writer::pull`] or [`Writer::push`] first.
+    Replaced,
+    /// A [`Patch::Try`](crate::Patch::Try) returned an error while replaying.
+    ///
+    /// The [`PatchError::index`] counts the [`Writer::committed_patches`], then the [`Writer::staged`] ones.
+    Patch(PatchError),
+}
+
+impl core::fmt::Display for RebaseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Poison(e) => e.fmt(f),
+            Self::Replaced => f.write_str("the data was replaced and cannot be replayed"),
+            Self::Patch(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for RebaseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Poison(e) => Some(e),
+            Self::Replaced => None,
+            Self::Patch(e) => Some(e),
+        }
+    }
+}
+
+impl From<PoisonError> for RebaseError {
+    fn from(error: PoisonError) -> Self {
+        Self::Poison(error)
+    }
+}
+
+impl From<PatchError> for RebaseError {
+    fn from(error: PatchError) -> Self {
+        Self::Patch(error)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- MergeError
+#[derive(Debug)]
+/// A [`Writer::merge_from`] failed
+///
+/// In either case, nothing was changed.
+pub enum MergeError<C> {
+    /// Neither [`Writer`] was forked from the other.
+    NoCommonAncestor,
+    /// The data could not be merged, see [`Merge::Conflict`](crate::Merge::Conflict).
+    Conflict(C),
+}
+
+impl<C: core::fmt::Display> core::fmt::Display for MergeError<C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NoCommonAncestor => f.write_str("the `Writer`'s have no common ancestor"),
+            Self::Conflict(c) => write!(f, "merge conflict: {c}"),
+        }
+    }
+}
+
+impl<C: core::fmt::Debug + core::fmt::Display> Error for MergeError<C> {}
@@ -0,0 +1,410 @@
+//! `FailoverGroup<T>` and `FailoverMember<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{Reader, Writer};
+
+//---------------------------------------------------------------------------------------------------- Failover
+#[allow(clippy::large_enum_variant)] // the `Writer` is the point
+#[derive(Debug)]
+/// The result of an election in a [`FailoverGroup`]
+///
+/// See [`FailoverMember::wait`].
+pub enum Failover<T: Clone> {
+    /// This member was elected, and is now the [`Writer`].
+    Leader(Writer<T>),
+    /// Another member was elected, this is its [`FailoverMember::id`].
+    Follower(usize),
+}
+
+//---------------------------------------------------------------------------------------------------- Shared
+/// The state of a `FailoverGroup`, shared with all members.
+struct State<T: Clone> {
+    /// The IDs of all current members.
+    members: BTreeSet<usize>,
+    /// The ID given to the next member.
+    next_id: usize,
+    /// How many elections have happened.
+    elections: usize,
+    /// The member elected in the last election.
+    leader: usize,
+    /// The revived `Writer`, if the leader hasn't taken it yet.
+    ///
+    /// INVARIANT: this must never be dropped while the lock is held,
+    /// as the `on_writer_dropped()` callback locks as well.
+    writer: Option<Writer<T>>,
+    /// If an `on_writer_dropped()` callback is registered.
+    watching: bool,
+}
+
+/// The lock and condvar shared by the group and all members.
+struct Shared<T: Clone> {
+    /// A `Reader` of the `Writer` the group fails over.
+    reader: Reader<T>,
+    /// The group state.
+    state: Mutex<State<T>>,
+    /// Notified when an election happens.
+    condvar: Condvar,
+}
+
+impl<T: Clone + Send + Sync + 'static> Shared<T> {
+    /// Lock `state`, ignoring poison.
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Hold an election the next time the `Writer` dies,
+    /// unless a callback doing so is already registered.
+    ///
+    /// This must not be called with the lock held.
+    fn watch(self: &Arc<Self>) {
+        if std::mem::replace(&mut self.lock().watching, true) {
+            return;
+        }
+
+        // `Weak`, the callback is stored in the token all `Reader`'s hold.
+        let shared = Arc::downgrade(self);
+        self.reader.on_writer_dropped(move || {
+            if let Some(shared) = shared.upgrade() {
+                shared.lock().watching = false;
+                shared.elect();
+            }
+        });
+    }
+
+    /// Revive the `Writer`, hand it to the lowest ID member,
+    /// and watch for the next time it dies.
+    ///
+    /// This must not be called with the lock held.
+    fn elect(self: &Arc<Self>) {
+        let mut state = self.lock();
+
+        // Without members, the next `join()` holds the election.
+        let Some(&leader) = state.members.first() else {
+            return;
+        };
+
+        match self.reader.clone().try_into_writer() {
+            Ok(writer) => {
+                state.writer = Some(writer);
+                state.leader = leader;
+                state.elections += 1;
+                self.condvar.notify_all();
+                drop(state);
+            }
+            // Someone else revived the `Writer`, watch that one instead.
+            Err(reader) if !reader.writer_dropped() => drop(state),
+            // Reviving failed, watching would call the callback right away.
+            Err(_reader) => return,
+        }
+
+        self.watch();
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- FailoverGroup
+/// A group of [`Reader`]'s that elect a new [`Writer`] when it is dropped
+///
+/// [`Reader::try_into_writer`] allows _a_ `Reader` to become the new
+/// `Writer`, but all `Reader`'s must poll and race each other for it.
+///
+/// A `FailoverGroup` instead has [`FailoverMember`]'s that block
+/// in [`FailoverMember::wait`] until the `Writer` is dropped, after
+/// which exactly one of them is elected to be the new `Writer`,
+/// and the rest are notified who it is.
+///
+/// ## Elections
+/// The elected member is always the current member with the lowest
+/// [`FailoverMember::id`], i.e. the oldest member that is still around.
+///
+/// The election is held on the thread that drops the `Writer`, even if no
+/// member is waiting. The elected member receives the revived `Writer` in
+/// its next [`FailoverMember::wait`] (or immediately if it is waiting).
+/// If the group has no members when the `Writer` is dropped, the first
+/// member to [`FailoverGroup::join`] is elected.
+///
+/// If the elected member is dropped before receiving the `Writer`,
+/// another election happens between the remaining members.
+///
+/// If the new `Writer` is dropped again, another election happens.
+///
+/// ```rust
+/// # use someday::*;
+/// let (r, w) = someday::new::<usize>(0);
+///
+/// let group = FailoverGroup::new(r);
+/// let members = [group.join(), group.join(), group.join()];
+///
+/// let threads: Vec<_> = members
+///     .into_iter()
+///     .map(|mut member| std::thread::spawn(move || (member.id(), member.wait())))
+///     .collect();
+///
+/// // The `Writer` "crashes".
+/// drop(w);
+///
+/// // Dropping the new `Writer` would start another election.
+/// let mut writer = None;
+/// for thread in threads {
+///     match thread.join().unwrap() {
+///         // The oldest member becomes the `Writer`.
+///         (id, Failover::Leader(mut w)) => {
+///             assert_eq!(id, 0);
+///             w.add_commit_push(|w, _| *w += 1);
+///             writer = Some(w);
+///         }
+///         // The others are told about it.
+///         (id, Failover::Follower(leader)) => {
+///             assert_ne!(id, 0);
+///             assert_eq!(leader, 0);
+///         }
+///     }
+/// }
+///
+/// assert!(writer.is_some());
+/// assert_eq!(group.reader().head().data, 1);
+/// ```
+pub struct FailoverGroup<T: Clone> {
+    /// State shared with all members.
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Clone> Clone for FailoverGroup<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> FailoverGroup<T> {
+    #[must_use]
+    /// Create a new [`FailoverGroup`] for the [`Writer`] of `reader`
+    ///
+    /// The group starts with no members, see [`FailoverGroup::join`].
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, _w) = someday::new(());
+    /// let group = FailoverGroup::new(r);
+    /// assert_eq!(group.members(), 0);
+    /// ```
+    pub fn new(reader: Reader<T>) -> Self {
+        let state = State {
+            members: BTreeSet::new(),
+            next_id: 0,
+            elections: 0,
+            leader: 0,
+            writer: None,
+            watching: false,
+        };
+
+        let shared = Arc::new(Shared {
+            reader,
+            state: Mutex::new(state),
+            condvar: Condvar::new(),
+        });
+        shared.watch();
+
+        Self { shared }
+    }
+
+    #[must_use]
+    /// Add a new [`FailoverMember`] to the group
+    ///
+    /// Each member gets a new [`FailoverMember::id`], starting from `0`.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, _w) = someday::new(());
+    /// let group = FailoverGroup::new(r);
+    ///
+    /// let a = group.join();
+    /// let b = group.join();
+    /// assert_eq!(a.id(), 0);
+    /// assert_eq!(b.id(), 1);
+    /// assert_eq!(group.members(), 2);
+    ///
+    /// drop(a);
+    /// assert_eq!(group.members(), 1);
+    /// ```
+    pub fn join(&self) -> FailoverMember<T> {
+        let member = {
+            let mut state = self.shared.lock();
+
+            let id = state.next_id;
+            state.next_id += 1;
+            state.members.insert(id);
+
+            FailoverMember {
+                id,
+                elections: state.elections,
+                reader: self.shared.reader.clone(),
+                shared: Arc::clone(&self.shared),
+            }
+        };
+
+        // The `Writer` died while there were no members to elect.
+        if self.shared.reader.writer_dropped() {
+            self.shared.elect();
+        }
+
+        member
+    }
+
+    #[must_use]
+    /// How many [`FailoverMember`]'s are in the group
+    pub fn members(&self) -> usize {
+        self.shared.lock().members.len()
+    }
+
+    #[must_use]
+    /// The [`FailoverMember::id`] elected in the last election
+    ///
+    /// This is `None` if no election has happened yet.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, w) = someday::new(());
+    /// let group = FailoverGroup::new(r);
+    /// let mut member = group.join();
+    /// assert_eq!(group.leader(), None);
+    ///
+    /// drop(w);
+    /// let Failover::Leader(w) = member.wait() else {
+    ///     panic!();
+    /// };
+    /// assert_eq!(group.leader(), Some(0));
+    /// ```
+    pub fn leader(&self) -> Option<usize> {
+        let state = self.shared.lock();
+        (state.elections != 0).then_some(state.leader)
+    }
+
+    #[must_use]
+    /// A [`Reader`] of the group's [`Writer`]
+    pub fn reader(&self) -> &Reader<T> {
+        &self.shared.reader
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- FailoverMember
+/// A member of a [`FailoverGroup`]
+///
+/// Created with [`FailoverGroup::join`], and leaves the group when dropped.
+pub struct FailoverMember<T: Clone + Send + Sync + 'static> {
+    /// Our ID.
+    id: usize,
+    /// The last election we were notified of.
+    elections: usize,
+    /// A `Reader` of the `Writer`.
+    reader: Reader<T>,
+    /// State shared with the group.
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Clone + Send + Sync + 'static> FailoverMember<T> {
+    #[must_use]
+    /// This member's ID, lower IDs are elected first
+    pub const fn id(&self) -> usize {
+        self.id
+    }
+
+    #[must_use]
+    /// A [`Reader`] of the group's [`Writer`]
+    pub const fn reader(&self) -> &Reader<T> {
+        &self.reader
+    }
+
+    /// Block until the next election, and return the result
+    ///
+    /// This waits until the [`Writer`] is dropped and
+    /// one of the [`FailoverMember`]'s is elected.
+    ///
+    /// If this member is elected, this returns [`Failover::Leader`] with the
+    /// new `Writer`, else [`Failover::Follower`] with the elected member's ID.
+    ///
+    /// If elections already happened since this member last called
+    /// `wait()` (or joined), this returns the latest one's result immediately.
+    ///
+    /// See [`FailoverGroup`] for an example with waiting threads.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, w) = someday::new::<usize>(0);
+    /// let group = FailoverGroup::new(r);
+    /// let mut member = group.join();
+    ///
+    /// // Nobody is waiting, the election still happens on drop.
+    /// drop(w);
+    /// assert_eq!(group.leader(), Some(0));
+    /// assert!(!group.reader().writer_dropped());
+    ///
+    /// let Failover::Leader(w) = member.wait() else {
+    ///     panic!();
+    /// };
+    ///
+    /// // Without members to elect, the next one to join is.
+    /// drop(member);
+    /// drop(w);
+    /// assert!(group.reader().writer_dropped());
+    ///
+    /// let mut member = group.join();
+    /// assert_eq!(group.leader(), Some(1));
+    /// assert!(matches!(member.wait(), Failover::Leader(_)));
+    /// ```
+    pub fn wait(&mut self) -> Failover<T> {
+        let mut state = self.shared.lock();
+
+        loop {
+            // An election happened since we last looked.
+            if state.elections != self.elections {
+                self.elections = state.elections;
+                let writer = if state.leader == self.id {
+                    state.writer.take()
+                } else {
+                    None
+                };
+                return writer.map_or(Failover::Follower(state.leader), Failover::Leader);
+            }
+
+            state = self
+                .shared
+                .condvar
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Drop for FailoverMember<T> {
+    #[allow(clippy::option_if_let_else)]
+    fn drop(&mut self) {
+        let writer = {
+            let mut state = self.shared.lock();
+            state.members.remove(&self.id);
+
+            // We were elected but never received the `Writer`,
+            // hand it to the next member, or drop it if there is none.
+            if state.leader == self.id && state.writer.is_some() {
+                if let Some(&leader) = state.members.first() {
+                    state.leader = leader;
+                    state.elections += 1;
+                    self.shared.condvar.notify_all();
+                    None
+                } else {
+                    state.writer.take()
+                }
+            } else {
+                None
+            }
+        };
+
+        // INVARIANT: dropped without the lock held.
+        drop(writer);
+    }
+}
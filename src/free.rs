@@ -1,16 +1,22 @@
 //! Free functions.
 
 //---------------------------------------------------------------------------------------------------- Use
-use crate::{commit::Commit, reader::Reader, writer::Writer};
-use arc_swap::ArcSwapAny;
-use std::sync::{atomic::AtomicBool, Arc};
+use crate::{
+    apply::Apply,
+    commit::Commit,
+    header::CommitHeader,
+    reader::Reader,
+    sync::ArcSwap,
+    writer::{Writer, WriterToken},
+};
+use alloc::sync::Arc;
 
 #[allow(unused_imports)] // docs
 use crate::{CommitRef, Timestamp};
 
 //---------------------------------------------------------------------------------------------------- Free functions
 /// The default `Vec` capacity for the
-/// `Patch`'s when using using `new()`.
+/// `Patch`'s when using using `local::new()`.
 pub(crate) const INIT_VEC_CAP: usize = 16;
 
 //---------------------------------------------------------------------------------------------------- Free functions
@@ -28,7 +34,11 @@ pub(crate) const INIT_VEC_CAP: usize = 16;
 /// assert_eq!(writer.data_remote(), "hello world!");
 /// ```
 pub fn new<T: Clone>(data: T) -> (Reader<T>, Writer<T>) {
-    let writer = new_inner(Commit { data, timestamp: 0 });
+    let writer = new_inner(Commit {
+        data,
+        timestamp: 0,
+        header: CommitHeader::new(),
+    });
     (writer.reader(), writer)
 }
 
@@ -47,10 +57,107 @@ pub fn default<T: Clone + Default>() -> (Reader<T>, Writer<T>) {
     let writer = new_inner(Commit {
         data: T::default(),
         timestamp: 0,
+        header: CommitHeader::new(),
     });
     (writer.reader(), writer)
 }
 
+#[inline]
+#[must_use]
+/// Create a new [`Reader`] & [`Writer`] pair that uses `P` as its `Patch`'s.
+///
+/// This is the same as [`new`], except the [`Writer`]'s
+/// `Patch`'s are statically dispatched `P`'s instead
+/// of [`Patch<T>`](crate::Patch), see [`Apply`].
+///
+/// ## Example
+/// ```rust
+/// // Function pointers need no allocation.
+/// let (reader, mut writer) = someday::new_static::<usize, fn(&mut usize, &usize)>(0);
+///
+/// writer.add(|w, _| *w += 1);
+/// writer.add(|w, _| *w *= 10);
+/// writer.commit();
+/// writer.push();
+///
+/// assert_eq!(*writer.data(), 10);
+/// assert_eq!(reader.head().data, 10);
+/// ```
+pub fn new_static<T: Clone, P: Apply<T>>(data: T) -> (Reader<T>, Writer<T, P>) {
+    let writer = new_inner(Commit {
+        data,
+        timestamp: 0,
+        header: CommitHeader::new(),
+    });
+    (writer.reader(), writer)
+}
+
+#[must_use]
+/// Create a new [`Reader`] & [`Writer`] pair for a `T` that may not be [`Clone`].
+///
+/// `f` is called twice, once for the [`Writer`]'s local data and once for the
+/// [`Reader`]'s, so both values must be equal, like a `clone()` would be.
+///
+/// The `Writer` never clones `T`, when it cannot reclaim the old head
+/// after a push, it waits for all `Reader`'s to drop their [`CommitRef`]'s
+/// to it instead of cloning the new one. This includes the pushes done by
+/// [`DropPolicy::Push`](crate::DropPolicy) when the `Writer` is dropped.
+///
+/// This wait is bounded (about a second), if `Reader`'s still hold onto
+/// the old head after that, the push gives up: the new head is still
+/// published, but the `Writer` keeps the old one and becomes
+/// [poisoned](Writer::is_poisoned) until [`Writer::recover`] reclaims it,
+/// so `Reader`'s should not hold onto old heads for a long time.
+///
+/// Functions that need to copy `T` (e.g. [`Writer::fork`]
+/// and [`Writer::push_clone`]) still require `T: Clone`
+/// and are not available.
+///
+/// ## Example
+/// ```rust
+/// // A type that cannot be cloned.
+/// #[derive(Debug, PartialEq)]
+/// struct Counter(usize);
+///
+/// let (reader, mut writer) = someday::new_with(|| Counter(0));
+///
+/// writer.add_commit(|w, _| w.0 += 1);
+/// let push_info = writer.push();
+///
+/// // The old head was reclaimed instead of cloning the new one.
+/// assert!(push_info.reclaimed);
+/// assert_eq!(*writer.data(), Counter(1));
+/// assert_eq!(reader.head().data, Counter(1));
+///
+/// // A `Reader` holding the old head makes the
+/// // `Writer` wait until it is dropped.
+/// let head = reader.head();
+/// let thread = std::thread::spawn(move || {
+///     std::thread::sleep(std::time::Duration::from_millis(10));
+///     drop(head);
+/// });
+///
+/// writer.add_commit(|w, _| w.0 += 1);
+/// assert!(writer.push().reclaimed);
+/// assert_eq!(reader.head().data, Counter(2));
+/// # thread.join().unwrap();
+/// ```
+pub fn new_with<T, F>(mut f: F) -> (Reader<T>, Writer<T>)
+where
+    F: FnMut() -> T,
+{
+    let commit = |data| Commit {
+        data,
+        timestamp: 0,
+        header: CommitHeader::new(),
+    };
+
+    let local = commit(f());
+    let remote = commit(f());
+    let writer = from_parts(local, remote, None);
+    (writer.reader(), writer)
+}
+
 #[inline]
 #[must_use]
 /// Create a new [`Reader`] & [`Writer`] pair from a [`Commit`].
@@ -72,6 +179,7 @@ pub fn default<T: Clone + Default>() -> (Reader<T>, Writer<T>) {
 /// let commit = Commit {
 ///     data: String::from("hello world!"),
 ///     timestamp: 123,
+///     header: CommitHeader::new(),
 /// };
 /// let (reader, mut writer) = someday::from_commit(commit);
 ///
@@ -92,16 +200,19 @@ pub fn from_commit<T: Clone>(commit: Commit<T>) -> (Reader<T>, Writer<T>) {
 }
 
 /// Inner function for constructors.
-pub(crate) fn new_inner<T: Clone>(local: Commit<T>) -> Writer<T> {
-    let remote = Arc::new(local.clone());
-    let arc = Arc::new(ArcSwapAny::new(Arc::clone(&remote)));
+pub(crate) fn new_inner<T: Clone, P: Apply<T>>(local: Commit<T>) -> Writer<T, P> {
+    let remote = local.clone();
+    from_parts(local, remote, Some(T::clone))
+}
+
+/// Create a [`Writer`] from 2 equal `Commit`'s, `remote` is pushed to the `Reader`'s.
+fn from_parts<T, P: Apply<T>>(
+    local: Commit<T>,
+    remote: Commit<T>,
+    clone_fn: Option<fn(&T) -> T>,
+) -> Writer<T, P> {
+    let remote = Arc::new(remote);
+    let arc = Arc::new(ArcSwap::new(Arc::clone(&remote)));
 
-    Writer {
-        token: Arc::new(AtomicBool::new(false)).into(),
-        local: Some(local),
-        remote,
-        arc,
-        patches: Vec::with_capacity(INIT_VEC_CAP),
-        patches_old: Vec::with_capacity(INIT_VEC_CAP),
-    }
+    Writer::from_parts(WriterToken::new(), local, remote, arc, clone_fn)
 }
@@ -0,0 +1,466 @@
+//! `WriterGroup<W>` and `GroupReader<H>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::{sync::Arc, vec, vec::Vec};
+
+use crate::{
+    commit::CommitRef,
+    error::ConflictError,
+    info::{GroupPushInfo, PushInfo},
+    sync::ArcSwap,
+    Writer,
+};
+
+#[allow(unused_imports)] // docs
+use crate::{Commit, Reader};
+
+//---------------------------------------------------------------------------------------------------- Generation
+/// The generation of a [`WriterGroup`]
+///
+/// This starts at `0` and increments by `1` each time
+/// [`WriterGroup::push`] makes new [`Commit`]'s visible.
+pub type Generation = usize;
+
+/// The shared root [`GroupReader`]'s enter through.
+#[derive(Debug)]
+struct Root<H> {
+    /// The generation of `heads`.
+    generation: Generation,
+    /// The head `Commit` of each `Writer`.
+    heads: H,
+}
+
+//---------------------------------------------------------------------------------------------------- Writers
+/// Sealing of [`Writers`].
+mod sealed {
+    /// Prevents [`super::Writers`] from being implemented outside of `someday`.
+    pub trait Sealed {}
+}
+
+/// A tuple of [`Writer`]'s that can be used in a [`WriterGroup`]
+///
+/// This is implemented for tuples of 1 to 8 `Writer`'s.
+///
+/// This trait is sealed and cannot be implemented outside of `someday`.
+pub trait Writers: sealed::Sealed {
+    /// A tuple of the head [`CommitRef`] of each [`Writer`], in the same order.
+    type Heads: Clone;
+
+    #[doc(hidden)]
+    /// A tuple of the old heads returned by `push_publish()`.
+    type Old;
+
+    #[doc(hidden)]
+    /// The current remote heads of all `Writer`'s.
+    fn remote_heads(&self) -> Self::Heads;
+
+    #[doc(hidden)]
+    /// If `heads` are the same as the current remote heads.
+    fn remote_eq(&self, heads: &Self::Heads) -> bool;
+
+    #[doc(hidden)]
+    /// `Writer::push_check()` for all `Writer`'s, then
+    /// `Writer::push_publish()` for all `Writer`'s.
+    ///
+    /// Nothing is published if any check fails, and
+    /// a lost push race does not stop the other `Writer`'s.
+    fn publish(&mut self) -> Self::Old;
+
+    #[doc(hidden)]
+    /// `Writer::push_reclaim()` and the push hooks for all `Writer`'s.
+    ///
+    /// A `Writer` that lost a push race in `publish()`
+    /// returns a [`PushInfo::conflict`].
+    fn reclaim(&mut self, old: Self::Old) -> Vec<PushInfo>;
+}
+
+/// Implement `Writers` for a tuple.
+macro_rules! impl_writers {
+    ($($t:ident => $i:tt),*) => {
+        impl<$($t: Clone),*> sealed::Sealed for ($(Writer<$t>,)*) {}
+
+        impl<$($t: Clone),*> Writers for ($(Writer<$t>,)*) {
+            type Heads = ($(CommitRef<$t>,)*);
+            type Old = ($(Result<Option<CommitRef<$t>>, ConflictError>,)*);
+
+            fn remote_heads(&self) -> Self::Heads {
+                ($(Arc::clone(&self.$i.remote),)*)
+            }
+
+            fn remote_eq(&self, heads: &Self::Heads) -> bool {
+                true $(&& Arc::ptr_eq(&self.$i.remote, &heads.$i))*
+            }
+
+            fn publish(&mut self) -> Self::Old {
+                $(self.$i.push_check();)*
+                ($(self.$i.push_publish(),)*)
+            }
+
+            fn reclaim(&mut self, old: Self::Old) -> Vec<PushInfo> {
+                vec![$({
+                    let writer = &mut self.$i;
+                    let (push_info, old) = match old.$i {
+                        Ok(Some(old)) => writer.push_reclaim(old, None, writer.clone_fn),
+                        // Nothing to push, or lost a `share()` push race.
+                        result => {
+                            let conflict = result.is_err();
+                            (
+                                PushInfo {
+                                    timestamp: writer.timestamp_remote(),
+                                    commits: 0,
+                                    reclaimed: false,
+                                    compacted: false,
+                                    conflict,
+                                },
+                                None,
+                            )
+                        }
+                    };
+                    writer.check_reclaimed(&push_info);
+                    writer.call_push_hooks(&push_info, old.as_ref());
+                    push_info
+                }),*]
+            }
+        }
+    };
+}
+
+impl_writers!(A => 0);
+impl_writers!(A => 0, B => 1);
+impl_writers!(A => 0, B => 1, C => 2);
+impl_writers!(A => 0, B => 1, C => 2, D => 3);
+impl_writers!(A => 0, B => 1, C => 2, D => 3, E => 4);
+impl_writers!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5);
+impl_writers!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6);
+impl_writers!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7);
+
+//---------------------------------------------------------------------------------------------------- WriterGroup
+/// A group of [`Writer`]'s that [`push()`](WriterGroup::push) together
+///
+/// Each `Writer` has its own [`Reader`]'s, and each [`Writer::push`]
+/// is atomic on its own, but there is no ordering between separate
+/// `Writer`'s, i.e. a `Reader` of one `Writer` may see new data,
+/// while a `Reader` of another `Writer` still sees the old data.
+///
+/// A `WriterGroup` holds a tuple of `Writer`'s and a shared root that
+/// contains the head [`Commit`] of each `Writer`. [`WriterGroup::push`]
+/// pushes all `Writer`'s and then swaps the root once, so that
+/// [`GroupReader::head`] always returns the heads of the same
+/// [`Generation`], never a mix of new and old heads.
+///
+/// The `Writer`'s themselves can be accessed with [`WriterGroup::writers`]
+/// to [`add()`](Writer::add) and [`commit()`](Writer::commit) as normal.
+///
+/// ```rust
+/// # use someday::*;
+/// # use std::collections::*;
+/// let (_, config) = someday::new::<HashMap<String, usize>>(HashMap::new());
+/// let (_, routes) = someday::new::<Vec<String>>(vec![]);
+///
+/// let mut group = WriterGroup::new((config, routes));
+/// let reader = group.reader();
+///
+/// // Make changes to both `Writer`'s.
+/// let (config, routes) = group.writers();
+/// config.add_commit(|w, _| { w.insert("port".into(), 8080); });
+/// routes.add_commit(|w, _| w.push("/api".into()));
+///
+/// // Nothing is visible until the group is pushed.
+/// let (config, routes) = reader.head();
+/// assert!(config.data.is_empty());
+/// assert!(routes.data.is_empty());
+/// assert_eq!(reader.generation(), 0);
+///
+/// // Push both atomically.
+/// let push_info = group.push();
+/// assert_eq!(push_info.generation, 1);
+/// assert_eq!(push_info.pushes[0].commits, 1);
+/// assert_eq!(push_info.pushes[1].commits, 1);
+///
+/// // `GroupReader`'s see both changes.
+/// let (config, routes) = reader.head();
+/// assert_eq!(config.data["port"], 8080);
+/// assert_eq!(routes.data, ["/api"]);
+/// assert_eq!(reader.generation(), 1);
+/// ```
+pub struct WriterGroup<W: Writers> {
+    /// The `Writer`'s.
+    writers: W,
+    /// The root `GroupReader`'s enter through.
+    root: Arc<ArcSwap<Root<W::Heads>>>,
+}
+
+impl<W: Writers> WriterGroup<W> {
+    #[must_use]
+    /// Create a new [`WriterGroup`] from a tuple of [`Writer`]'s
+    ///
+    /// The initial [`Generation`] is `0`, and contains
+    /// the current [`Writer::head_remote`] of each `Writer`.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, a) = someday::new(0);
+    /// let (_, b) = someday::new(String::new());
+    ///
+    /// let group = WriterGroup::new((a, b));
+    /// assert_eq!(group.generation(), 0);
+    /// ```
+    pub fn new(writers: W) -> Self {
+        let root = Root {
+            generation: 0,
+            heads: writers.remote_heads(),
+        };
+
+        Self {
+            writers,
+            root: Arc::new(ArcSwap::from_pointee(root)),
+        }
+    }
+
+    #[must_use]
+    /// Create a new [`GroupReader`] of this [`WriterGroup`]
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, a) = someday::new(0);
+    /// let (_, b) = someday::new(String::new());
+    ///
+    /// let group = WriterGroup::new((a, b));
+    /// let reader = group.reader();
+    /// assert_eq!(reader.head().0.data, 0);
+    /// ```
+    pub fn reader(&self) -> GroupReader<W::Heads> {
+        GroupReader {
+            root: Arc::clone(&self.root),
+        }
+    }
+
+    /// Mutable access to the [`Writer`]'s
+    ///
+    /// `Commit`'s pushed with [`Writer::push`] directly will
+    /// not be visible to [`GroupReader`]'s until the next
+    /// [`WriterGroup::push`].
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, a) = someday::new(0);
+    /// let (_, b) = someday::new(String::new());
+    ///
+    /// let mut group = WriterGroup::new((a, b));
+    /// group.writers().0.add_commit(|w, _| *w += 1);
+    /// assert_eq!(*group.writers().0.data(), 1);
+    /// ```
+    pub fn writers(&mut self) -> &mut W {
+        &mut self.writers
+    }
+
+    #[must_use]
+    /// The current [`Generation`] visible to [`GroupReader`]'s
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, a) = someday::new(0);
+    ///
+    /// let mut group = WriterGroup::new((a,));
+    /// group.writers().0.add_commit(|w, _| *w += 1);
+    /// group.push();
+    /// assert_eq!(group.generation(), 1);
+    /// ```
+    pub fn generation(&self) -> Generation {
+        self.root.load().generation
+    }
+
+    /// Push all [`Writer`]'s, and make them visible to [`GroupReader`]'s at once
+    ///
+    /// This is done in 2 phases:
+    /// 1. Each `Writer` with new [`Commit`]'s makes them visible to its own [`Reader`]'s
+    /// 2. The [`GroupReader`] root is swapped to the new heads
+    ///
+    /// and then each `Writer` attempts to reclaim old data like [`Writer::push`].
+    ///
+    /// The [`Generation`] is only incremented if a head changed.
+    ///
+    /// The [`Writer::on_push`] hooks are called as normal.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, a) = someday::new(0);
+    /// let (_, b) = someday::new(0);
+    ///
+    /// let mut group = WriterGroup::new((a, b));
+    /// let reader = group.reader();
+    ///
+    /// // Nothing to push.
+    /// let push_info = group.push();
+    /// assert_eq!(push_info.generation, 0);
+    ///
+    /// // Only 1 `Writer` has changes.
+    /// group.writers().1.add_commit(|w, _| *w += 1);
+    /// let push_info = group.push();
+    /// assert_eq!(push_info.generation, 1);
+    /// assert_eq!(push_info.pushes[0].commits, 0);
+    /// assert_eq!(push_info.pushes[1].commits, 1);
+    /// assert_eq!(reader.head().1.data, 1);
+    /// ```
+    ///
+    /// ## Panics
+    /// This panics if any of the `Writer`'s are [poisoned](Writer::is_poisoned),
+    /// before anything is pushed.
+    ///
+    /// If a [`Writer::share`]'ed `Writer` pushed first, that `Writer`'s
+    /// [`PushInfo::conflict`] is `true`, the other `Writer`'s are still pushed.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::panic::*;
+    /// let (r, a) = someday::new(0);
+    /// let (_, b) = someday::new(0);
+    /// let mut group = WriterGroup::new((a, b));
+    ///
+    /// let (a, b) = group.writers();
+    /// a.add_commit(|w, _| *w += 1);
+    /// let _ = catch_unwind(AssertUnwindSafe(|| b.add_commit(|_, _| panic!())));
+    /// assert!(b.is_poisoned());
+    ///
+    /// // `b` is poisoned, so `a` is not pushed either.
+    /// assert!(catch_unwind(AssertUnwindSafe(|| group.push())).is_err());
+    /// assert_eq!(r.head().data, 0);
+    /// assert!(group.writers().0.ahead());
+    /// ```
+    pub fn push(&mut self) -> GroupPushInfo {
+        let old = self.writers.publish();
+
+        // Only create a new generation if something changed,
+        // this also covers `Writer::push()` called directly.
+        let root = self.root.load_full();
+        let generation = if self.writers.remote_eq(&root.heads) {
+            root.generation
+        } else {
+            let generation = root.generation + 1;
+            self.root.store(Arc::new(Root {
+                generation,
+                heads: self.writers.remote_heads(),
+            }));
+            generation
+        };
+
+        // Let go of the old root so its heads can be reclaimed.
+        drop(root);
+
+        GroupPushInfo {
+            generation,
+            pushes: self.writers.reclaim(old),
+        }
+    }
+
+    #[must_use]
+    /// Consume the [`WriterGroup`] and return the [`Writer`]'s
+    ///
+    /// Existing [`GroupReader`]'s will keep
+    /// seeing the last pushed [`Generation`].
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, a) = someday::new(0);
+    /// let (_, b) = someday::new(0);
+    ///
+    /// let group = WriterGroup::new((a, b));
+    /// let (a, b) = group.into_inner();
+    /// ```
+    pub fn into_inner(self) -> W {
+        self.writers
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- GroupReader
+/// Reader(s) of a [`WriterGroup`]
+///
+/// [`GroupReader::head`] returns the head [`Commit`] of each [`Writer`]
+/// in a [`WriterGroup`], all from the same [`Generation`].
+///
+/// Like [`Reader`]'s, `GroupReader`'s can cheaply [`Clone`] themselves.
+///
+/// See [`WriterGroup`] for an example.
+pub struct GroupReader<H> {
+    /// The root shared with the `WriterGroup`.
+    root: Arc<ArcSwap<Root<H>>>,
+}
+
+impl<H> Clone for GroupReader<H> {
+    fn clone(&self) -> Self {
+        Self {
+            root: Arc::clone(&self.root),
+        }
+    }
+}
+
+impl<H: core::fmt::Debug> core::fmt::Debug for GroupReader<H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GroupReader")
+            .field("root", &self.root)
+            .finish()
+    }
+}
+
+impl<H: Clone> GroupReader<H> {
+    #[must_use]
+    /// Acquire the head [`Commit`] of each [`Writer`] in the [`WriterGroup`]
+    ///
+    /// The returned tuple of [`CommitRef`]'s are all
+    /// from the same [`Generation`] of [`WriterGroup::push`].
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, a) = someday::new(0);
+    /// let (_, b) = someday::new(0);
+    ///
+    /// let mut group = WriterGroup::new((a, b));
+    /// let reader = group.reader();
+    ///
+    /// let (a, b) = group.writers();
+    /// a.add_commit(|w, _| *w += 1);
+    /// b.add_commit(|w, _| *w += 1);
+    /// group.push();
+    ///
+    /// let (a, b) = reader.head();
+    /// assert_eq!(a.data, b.data);
+    /// ```
+    pub fn head(&self) -> H {
+        self.root.load().heads.clone()
+    }
+
+    #[must_use]
+    /// Same as [`GroupReader::head`], but also returns the [`Generation`] of the heads
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, a) = someday::new(0);
+    ///
+    /// let mut group = WriterGroup::new((a,));
+    /// let reader = group.reader();
+    /// group.writers().0.add_commit(|w, _| *w += 1);
+    /// group.push();
+    ///
+    /// let (generation, (a,)) = reader.head_generation();
+    /// assert_eq!(generation, 1);
+    /// assert_eq!(a.data, 1);
+    /// ```
+    pub fn head_generation(&self) -> (Generation, H) {
+        let root = self.root.load();
+        (root.generation, root.heads.clone())
+    }
+
+    #[must_use]
+    /// The current [`Generation`] visible to this [`GroupReader`]
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, a) = someday::new(0);
+    ///
+    /// let group = WriterGroup::new((a,));
+    /// assert_eq!(group.reader().generation(), 0);
+    /// ```
+    pub fn generation(&self) -> Generation {
+        self.root.load().generation
+    }
+}
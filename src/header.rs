@@ -0,0 +1,104 @@
+//! Headers of commits.
+
+//---------------------------------------------------------------------------------------------------- Use
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+use crate::{digest::Digest, lineage::Lineage, metadata::Metadata};
+#[allow(unused_imports)] // docs
+use crate::{Commit, Reader, Writer};
+
+//---------------------------------------------------------------------------------------------------- CommitHeader
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[derive(Clone, Debug, Default, Hash, PartialEq, PartialOrd, Eq, Ord)]
+/// Optional information about a [`Commit`], stored in [`Commit::header`]
+///
+/// The header is part of the `Commit`, so it is shared with
+/// [`Reader`]'s and (de)serialized along with the data.
+///
+/// ```rust
+/// # use someday::*;
+/// let (r, mut w) = someday::new::<usize>(0);
+/// let lineage = w.track_lineage();
+/// w.add_commit_push(|w, _| *w += 1);
+///
+/// let head = r.head();
+/// assert_eq!(head.header.lineage, Some(lineage));
+/// assert_eq!(head.header, w.head().header);
+/// ```
+pub struct CommitHeader {
+    /// The [`Lineage`] the [`Commit`] belongs to.
+    ///
+    /// This is `None` unless lineages are
+    /// being tracked, see [`Writer::track_lineage`].
+    pub lineage: Option<Lineage>,
+
+    /// The [`Digest`] of the [`Commit`].
+    ///
+    /// This is `None` unless digests are
+    /// enabled, see `Writer::enable_digest`.
+    pub digest: Option<Digest>,
+
+    /// The [`Metadata`] of the [`Commit`].
+    ///
+    /// This is `None` unless the `Commit` was
+    /// created with [`Writer::commit_with`].
+    pub meta: Option<Metadata>,
+}
+
+//---------------------------------------------------------------------------------------------------- CommitHeader Impl
+impl CommitHeader {
+    #[must_use]
+    /// Create an empty [`CommitHeader`].
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let header = CommitHeader::new();
+    /// assert_eq!(header.lineage, None);
+    /// assert_eq!(header.digest, None);
+    /// assert_eq!(header.meta, None);
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            lineage: None,
+            digest: None,
+            meta: None,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    /// If nothing is set, i.e. it need not be serialized.
+    pub(crate) const fn is_empty(&self) -> bool {
+        self.lineage.is_none() && self.digest.is_none() && self.meta.is_none()
+    }
+
+    #[cfg(feature = "std")]
+    #[must_use]
+    /// How long ago the [`Commit`] was created, according to its [`Metadata`]
+    ///
+    /// This returns `None` if there is no `Metadata`, or if
+    /// the system clock went backwards since its creation.
+    ///
+    /// This can be used to reject stale data:
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::time::*;
+    /// let (r, mut w) = someday::new::<usize>(0);
+    /// assert_eq!(r.head().header.age(), None);
+    ///
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.commit_with(Metadata::new());
+    /// w.push();
+    ///
+    /// let fresh = |h: &CommitHeader| h.age().is_some_and(|age| age < Duration::from_secs(30));
+    /// assert!(fresh(&r.head().header));
+    /// ```
+    pub fn age(&self) -> Option<Duration> {
+        self.meta.as_ref().and_then(Metadata::age)
+    }
+}
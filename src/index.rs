@@ -0,0 +1,202 @@
+//! Operation-driven data and incrementally maintained secondary indexes.
+
+//---------------------------------------------------------------------------------------------------- Use
+#[allow(unused_imports)] // docs
+use crate::{Commit, Patch, Reader, Writer};
+
+//---------------------------------------------------------------------------------------------------- Operate
+/// Data that is mutated by discrete operations instead of closures.
+///
+/// Instead of [`Patch`]'s being arbitrary functions, an [`Operate`]
+/// type describes its mutations as values (e.g an `enum` of `Insert`/`Remove`).
+///
+/// These operations can be added to a [`Writer`] with [`Writer::add_op`].
+///
+/// The main benefit of using operations is that things derived from
+/// your data (see [`Index`]) can be updated incrementally by inspecting
+/// each operation, instead of being recomputed from scratch.
+///
+/// # ⚠️ Non-deterministic operations
+/// Operations are applied to your data the same way [`Patch`]'s are,
+/// so [`Operate::operate`] **must be deterministic**, see [`Patch`] for more details.
+pub trait Operate {
+    /// The operation that mutates this data.
+    type Op;
+
+    /// Apply the operation `op` to `self`.
+    fn operate(&mut self, op: &Self::Op);
+}
+
+//---------------------------------------------------------------------------------------------------- Index
+/// A secondary index of some [`Operate`] data `T`.
+///
+/// An [`Index`] is updated with every operation applied to `T`,
+/// _before_ the operation is applied, so that it can inspect
+/// the previous state of the data (e.g. to remove stale entries).
+///
+/// Multiple indexes can be registered at once by using a tuple, e.g. `(IndexA, IndexB)`.
+///
+/// See [`Indexed`] for usage.
+pub trait Index<T: Operate> {
+    /// Update the index with the operation `op`.
+    ///
+    /// `data` is the state of the data _before_ `op` is applied to it.
+    fn update(&mut self, data: &T, op: &T::Op);
+}
+
+impl<T: Operate> Index<T> for () {
+    #[inline]
+    fn update(&mut self, _: &T, _: &T::Op) {}
+}
+
+/// Implement [`Index`] on tuples of indexes.
+macro_rules! impl_index_tuple {
+    ($($index:ident => $field:tt),*) => {
+        impl<T, $($index),*> Index<T> for ($($index,)*)
+        where
+            T: Operate,
+            $($index: Index<T>,)*
+        {
+            #[inline]
+            fn update(&mut self, data: &T, op: &T::Op) {
+                $(self.$field.update(data, op);)*
+            }
+        }
+    };
+}
+impl_index_tuple!(A => 0);
+impl_index_tuple!(A => 0, B => 1);
+impl_index_tuple!(A => 0, B => 1, C => 2);
+impl_index_tuple!(A => 0, B => 1, C => 2, D => 3);
+impl_index_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4);
+impl_index_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5);
+impl_index_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6);
+impl_index_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7);
+
+//---------------------------------------------------------------------------------------------------- Indexed
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, PartialOrd, Eq, Ord)]
+/// Some [`Operate`] data `T` along with its [`Index`]'s `I`.
+///
+/// [`Indexed`] is itself [`Operate`], every operation applied to it will:
+/// 1. Update the indexes `I` with [`Index::update`]
+/// 2. Apply the operation onto the data `T` with [`Operate::operate`]
+///
+/// As the data and indexes live in the same `T` a [`Writer`] holds, they:
+/// - Are reclaimed and re-applied with the same operations after [`Writer::push`]
+///   (meaning the indexes are _never_ recomputed from scratch)
+/// - Are published in the same [`Commit`], so [`Reader`]'s
+///   always see the indexes and data in a consistent state
+///
+/// ## Example
+/// ```rust
+/// # use someday::*;
+/// use std::collections::{BTreeMap, HashMap};
+///
+/// // Our data, `id -> name`.
+/// #[derive(Clone, Default)]
+/// struct Users(HashMap<u32, String>);
+///
+/// // The operations on our data.
+/// enum Op {
+///     Insert(u32, String),
+///     Remove(u32),
+/// }
+///
+/// impl Operate for Users {
+///     type Op = Op;
+///     fn operate(&mut self, op: &Op) {
+///         match op {
+///             Op::Insert(id, name) => drop(self.0.insert(*id, name.clone())),
+///             Op::Remove(id) => drop(self.0.remove(id)),
+///         }
+///     }
+/// }
+///
+/// // A secondary index, `name -> id`.
+/// #[derive(Clone, Default)]
+/// struct ByName(BTreeMap<String, u32>);
+///
+/// impl Index<Users> for ByName {
+///     fn update(&mut self, users: &Users, op: &Op) {
+///         // `users` is the data _before_ `op` is applied,
+///         // so we can remove the previous entry if needed.
+///         let id = match op {
+///             Op::Insert(id, _) | Op::Remove(id) => id,
+///         };
+///         if let Some(old_name) = users.0.get(id) {
+///             self.0.remove(old_name);
+///         }
+///         if let Op::Insert(id, name) = op {
+///             self.0.insert(name.clone(), *id);
+///         }
+///     }
+/// }
+///
+/// let (r, mut w) = someday::new(Indexed::<Users, ByName>::default());
+///
+/// w.add_op(Op::Insert(0, "alice".into()));
+/// w.add_op(Op::Insert(1, "bob".into()));
+/// w.commit();
+/// w.push();
+///
+/// w.add_op(Op::Insert(1, "carol".into()));
+/// w.add_op(Op::Remove(0));
+/// w.commit();
+/// w.push();
+///
+/// // The `Reader`'s see the data and
+/// // the index from the same `Commit`.
+/// let head = r.head();
+/// assert_eq!(head.data.data.0.len(), 1);
+/// assert_eq!(head.data.data.0[&1], "carol");
+/// assert_eq!(head.data.index.0.len(), 1);
+/// assert_eq!(head.data.index.0["carol"], 1);
+///
+/// // The `Writer` re-applied the same operations
+/// // onto the reclaimed data, including the index.
+/// assert_eq!(w.data().index.0.len(), 1);
+/// assert_eq!(w.data().index.0["carol"], 1);
+/// ```
+pub struct Indexed<T, I> {
+    /// The data.
+    pub data: T,
+    /// The indexes of `data`.
+    pub index: I,
+}
+
+impl<T, I> Indexed<T, I> {
+    #[inline]
+    /// Create a new [`Indexed`] from data and its indexes.
+    ///
+    /// `index` must already be consistent with `data`,
+    /// as only future operations will update it.
+    pub const fn new(data: T, index: I) -> Self {
+        Self { data, index }
+    }
+
+    #[inline]
+    /// Return the inner data and indexes.
+    pub fn into_inner(self) -> (T, I) {
+        (self.data, self.index)
+    }
+}
+
+impl<T, I> Operate for Indexed<T, I>
+where
+    T: Operate,
+    I: Index<T>,
+{
+    type Op = T::Op;
+
+    #[inline]
+    fn operate(&mut self, op: &Self::Op) {
+        self.index.update(&self.data, op);
+        self.data.operate(op);
+    }
+}
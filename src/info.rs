@@ -4,14 +4,16 @@
 //! information about [`Writer`] operations.
 
 //---------------------------------------------------------------------------------------------------- Use
+use alloc::vec::Vec;
+
 use crate::{
     commit::{Commit, CommitRef},
     patch::Patch,
-    Timestamp,
+    Generation, Timestamp,
 };
 #[allow(unused_imports)] // docs
-use crate::{Reader, Writer};
-use std::num::NonZeroUsize;
+use crate::{CompactPolicy, GroupReader, PushError, Reader, RetryPolicy, Writer, WriterGroup};
+use core::num::NonZeroUsize;
 
 //---------------------------------------------------------------------------------------------------- Info
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -65,6 +67,40 @@ pub struct PushInfo {
     /// - The `Writer` expensively cloned the data directly OR
     /// - `push()` didn't have any changes to push (up-to-date with readers)
     pub reclaimed: bool,
+    /// Did the [`Writer`] clone the pushed data into the
+    /// reclaimed data instead of re-applying the `Patch`'s?
+    ///
+    /// This is only `true` if [`PushInfo::reclaimed`] is, see [`CompactPolicy`].
+    pub compacted: bool,
+    /// Did a [`Writer::share`]'ed `Writer` push first, such that nothing was pushed?
+    ///
+    /// This happens if the committed `Patch`'s could not be re-applied
+    /// on top of the other `Writer`'s push, or the [`RetryPolicy`] ran out.
+    /// The commits are kept and the `Writer` is still [ahead](Writer::ahead).
+    ///
+    /// [`Writer::try_push`] returns [`PushError::Conflict`] instead.
+    pub conflict: bool,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Metadata about a [`WriterGroup::push()`]
+///
+/// This is a container for holding the metadata
+/// [`WriterGroup`] push operations produce.
+pub struct GroupPushInfo {
+    /// The [`Generation`] visible to [`GroupReader`]'s after the push
+    ///
+    /// This will be the same as before if `push()` didn't
+    /// actually do anything (all `Writer`'s up-to-date).
+    pub generation: Generation,
+    /// The [`PushInfo`] of each [`Writer`], in the same order as the group.
+    pub pushes: Vec<PushInfo>,
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -73,26 +109,25 @@ pub struct PushInfo {
     feature = "borsh",
     derive(borsh::BorshSerialize, borsh::BorshDeserialize)
 )]
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// Metadata about a [`Writer::pull()`]
 ///
 /// This is a container for holding the metadata
 /// [`Writer`] pull operations produce.
 ///
 /// It is returned from pull-like functions.
-pub struct PullInfo<T: Clone> {
+pub struct PullInfo<T> {
     /// How many [`Commit`]'s did the [`Writer`] go backwards?
     ///
     /// For example, if the [`Writer`]'s [`Timestamp`] is `5`
     /// and they [`Writer::pull()`]'ed when the [`Reader`]'s
     /// [`Timestamp`] was `3`, this field would hold `2`.
-    pub commits_reverted: std::num::NonZeroUsize,
+    pub commits_reverted: core::num::NonZeroUsize,
     /// The owned [`Commit`] the [`Writer`] had before
     /// replacing it with the [`Reader`]'s data.
     pub old_writer_commit: Commit<T>,
 }
 
-#[derive(Copy, Clone)]
 /// A variety of status info about the [`Writer`] and [`Reader`]
 ///
 /// This is a bag of various metadata about the current
@@ -102,11 +137,11 @@ pub struct PullInfo<T: Clone> {
 ///
 /// If you only need 1 or a few of these fields, consider
 /// using their individual methods instead.
-pub struct StatusInfo<'a, T: Clone> {
+pub struct StatusInfo<'a, T, P = Patch<T>> {
     /// [`Writer::staged`]
-    pub staged_patches: &'a Vec<Patch<T>>,
+    pub staged_patches: &'a Vec<P>,
     /// [`Writer::committed_patches`]
-    pub committed_patches: &'a Vec<Patch<T>>,
+    pub committed_patches: &'a Vec<P>,
     /// [`Writer::head`]
     pub head: &'a Commit<T>,
     /// [`Writer::head_remote`]
@@ -121,8 +156,16 @@ pub struct StatusInfo<'a, T: Clone> {
     pub timestamp_remote: Timestamp,
 }
 
+impl<T, P> Clone for StatusInfo<'_, T, P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, P> Copy for StatusInfo<'_, T, P> {}
+
 /// The inner structures of a `Writer`, returned by [`Writer::into_inner`].
-pub struct WriterInfo<T: Clone> {
+pub struct WriterInfo<T, P = Patch<T>> {
     /// The `Writer`'s local data.
     ///
     /// [`Writer::head`].
@@ -136,10 +179,29 @@ pub struct WriterInfo<T: Clone> {
     /// The "staged" `Patch`'s that haven't been [`commit()`](Writer::commit)'ed.
     ///
     /// [`Writer::staged`].
-    pub staged: Vec<Patch<T>>,
+    pub staged: Vec<P>,
 
     /// The committed `Patch`'s that haven't been [`push()`](Writer::push)'ed.
     ///
     /// [`Writer::committed_patches`].
-    pub committed_patches: Vec<Patch<T>>,
+    pub committed_patches: Vec<P>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Metadata about a [`Writer::rebase_onto()`]
+///
+/// This is a container for holding the metadata
+/// [`Writer`] rebase operations produce.
+pub struct RebaseInfo {
+    /// How many [`Patch`]'s (committed and staged) were replayed onto the new base.
+    pub replayed: usize,
+    /// How many [`Commit`]'s is the [`Writer`] now ahead of
+    /// compared to the [`Reader`]'s latest head [`Commit`]?
+    pub timestamp_diff: usize,
 }
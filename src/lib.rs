@@ -1,6 +1,7 @@
 #![doc = include_str!("../README.md")]
 //---------------------------------------------------------------------------------------------------- Docs
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![no_std]
 //---------------------------------------------------------------------------------------------------- Lints
 #![forbid(
     future_incompatible,
@@ -78,21 +79,36 @@
     clippy::let_underscore_untyped,
     clippy::items_after_statements,
     clippy::single_call_fn,
-    clippy::if_then_some_else_none
+    clippy::if_then_some_else_none,
+    clippy::allow_attributes,
+    clippy::allow_attributes_without_reason
 )]
 
 //---------------------------------------------------------------------------------------------------- Mod
+// Only `alloc` is required, see `sync.rs`.
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(not(any(feature = "std", feature = "spin")))]
+compile_error!("`someday` requires either the `std` or `spin` feature");
+
 mod commit;
 pub use commit::{Commit, CommitRef};
 
 pub mod info;
 pub use info::*;
 
+pub mod error;
+pub use error::*;
+
 mod reader;
 pub use reader::Reader;
 
 mod writer;
-pub use writer::Writer;
+pub use writer::{CompactPolicy, DropPolicy, RetryPolicy, Writer, WriterState};
+#[cfg(feature = "stats")]
+pub use writer::{Histogram, WriterStats};
 
 mod transaction;
 pub use transaction::Transaction;
@@ -100,8 +116,44 @@ pub use transaction::Transaction;
 mod patch;
 pub use patch::Patch;
 
+mod apply;
+pub use apply::Apply;
+
 mod timestamp;
 pub use timestamp::Timestamp;
 
 mod free;
-pub use free::{default, from_commit, new};
+pub use free::{default, from_commit, new, new_static, new_with};
+
+mod index;
+pub use index::{Index, Indexed, Operate};
+
+mod merge;
+pub use merge::Merge;
+
+mod header;
+pub use header::CommitHeader;
+
+mod lineage;
+pub use lineage::{CommitId, Lineage, LineageId};
+
+mod digest;
+pub use digest::Digest;
+
+mod metadata;
+pub use metadata::Metadata;
+
+#[cfg(feature = "std")]
+mod failover;
+#[cfg(feature = "std")]
+pub use failover::{Failover, FailoverGroup, FailoverMember};
+
+mod group;
+pub use group::{Generation, GroupReader, WriterGroup, Writers};
+
+pub mod local;
+
+mod sync;
+
+#[cfg(feature = "testing")]
+pub mod testing;
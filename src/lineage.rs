@@ -0,0 +1,208 @@
+//! Lineages of commits across forked writers.
+
+//---------------------------------------------------------------------------------------------------- Use
+use crate::Timestamp;
+#[allow(unused_imports)] // docs
+use crate::{Commit, Reader, Writer};
+
+//---------------------------------------------------------------------------------------------------- LineageId
+/// A unique ID of a [`Lineage`].
+///
+/// These are randomly generated, and are not ordered in any way.
+pub type LineageId = u64;
+
+//---------------------------------------------------------------------------------------------------- CommitId
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, PartialOrd, Eq, Ord)]
+/// A reference to a specific [`Commit`] within a [`Lineage`].
+pub struct CommitId {
+    /// The [`Lineage::id`] the `Commit` belongs to.
+    pub lineage: LineageId,
+    /// The [`Timestamp`] of the `Commit`.
+    pub timestamp: Timestamp,
+}
+
+//---------------------------------------------------------------------------------------------------- Lineage
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, PartialOrd, Eq, Ord)]
+/// The history a [`Commit`] belongs to.
+///
+/// [`Timestamp`]'s only make sense within a single history, i.e.
+/// after [`Writer::fork`], [`Reader::try_into_writer`] or re-creating a
+/// [`Writer`] from a (deserialized) `Commit`, the new and old `Writer`'s
+/// will create unrelated `Commit`'s with the same `Timestamp`'s.
+///
+/// A [`Lineage`] identifies which `Writer` history a `Commit` is from, and
+/// where that history branched off from, so that `Commit`'s can be checked
+/// with [`Commit::comparable`] and [`Commit::common_ancestor`].
+///
+/// Lineages are opt-in, see [`Writer::track_lineage`]. Once a `Writer`
+/// is tracking its lineage, all `Writer`'s created from it will as well,
+/// each with a new [`Lineage::id`].
+pub struct Lineage {
+    /// The unique ID of this lineage.
+    pub id: LineageId,
+
+    /// The [`Lineage::id`] of the root lineage that all
+    /// related lineages eventually branched off from.
+    ///
+    /// This is equal to `id` for root lineages.
+    pub origin: LineageId,
+
+    /// The `Commit` this lineage branched off from.
+    ///
+    /// This is `None` for root lineages.
+    pub parent: Option<CommitId>,
+}
+
+impl Lineage {
+    #[must_use]
+    /// Create a new root [`Lineage`] with a random ID.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let lineage = Lineage::new();
+    /// assert_eq!(lineage.id, lineage.origin);
+    /// assert_eq!(lineage.parent, None);
+    /// assert_ne!(lineage.id, Lineage::new().id);
+    /// ```
+    pub fn new() -> Self {
+        let id = new_id();
+        Self {
+            id,
+            origin: id,
+            parent: None,
+        }
+    }
+
+    #[must_use]
+    /// Create a new [`Lineage`] branching off of `self` at `timestamp`.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let root = Lineage::new();
+    /// let branch = root.branch(5);
+    ///
+    /// assert_ne!(branch.id, root.id);
+    /// assert_eq!(branch.origin, root.id);
+    /// assert_eq!(branch.parent, Some(CommitId { lineage: root.id, timestamp: 5 }));
+    /// ```
+    pub fn branch(&self, timestamp: Timestamp) -> Self {
+        Self {
+            id: new_id(),
+            origin: self.origin,
+            parent: Some(CommitId {
+                lineage: self.id,
+                timestamp,
+            }),
+        }
+    }
+}
+
+impl Default for Lineage {
+    /// Same as [`Lineage::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Free functions
+/// Generate a new random [`LineageId`].
+#[cfg(feature = "std")]
+pub(crate) fn new_id() -> LineageId {
+    use core::hash::{BuildHasher as _, Hasher as _};
+
+    // Each `RandomState` has different keys,
+    // so hashing nothing returns a random value.
+    std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish()
+}
+
+/// Generate a new unique [`LineageId`].
+///
+/// Without `std` there is no source of randomness, so this
+/// scrambles a global counter with `SplitMix64` instead.
+///
+/// These are only unique within the current process.
+#[cfg(not(feature = "std"))]
+pub(crate) fn new_id() -> LineageId {
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    /// The amount of [`LineageId`]'s generated so far.
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let mut z = COUNTER
+        .fetch_add(1, Ordering::Relaxed)
+        .wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// The common ancestor of `a` at `a_timestamp` and `b` at `b_timestamp`.
+///
+/// Only direct relations are known, i.e. the same lineage,
+/// one branching off the other, or both branching off the same lineage.
+pub(crate) fn common_ancestor(
+    a: &Lineage,
+    a_timestamp: Timestamp,
+    b: &Lineage,
+    b_timestamp: Timestamp,
+) -> Option<CommitId> {
+    let at = |lineage: LineageId, x: Timestamp, y: Timestamp| {
+        Some(CommitId {
+            lineage,
+            timestamp: x.min(y),
+        })
+    };
+
+    if a.id == b.id {
+        return at(a.id, a_timestamp, b_timestamp);
+    }
+
+    match (a.parent, b.parent) {
+        (Some(a_parent), _) if a_parent.lineage == b.id => {
+            at(b.id, a_parent.timestamp, b_timestamp)
+        }
+        (_, Some(b_parent)) if b_parent.lineage == a.id => {
+            at(a.id, a_timestamp, b_parent.timestamp)
+        }
+        (Some(a_parent), Some(b_parent)) if a_parent.lineage == b_parent.lineage => {
+            at(a_parent.lineage, a_parent.timestamp, b_parent.timestamp)
+        }
+        _ => None,
+    }
+}
+
+/// If `a` at `a_timestamp` and `b` at `b_timestamp` are the same
+/// `Commit`, or one is a known ancestor of the other.
+///
+/// Diverged `Commit`'s (e.g. of sibling lineages) are not related,
+/// even though they may have a [`common_ancestor`].
+pub(crate) fn related(
+    a: &Lineage,
+    a_timestamp: Timestamp,
+    b: &Lineage,
+    b_timestamp: Timestamp,
+) -> bool {
+    // The base `Commit` of a branch is labeled with the branch's
+    // lineage, but is the same `Commit` as its parent.
+    let is = |lineage: &Lineage, timestamp: Timestamp, id: CommitId| {
+        timestamp == id.timestamp
+            && (lineage.id == id.lineage || lineage.parent.is_some_and(|parent| parent == id))
+    };
+
+    common_ancestor(a, a_timestamp, b, b_timestamp)
+        .is_some_and(|id| is(a, a_timestamp, id) || is(b, b_timestamp, id))
+}
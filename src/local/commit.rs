@@ -0,0 +1,16 @@
+//! `CommitRef<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::rc::Rc;
+
+use crate::commit::Commit;
+
+#[allow(unused_imports)] // docs
+use crate::local::Reader;
+
+//---------------------------------------------------------------------------------------------------- CommitRef
+/// Cheaply cloneable snapshot of data with a timestamp
+///
+/// This is the single-threaded [`crate::CommitRef`],
+/// returned by [`Reader::head()`].
+pub type CommitRef<T> = Rc<Commit<T>>;
@@ -0,0 +1,66 @@
+//! Free functions.
+
+//---------------------------------------------------------------------------------------------------- Use
+use crate::{
+    commit::Commit,
+    header::CommitHeader,
+    local::{Reader, Writer},
+};
+
+//---------------------------------------------------------------------------------------------------- Free functions
+#[inline]
+#[must_use]
+/// Create a new single-threaded [`Reader`] & [`Writer`] pair.
+///
+/// This is the single-threaded [`crate::new`].
+///
+/// ```rust
+/// let (reader, mut writer) = someday::local::new::<String>("hello world!".into());
+///
+/// assert_eq!(writer.data(), "hello world!");
+/// assert_eq!(reader.head().data, "hello world!");
+/// ```
+pub fn new<T: Clone>(data: T) -> (Reader<T>, Writer<T>) {
+    from_commit(Commit {
+        data,
+        timestamp: 0,
+        header: CommitHeader::new(),
+    })
+}
+
+#[inline]
+#[must_use]
+/// Create a new single-threaded [`Reader`] & [`Writer`] pair from `T::default()`.
+///
+/// ```rust
+/// let (reader, mut writer) = someday::local::default::<usize>();
+///
+/// assert_eq!(*writer.data(), 0);
+/// assert_eq!(reader.head().data, 0);
+/// ```
+pub fn default<T: Clone + Default>() -> (Reader<T>, Writer<T>) {
+    new(T::default())
+}
+
+#[inline]
+#[must_use]
+/// Create a new single-threaded [`Reader`] & [`Writer`] pair from a [`Commit`].
+///
+/// This is the single-threaded [`crate::from_commit`].
+///
+/// ```rust
+/// # use someday::*;
+/// let commit = Commit {
+///     data: String::from("hello world!"),
+///     timestamp: 123,
+///     header: CommitHeader::new(),
+/// };
+/// let (reader, mut writer) = someday::local::from_commit(commit);
+///
+/// assert_eq!(writer.timestamp(), 123);
+/// assert_eq!(reader.head().timestamp, 123);
+/// ```
+pub fn from_commit<T: Clone>(commit: Commit<T>) -> (Reader<T>, Writer<T>) {
+    let writer = Writer::new_inner(commit);
+    (writer.reader(), writer)
+}
@@ -0,0 +1,45 @@
+//! Single-threaded [`Writer`]/[`Reader`] built on [`Rc`](alloc::rc::Rc) and [`Cell`](core::cell::Cell).
+//!
+//! This is the same as the top-level [`someday`](crate) `Writer`/`Reader`,
+//! with the same `add()`/`commit()`/`push()`/`pull()`/[`Transaction`]
+//! semantics, except nothing here is [`Send`] or [`Sync`].
+//!
+//! In exchange:
+//! - There are no atomic operations
+//! - [`Patch`]'s and the data `T` do not need to be `Send`, e.g. `T` can contain [`Rc`](alloc::rc::Rc) or [`RefCell`](core::cell::RefCell)
+//!
+//! Old data is still reclaimed when no [`Reader`] is holding onto it, and
+//! [`Patch`]'s are still re-applied to it, so they must be deterministic.
+//!
+//! ```rust
+//! use someday::local::{self, Patch};
+//! use std::{cell::RefCell, rc::Rc};
+//!
+//! // `Rc<RefCell<T>>` cannot be used with `someday::new()`.
+//! let (r, mut w) = local::new::<Vec<Rc<RefCell<usize>>>>(vec![]);
+//!
+//! w.add(Patch::Ptr(|w, _| w.push(Rc::new(RefCell::new(0)))));
+//! w.commit();
+//! w.push();
+//!
+//! assert_eq!(r.head().data.len(), 1);
+//! assert_eq!(w.data().len(), 1);
+//! ```
+
+mod commit;
+pub use commit::CommitRef;
+
+mod free;
+pub use free::{default, from_commit, new};
+
+mod patch;
+pub use patch::Patch;
+
+mod reader;
+pub use reader::Reader;
+
+mod transaction;
+pub use transaction::Transaction;
+
+mod writer;
+pub use writer::Writer;
@@ -0,0 +1,171 @@
+//! `Patch<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::{boxed::Box, rc::Rc};
+
+#[allow(unused_imports)] // docs
+use crate::local::{Reader, Writer};
+
+//---------------------------------------------------------------------------------------------------- Patch
+/// Functions to be applied to your data `T`.
+///
+/// This is the single-threaded [`crate::Patch`], the
+/// functions do not need to be [`Send`] or [`Sync`].
+///
+/// The 2 inputs you are given are:
+/// - The [`Writer`]'s local mutable data, `T` (the thing you're modifying)
+/// - The [`Reader`]'s latest head commit
+///
+/// # ⚠️ Non-deterministic `Patch`
+/// Like [`crate::Patch`], the `Writer` may apply your `Patch` twice,
+/// so it **must be deterministic**.
+///
+/// ```rust
+/// # use someday::local::*;
+/// # use std::{cell::*, rc::*};
+/// let (_, mut w) = someday::local::new::<String>("".into());
+///
+/// // A function pointer.
+/// w.add(Patch::Ptr(|w, _| w.push_str("hello")));
+///
+/// // Capturing a non-`Send` value.
+/// let rc = Rc::new(String::from(" world"));
+/// w.add(Patch::rc(move |w: &mut String, _| w.push_str(&rc)));
+///
+/// w.commit();
+/// assert_eq!(w.data(), "hello world");
+/// ```
+pub enum Patch<T: Clone> {
+    /// Dynamically dispatched, potentially capturing, boxed function.
+    Box(Box<dyn FnMut(&mut T, &T) + 'static>),
+
+    /// Dynamically dispatched, potentially capturing, cheaply [`Clone`]-able function.
+    Rc(Rc<dyn Fn(&mut T, &T) + 'static>),
+
+    /// Non-capturing, static function pointer.
+    Ptr(fn(&mut T, &T)),
+}
+
+impl<T: Clone + PartialEq> Patch<T> {
+    /// A [`Patch::Ptr`] that clones the [`Reader`]'s data into
+    /// the [`Writer`], but only if they are not [`PartialEq::eq`].
+    pub const CLONE_IF_DIFF: Self = Self::Ptr(|w, r| {
+        if w != r {
+            *w = r.clone();
+        }
+    });
+}
+
+impl<T: Clone> Patch<T> {
+    /// A [`Patch::Ptr`] that always clones the [`Reader`]'s data into the [`Writer`].
+    pub const CLONE: Self = Self::Ptr(|w, r| *w = r.clone());
+    /// A [`Patch::Ptr`] that does nothing.
+    pub const NOTHING: Self = Self::Ptr(|_, _| {});
+
+    #[inline]
+    /// Short-hand for `Self::Box(Box::new(patch))`.
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// # use std::rc::*;
+    /// let rc = Rc::new(String::new());
+    ///
+    /// let boxed_patch = Patch::<String>::boxed(move |_, _| {
+    ///     let captured_variable = &rc;
+    /// });
+    /// assert!(boxed_patch.is_box());
+    /// ```
+    pub fn boxed<P>(patch: P) -> Self
+    where
+        P: FnMut(&mut T, &T) + 'static,
+    {
+        Self::Box(Box::new(patch))
+    }
+
+    #[inline]
+    /// Short-hand for `Self::Rc(Rc::new(patch))`.
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// # use std::rc::*;
+    /// let rc = Rc::new(String::new());
+    ///
+    /// let rc_patch = Patch::<String>::rc(move |_, _| {
+    ///     let captured_variable = &rc;
+    /// });
+    /// assert!(rc_patch.is_rc());
+    /// ```
+    pub fn rc<P>(patch: P) -> Self
+    where
+        P: Fn(&mut T, &T) + 'static,
+    {
+        Self::Rc(Rc::new(patch))
+    }
+
+    #[inline]
+    /// Apply the [`Patch`] onto the [`Writer`] data.
+    pub(crate) fn apply(&mut self, writer: &mut T, reader: &T) {
+        match self {
+            Self::Box(f) => f(writer, reader),
+            Self::Rc(f) => f(writer, reader),
+            Self::Ptr(f) => f(writer, reader),
+        }
+    }
+
+    #[must_use]
+    /// If `self` is the `Patch::Box` variant.
+    pub const fn is_box(&self) -> bool {
+        matches!(self, Self::Box(_))
+    }
+
+    #[must_use]
+    /// If `self` is the `Patch::Rc` variant.
+    pub const fn is_rc(&self) -> bool {
+        matches!(self, Self::Rc(_))
+    }
+
+    #[must_use]
+    /// If `self` is the `Patch::Ptr` variant.
+    pub const fn is_ptr(&self) -> bool {
+        matches!(self, Self::Ptr(_))
+    }
+}
+
+impl<T: Clone> Default for Patch<T> {
+    /// Returns [`Patch::NOTHING`].
+    fn default() -> Self {
+        Self::NOTHING
+    }
+}
+
+impl<T: Clone> From<Box<dyn FnMut(&mut T, &T) + 'static>> for Patch<T> {
+    fn from(patch: Box<dyn FnMut(&mut T, &T) + 'static>) -> Self {
+        Self::Box(patch)
+    }
+}
+
+impl<T: Clone> From<Rc<dyn Fn(&mut T, &T) + 'static>> for Patch<T> {
+    fn from(patch: Rc<dyn Fn(&mut T, &T) + 'static>) -> Self {
+        Self::Rc(patch)
+    }
+}
+
+impl<T: Clone> From<fn(&mut T, &T)> for Patch<T> {
+    fn from(patch: fn(&mut T, &T)) -> Self {
+        Self::Ptr(patch)
+    }
+}
+
+impl<T: Clone> core::fmt::Debug for Patch<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Box(ptr) => {
+                f.write_fmt(format_args!("Patch::Box({:?})", core::ptr::addr_of!(**ptr)))
+            }
+            Self::Rc(ptr) => {
+                f.write_fmt(format_args!("Patch::Rc({:?})", core::ptr::addr_of!(**ptr)))
+            }
+            Self::Ptr(ptr) => f.write_fmt(format_args!("Patch::Ptr({ptr:?})")),
+        }
+    }
+}
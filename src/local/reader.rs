@@ -0,0 +1,169 @@
+//! `Reader<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::rc::Rc;
+use core::cell::Cell;
+
+use crate::local::{CommitRef, Writer};
+
+//---------------------------------------------------------------------------------------------------- Shared
+/// The state shared between a [`Writer`] and its [`Reader`]'s.
+pub(super) struct Shared<T: Clone> {
+    /// The latest `push()`'ed head.
+    ///
+    /// INVARIANT: this is always `Some`, it is only
+    /// `take()`'n temporarily within `head()` and `swap()`.
+    head: Cell<Option<CommitRef<T>>>,
+    /// Has the associated `Writer` been dropped?
+    pub(super) writer_dropped: Cell<bool>,
+}
+
+impl<T: Clone> Shared<T> {
+    /// Create a new `Self` with `head` as the latest head.
+    pub(super) const fn new(head: CommitRef<T>) -> Self {
+        Self {
+            head: Cell::new(Some(head)),
+            writer_dropped: Cell::new(false),
+        }
+    }
+
+    /// Clone the latest head.
+    pub(super) fn head(&self) -> CommitRef<T> {
+        // INVARIANT: `head` is always `Some`.
+        let head = self.head.take().unwrap();
+        self.head.set(Some(Rc::clone(&head)));
+        head
+    }
+
+    /// Replace the latest head with `new`, returning the old one.
+    pub(super) fn swap(&self, new: CommitRef<T>) -> CommitRef<T> {
+        // INVARIANT: `head` is always `Some`.
+        self.head.replace(Some(new)).unwrap()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Reader
+/// Reader(s) who can read some data `T`
+///
+/// This is the single-threaded [`crate::Reader`], it is
+/// neither [`Send`] nor [`Sync`] and is built on [`Rc`].
+///
+/// `Reader`'s are cheaply [`Clone`]-able, akin to `Rc::clone()`.
+///
+/// ```rust
+/// # use someday::local::*;
+/// let (r, mut w) = someday::local::new::<usize>(0);
+///
+/// w.add_commit_push(|w, _| *w += 1);
+///
+/// let r2 = r.clone();
+/// assert_eq!(r.head().data, 1);
+/// assert_eq!(r2.head().data, 1);
+/// ```
+pub struct Reader<T: Clone> {
+    /// The state shared with the `Writer`.
+    pub(super) shared: Rc<Shared<T>>,
+}
+
+impl<T: Clone> Reader<T> {
+    #[inline]
+    #[must_use]
+    /// Acquire the latest [`CommitRef`] pushed by the [`Writer`].
+    ///
+    /// Holding onto the returned `CommitRef` prevents
+    /// the `Writer` from reclaiming it on the next push.
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (r, mut w) = someday::local::new::<String>("".into());
+    ///
+    /// w.add(Patch::Ptr(|w, _| *w = "hello".into()));
+    /// w.commit();
+    ///
+    /// // `Reader` does not see un-pushed changes.
+    /// assert_eq!(r.head().data, "");
+    ///
+    /// w.push();
+    /// let head: CommitRef<String> = r.head();
+    /// assert_eq!(head.timestamp, 1);
+    /// assert_eq!(head.data, "hello");
+    /// ```
+    pub fn head(&self) -> CommitRef<T> {
+        self.shared.head()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Has the associated [`Writer`] been dropped?
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (r, w) = someday::local::new::<usize>(0);
+    /// assert!(!r.writer_dropped());
+    ///
+    /// drop(w);
+    /// assert!(r.writer_dropped());
+    /// ```
+    pub fn writer_dropped(&self) -> bool {
+        self.shared.writer_dropped.get()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Are both these [`Reader`]'s connected to the same [`Writer`]?
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (r, _) = someday::local::new::<usize>(0);
+    /// let (r2, _) = someday::local::new::<usize>(0);
+    ///
+    /// assert!(r.connected(&r.clone()));
+    /// assert!(!r.connected(&r2));
+    /// ```
+    pub fn connected(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.shared, &other.shared)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Is this [`Reader`] connected to this [`Writer`]?
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (r, w) = someday::local::new::<usize>(0);
+    /// let (_, w2) = someday::local::new::<usize>(0);
+    ///
+    /// assert!(r.connected_writer(&w));
+    /// assert!(!r.connected_writer(&w2));
+    /// ```
+    pub fn connected_writer(&self, writer: &Writer<T>) -> bool {
+        Rc::ptr_eq(&self.shared, &writer.shared)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Trait
+impl<T: Clone> Clone for Reader<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Rc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T: Clone> From<&Writer<T>> for Reader<T> {
+    fn from(value: &Writer<T>) -> Self {
+        value.reader()
+    }
+}
+
+impl<T> core::fmt::Debug for Reader<T>
+where
+    T: Clone + core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Reader")
+            .field("head", &self.head())
+            .field("writer_dropped", &self.writer_dropped())
+            .finish()
+    }
+}
@@ -0,0 +1,245 @@
+//! `Transaction<'_, T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use core::{
+    borrow::{Borrow, BorrowMut},
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    info::CommitInfo,
+    local::{Patch, Writer},
+    transaction::SyncBase,
+    Timestamp,
+};
+
+//---------------------------------------------------------------------------------------------------- Tx
+/// Mutate the data `T` _directly_.
+///
+/// This is the single-threaded [`crate::Transaction`], returned by [`Writer::tx`].
+///
+/// Each mutable borrow of `T` will increment the [`Timestamp`]
+/// by `1`, regardless if the data was changed or not.
+///
+/// After `Transaction` has been [`drop()`]'ed (and `T` was mutably borrowed):
+/// 1. All previous `Patch`'s get cleared
+/// 2. The [`Transaction::sync_patch`] gets added, [`Patch::CLONE`] by default
+///
+/// ```rust
+/// # use someday::local::*;
+/// let (r, mut w) = someday::local::new(String::new());
+///
+/// let mut tx = w.tx();
+/// tx.push_str("hello");
+/// tx.push_str(" world");
+/// assert_eq!(tx.original_timestamp(), 0);
+/// assert_eq!(tx.current_timestamp(), 2);
+/// drop(tx);
+///
+/// assert_eq!(w.committed_patches().len(), 1);
+/// assert_eq!(r.head().data, "");
+///
+/// w.push();
+/// assert_eq!(r.head().data, "hello world");
+/// assert_eq!(r.head().timestamp, 2);
+/// ```
+pub struct Transaction<'writer, T: Clone> {
+    /// The `Writer` being mutated.
+    writer: &'writer mut Writer<T>,
+    /// The `Writer`'s timestamp when this was created.
+    original_timestamp: Timestamp,
+    /// The `Patch` that syncs the reclaimed data.
+    sync_patch: Patch<T>,
+    /// If `sync_patch` is still the default `Patch::CLONE`.
+    clone_sync: bool,
+}
+
+impl<'writer, T: Clone> Transaction<'writer, T> {
+    /// Create a new [`Transaction`] associated with a [`Writer`].
+    ///
+    /// This is the same as [`Writer::tx`].
+    pub fn new(writer: &'writer mut Writer<T>) -> Transaction<'writer, T> {
+        Self {
+            original_timestamp: writer.timestamp(),
+            writer,
+            sync_patch: Patch::CLONE,
+            clone_sync: true,
+        }
+    }
+
+    #[must_use]
+    /// Immutably borrow the [`Writer`]'s data `T`.
+    ///
+    /// This will not increment the [`Timestamp`].
+    pub fn data(&self) -> &T {
+        &self.writer.local_as_ref().data
+    }
+
+    /// Mutably borrow the [`Writer`]'s data `T`.
+    ///
+    /// Each call to this function will increment the [`Timestamp`]
+    /// by `1`, regardless if the data was changed or not.
+    pub fn data_mut(&mut self) -> &mut T {
+        let commit = self.writer.local_as_mut();
+        commit.timestamp += 1;
+
+        &mut commit.data
+    }
+
+    #[must_use]
+    /// Immutably borrow the [`Writer`]'s associated with this [`Transaction`].
+    pub fn writer(&self) -> &Writer<T> {
+        self.writer
+    }
+
+    #[must_use]
+    /// Get the original [`Timestamp`] of when this [`Transaction`] was created.
+    pub const fn original_timestamp(&self) -> Timestamp {
+        self.original_timestamp
+    }
+
+    #[must_use]
+    /// Get the current [`Timestamp`] of the [`Writer`]
+    /// associated with this [`Transaction`].
+    pub const fn current_timestamp(&self) -> Timestamp {
+        self.writer.timestamp()
+    }
+
+    #[must_use]
+    /// Return information about the changes made
+    /// and complete the [`Transaction`].
+    ///
+    /// [`CommitInfo::patches`] in this case will represent
+    /// how many times the `Transaction` was mutably referenced.
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (_, mut w) = someday::local::new(String::new());
+    ///
+    /// let mut tx = w.tx();
+    /// tx.push_str(""); // 1
+    /// tx.push_str(""); // 2
+    /// assert_eq!(tx.commit().patches, 2);
+    /// ```
+    pub fn commit(self) -> CommitInfo {
+        CommitInfo {
+            patches: self
+                .current_timestamp()
+                .saturating_sub(self.original_timestamp),
+            timestamp_diff: self.writer.timestamp_diff(),
+        }
+
+        /* drop code */
+    }
+
+    /// Customize the synchronization function used by [`Transaction`].
+    ///
+    /// This returns the previous `sync_patch`, see [`crate::Transaction::sync_patch`].
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (r, mut w) = someday::local::new(Vec::<usize>::new());
+    ///
+    /// let mut tx = w.tx();
+    /// tx.push(0);
+    /// tx.sync_patch(Patch::Ptr(|w, r| {
+    ///     w.clear();
+    ///     w.extend_from_slice(r);
+    /// }));
+    /// drop(tx);
+    /// w.add_commit(|w, _| w.push(1));
+    ///
+    /// assert!(w.push().reclaimed);
+    /// w.add_commit_push(|w, _| w.push(2));
+    /// assert_eq!(w.data().as_slice(), [0, 1, 2]);
+    /// assert_eq!(r.head().data.as_slice(), [0, 1, 2]);
+    /// ```
+    pub fn sync_patch(&mut self, sync_patch: Patch<T>) -> Patch<T> {
+        self.clone_sync = false;
+        core::mem::replace(&mut self.sync_patch, sync_patch)
+    }
+
+    /// Attempt to abort the `Transaction`.
+    ///
+    /// # Errors
+    /// If a mutable reference to `T` was created with
+    /// [`Transaction::data_mut`], this will return `self` back inside [`Err`].
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (_, mut w) = someday::local::new(String::new());
+    ///
+    /// let mut tx = w.tx();
+    /// assert!(tx.abort().is_ok());
+    ///
+    /// let mut tx = w.tx();
+    /// tx.data_mut();
+    /// assert!(tx.abort().is_err());
+    /// ```
+    pub fn abort(self) -> Result<(), Self> {
+        if self.original_timestamp == self.current_timestamp() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Drop
+impl<T: Clone> Drop for Transaction<'_, T> {
+    fn drop(&mut self) {
+        // If we made changes, force a `clone` commit.
+        if self.original_timestamp != self.current_timestamp() {
+            self.writer.patches_old.clear();
+            let patch = core::mem::take(&mut self.sync_patch);
+            self.writer.patches_old.push(patch);
+            self.writer.clone_sync = self.clone_sync;
+            self.writer.sync_base = (!self.clone_sync).then_some(SyncBase::Pending(T::clone));
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Trait
+impl<T: Clone> Deref for Transaction<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.writer
+    }
+}
+
+impl<T: Clone> DerefMut for Transaction<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.data_mut()
+    }
+}
+
+impl<T: Clone> Borrow<T> for Transaction<'_, T> {
+    #[inline]
+    fn borrow(&self) -> &T {
+        self.writer.data()
+    }
+}
+
+impl<T: Clone> BorrowMut<T> for Transaction<'_, T> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut T {
+        self.data_mut()
+    }
+}
+
+impl<T: Clone> AsRef<T> for Transaction<'_, T> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        self.writer.data()
+    }
+}
+
+impl<T: Clone> AsMut<T> for Transaction<'_, T> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut T {
+        self.data_mut()
+    }
+}
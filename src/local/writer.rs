@@ -0,0 +1,626 @@
+//! `Writer<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::{rc::Rc, vec::Vec};
+use core::num::NonZeroUsize;
+
+use crate::{
+    commit::Commit,
+    free::INIT_VEC_CAP,
+    header::CommitHeader,
+    info::{CommitInfo, PullInfo, PushInfo},
+    local::{reader::Shared, CommitRef, Patch, Reader, Transaction},
+    transaction::SyncBase,
+    Timestamp,
+};
+
+//---------------------------------------------------------------------------------------------------- Writer
+/// The single [`Writer`] of some data `T`
+///
+/// This is the single-threaded [`crate::Writer`], it is
+/// neither [`Send`] nor [`Sync`] and is built on [`Rc`].
+///
+/// The `Writer`:
+/// 1. Stores your [`Patch`]'s with [`add()`](Writer::add)
+/// 2. Actually applies them to `T` by [`commit()`](Writer::commit)'ing
+/// 3. Can [`push()`](Writer::push) so that [`Reader`]'s can see the changes
+///
+/// ```rust
+/// # use someday::local::*;
+/// let (r, mut w) = someday::local::new::<String>("".into());
+///
+/// w.add(Patch::Ptr(|w, _| w.push_str("abc")));
+/// w.add(Patch::Ptr(|w, _| w.push_str("def")));
+/// assert_eq!(w.staged().len(), 2);
+///
+/// let commit_info = w.commit();
+/// assert_eq!(commit_info.patches, 2);
+/// assert_eq!(w.timestamp(), 1);
+/// assert_eq!(w.data(), "abcdef");
+/// assert_eq!(r.head().data, "");
+///
+/// let push_info = w.push();
+/// assert_eq!(push_info.commits, 1);
+/// assert_eq!(r.head().timestamp, 1);
+/// assert_eq!(r.head().data, "abcdef");
+/// ```
+pub struct Writer<T: Clone> {
+    /// The writer's local mutually
+    /// exclusive copy of the data.
+    ///
+    /// This is an `Option` only because there's
+    /// a brief moment in `push()` where we need
+    /// to send off `local`, but we can't yet swap it
+    /// with the old data.
+    ///
+    /// It MUST be `Some` before `push()` is over.
+    pub(super) local: Option<Commit<T>>,
+
+    /// The current data the remote `Reader`'s can see.
+    pub(super) remote: CommitRef<T>,
+
+    /// The state shared with `Reader`'s.
+    pub(super) shared: Rc<Shared<T>>,
+
+    /// `Patch`'s that haven't been `commit()`'ed.
+    pub(super) patches: Vec<Patch<T>>,
+
+    /// `Patch`'s that were already applied, that
+    /// must be re-applied to the old `T`.
+    pub(super) patches_old: Vec<Patch<T>>,
+
+    /// If `patches_old` starts with a known full sync `Patch`
+    /// (`overwrite()` or a default `Transaction`), see `crate::Writer`.
+    pub(super) clone_sync: bool,
+
+    /// If `patches_old` starts with a custom `Transaction::sync_patch`, see `crate::Writer`.
+    pub(super) sync_base: Option<SyncBase<T>>,
+}
+
+//---------------------------------------------------------------------------------------------------- Add, commit, push
+impl<T: Clone> Writer<T> {
+    #[inline]
+    /// Add a [`Patch`] to apply to the data `T`
+    ///
+    /// This does not execute the `Patch` immediately,
+    /// it will only store it for later usage.
+    ///
+    /// [`Commit`]-like operations are when these
+    /// functions are applied to your data, e.g. [`Writer::commit()`].
+    pub fn add(&mut self, patch: Patch<T>) {
+        self.patches.push(patch);
+    }
+
+    /// Apply all the [`Patch`]'s that were [`add()`](Writer::add)'ed
+    ///
+    /// The new [`Commit`] created from this will become
+    /// the `Writer`'s new [`Writer::head()`].
+    ///
+    /// ## Timestamp
+    /// This will increment the [`Writer`]'s local [`Timestamp`] by `1`,
+    /// but only if there were `Patch`'s to actually apply.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn commit(&mut self) -> CommitInfo {
+        let patch_len = self.patches.len();
+
+        // Early return if there was nothing to do.
+        if patch_len == 0 {
+            return CommitInfo {
+                patches: 0,
+                timestamp_diff: self.timestamp_diff(),
+            };
+        }
+
+        self.bump_local();
+
+        // INVARIANT: local must be initialized after push()
+        let local = self.local.as_mut().unwrap();
+        self.patches_old.reserve_exact(patch_len);
+        for mut patch in self.patches.drain(..) {
+            patch.apply(&mut local.data, &self.remote.data);
+            self.patches_old.push(patch);
+        }
+
+        CommitInfo {
+            patches: patch_len,
+            timestamp_diff: self.timestamp_diff(),
+        }
+    }
+
+    /// [`add()`](Writer::add) and [`commit()`](Writer::commit)
+    ///
+    /// This will commit the already staged `Patch`'s, then
+    /// apply `patch` and return its output.
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (_, mut w) = someday::local::new::<Vec<usize>>(vec![]);
+    ///
+    /// let (commit_info, len) = w.add_commit(|w, _| {
+    ///     w.push(0);
+    ///     w.len()
+    /// });
+    /// assert_eq!(commit_info.patches, 1);
+    /// assert_eq!(len, 1);
+    /// assert_eq!(w.timestamp(), 1);
+    /// ```
+    ///
+    /// # Timestamp
+    /// This function will always increment the [`Writer`]'s local [`Timestamp`] by `1`.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn add_commit<P, Output>(&mut self, mut patch: P) -> (CommitInfo, Output)
+    where
+        P: FnMut(&mut T, &T) -> Output + 'static,
+    {
+        // Commit the current patches.
+        let mut commit_info = self.commit();
+
+        // `commit()` won't update the timestamp
+        // if there we no previous patches.
+        if commit_info.patches == 0 {
+            self.bump_local();
+            commit_info.timestamp_diff += 1;
+        }
+        commit_info.patches += 1;
+
+        // INVARIANT: local must be initialized after push()
+        let local = self.local.as_mut().unwrap();
+        let r = patch(&mut local.data, &self.remote.data);
+
+        // Convert patch to immediately drop return value.
+        self.patches_old
+            .push(Patch::boxed(move |w, r| drop(patch(w, r))));
+
+        (commit_info, r)
+    }
+
+    #[inline]
+    /// Conditionally push [`Writer`]'s local _committed_ data to the [`Reader`]'s
+    ///
+    /// This will only push changes if there are new [`Commit`]'s
+    /// (i.e if [`Writer::synced`] returns `false`).
+    ///
+    /// If no [`Reader`] is holding onto the old head, it is reclaimed
+    /// and the committed [`Patch`]'s are re-applied to it, else it is cloned.
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (r, mut w) = someday::local::new::<usize>(0);
+    ///
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.commit();
+    ///
+    /// // Nobody is holding onto the old data.
+    /// let push_info = w.push();
+    /// assert_eq!(push_info.commits, 1);
+    /// assert!(push_info.reclaimed);
+    ///
+    /// // Now somebody is.
+    /// let head = r.head();
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.commit();
+    /// let push_info = w.push();
+    /// assert!(!push_info.reclaimed);
+    ///
+    /// assert_eq!(head.data, 1);
+    /// assert_eq!(r.head().data, 2);
+    /// assert_eq!(*w.data(), 2);
+    /// ```
+    ///
+    /// ## Timestamp
+    /// This will set the [`Writer`]'s local [`Timestamp`] to whatever the remote
+    /// (the `Reader`'s) `Timestamp` is after pushing.
+    pub fn push(&mut self) -> PushInfo {
+        self.push_inner::<false>()
+    }
+
+    #[inline]
+    /// This is the same as [`Writer::push()`] but it will always `clone()`
+    /// the data instead of reclaiming it.
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (_, mut w) = someday::local::new::<usize>(0);
+    ///
+    /// w.add_commit(|w, _| *w += 1);
+    /// let push_info = w.push_clone();
+    /// assert_eq!(push_info.commits, 1);
+    /// assert!(!push_info.reclaimed);
+    /// assert!(w.committed_patches().is_empty());
+    /// ```
+    pub fn push_clone(&mut self) -> PushInfo {
+        self.push_inner::<true>()
+    }
+
+    /// [`add()`](Writer::add), [`commit()`](Writer::commit), and [`push()`](Writer::push)
+    ///
+    /// This is the same as the top-level [`crate::Writer::add_commit_push`],
+    /// the returned `Option<Output>` is `Some` if the
+    /// `Patch` was re-applied to reclaimed data.
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (r, mut w) = someday::local::new::<String>("".into());
+    ///
+    /// let (push_info, len, reclaimed_len) = w.add_commit_push(|w, _| {
+    ///     w.push_str("abc");
+    ///     w.len()
+    /// });
+    /// assert_eq!(push_info.commits, 1);
+    /// assert!(push_info.reclaimed);
+    /// assert_eq!(len, 3);
+    /// assert_eq!(reclaimed_len, Some(3));
+    /// assert_eq!(r.head().data, "abc");
+    /// ```
+    ///
+    /// # Timestamp
+    /// This function will always increment the [`Writer`]'s local [`Timestamp`] by `1`.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn add_commit_push<P, Output>(&mut self, mut patch: P) -> (PushInfo, Output, Option<Output>)
+    where
+        P: FnMut(&mut T, &T) -> Output,
+    {
+        // Commit `Patch` to our local data.
+        self.bump_local();
+        // INVARIANT: local must be initialized after push()
+        let return_1 = patch(&mut self.local.as_mut().unwrap().data, &self.remote.data);
+
+        // Push all commits so far.
+        let clone_sync = self.clone_sync;
+        let push_info = self.push();
+
+        // If the `Writer` reclaimed data, we must re-apply
+        // since we did not push the Patch onto `patches_old`,
+        // unless a "sync" `Patch` already synced the reclaimed data.
+        let return_2 = (push_info.reclaimed && !clone_sync)
+            .then(|| patch(&mut self.local.as_mut().unwrap().data, &self.remote.data));
+
+        (push_info, return_1, return_2)
+    }
+
+    /// Push, then reclaim or clone the old data.
+    fn push_inner<const CLONE: bool>(&mut self) -> PushInfo {
+        // Early return if no commits.
+        if self.synced() {
+            return PushInfo {
+                timestamp: self.timestamp(),
+                commits: 0,
+                reclaimed: false,
+                compacted: false,
+                conflict: false,
+            };
+        }
+
+        // INVARIANT: we're temporarily "taking" our `self.local`.
+        // It will be uninitialized for the time being.
+        // We need to initialize it before returning.
+        let new = Rc::new(self.local.take().unwrap());
+        self.remote = Rc::clone(&new);
+        let old = self.shared.swap(new);
+        let timestamp_diff = self.remote.timestamp - old.timestamp;
+
+        // Try to reclaim data, unless the user wants to deep-clone no matter what.
+        let attempt = if CLONE { Err(old) } else { Rc::try_unwrap(old) };
+        let (mut local, reclaimed) = match attempt {
+            Ok(old) => (old, true),
+            Err(_) => ((*self.remote).clone(), false),
+        };
+
+        if reclaimed {
+            // Re-apply patches to this old data.
+            //
+            // A `Patch::CLONE` already turns it into the pushed
+            // data, so the rest must not be re-applied after it.
+            let len = if self.clone_sync {
+                1
+            } else {
+                self.patches_old.len()
+            };
+            // A custom `Transaction::sync_patch` gets the data as of the `Transaction`.
+            let sync_base = match self.sync_base.take() {
+                Some(SyncBase::Data(base)) => Some(base),
+                Some(SyncBase::Pending(_)) | None => None,
+            };
+            for (index, mut patch) in self.patches_old.drain(..).take(len).enumerate() {
+                let r = match (index, &sync_base) {
+                    (0, Some(base)) => base,
+                    _ => &self.remote.data,
+                };
+                patch.apply(&mut local.data, r);
+            }
+            local.timestamp = self.remote.timestamp;
+        } else {
+            self.patches_old.clear();
+        }
+
+        // Re-initialize `self.local`.
+        self.local = Some(local);
+        self.clone_sync = false;
+        self.sync_base = None;
+
+        PushInfo {
+            timestamp: self.remote.timestamp,
+            commits: timestamp_diff,
+            reclaimed,
+            compacted: false,
+            conflict: false,
+        }
+    }
+
+    /// Increment the local [`Timestamp`] for a new [`Commit`].
+    fn bump_local(&mut self) {
+        // Keep the data as of a custom `Transaction::sync_patch`
+        // before something is committed on top of it.
+        if let Some(SyncBase::Pending(clone_fn)) = self.sync_base {
+            self.sync_base = Some(SyncBase::Data(clone_fn(&self.local_as_ref().data)));
+        }
+
+        let local = self.local_as_mut();
+        local.timestamp += 1;
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Pull, overwrite
+impl<T: Clone> Writer<T> {
+    #[allow(clippy::missing_panics_doc)]
+    /// Conditionally overwrite the [`Writer`]'s local [`Commit`] with the current [`Reader`] `Commit`
+    ///
+    /// If the `Writer` and `Reader` are [`Writer::synced()`], this will return `None`.
+    ///
+    /// Else, the committed `Patch`'s are discarded, the staged ones are kept.
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (r, mut w) = someday::local::new::<String>("".into());
+    ///
+    /// w.add_commit(|w, _| w.push_str("hello"));
+    /// let pull_info = w.pull().unwrap();
+    /// assert_eq!(pull_info.old_writer_commit.data, "hello");
+    ///
+    /// assert_eq!(w.data(), "");
+    /// assert!(w.pull().is_none());
+    /// ```
+    pub fn pull(&mut self) -> Option<PullInfo<T>> {
+        // Early return if we're synced.
+        if self.synced() {
+            return None;
+        }
+
+        // INVARIANT: if we're not synced, that
+        // means `timestamp_diff` is non-zero.
+        let commits_reverted = NonZeroUsize::new(self.timestamp_diff()).unwrap();
+
+        // INVARIANT: `local` must be initialized after push()
+        let old_writer_commit = self.local.take().unwrap();
+        self.local = Some((*self.remote).clone());
+
+        self.patches_old.clear();
+        self.clone_sync = false;
+        self.sync_base = None;
+
+        Some(PullInfo {
+            commits_reverted,
+            old_writer_commit,
+        })
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Overwrite the [`Writer`]'s local data with `data`.
+    ///
+    /// The `Writer`'s old local data is returned.
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (r, mut w) = someday::local::new::<String>("".into());
+    ///
+    /// let old = w.overwrite(String::from("hello"));
+    /// assert_eq!(old.data, "");
+    /// assert_eq!(w.timestamp(), 1);
+    ///
+    /// w.push();
+    /// assert_eq!(r.head().data, "hello");
+    /// ```
+    ///
+    /// ## Timestamp
+    /// This increments the `Writer`'s local `Timestamp` by `1`.
+    pub fn overwrite(&mut self, data: T) -> Commit<T> {
+        // Delete old functions, we won't need
+        // them anymore since we just overwrote
+        // our data anyway.
+        self.patches_old.clear();
+        self.clone_sync = true;
+        self.sync_base = None;
+
+        // INVARIANT: `local` must be initialized after push()
+        let timestamp = self.timestamp() + 1;
+        let old_data = self.local.take().unwrap();
+
+        self.local = Some(Commit {
+            timestamp,
+            data,
+            header: CommitHeader::new(),
+        });
+
+        // By the time this is applied in `push()`, `r` is the new data.
+        self.patches_old.push(Patch::CLONE);
+
+        old_data
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Get
+impl<T: Clone> Writer<T> {
+    #[inline]
+    /// Cheaply construct a [`Reader`] connected to this [`Writer`]
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (r, w) = someday::local::new::<usize>(0);
+    /// assert!(w.reader().connected(&r));
+    /// ```
+    pub fn reader(&self) -> Reader<T> {
+        Reader {
+            shared: Rc::clone(&self.shared),
+        }
+    }
+
+    #[inline]
+    /// Generate a [`Transaction`], see its documentation.
+    pub fn tx(&mut self) -> Transaction<'_, T> {
+        Transaction::new(self)
+    }
+
+    #[inline]
+    #[must_use]
+    /// View the [`Writer`]'s _local_ data.
+    pub const fn data(&self) -> &T {
+        &self.local_as_ref().data
+    }
+
+    #[inline]
+    #[must_use]
+    /// View the latest copy of data [`Reader`]'s have access to.
+    pub fn data_remote(&self) -> &T {
+        &self.remote.data
+    }
+
+    #[inline]
+    #[must_use]
+    /// View the [`Writer`]'s _local_ "head" [`Commit`].
+    pub const fn head(&self) -> &Commit<T> {
+        self.local_as_ref()
+    }
+
+    #[inline]
+    #[must_use]
+    /// View the [`Reader`]'s latest "head" [`Commit`].
+    pub fn head_remote(&self) -> &Commit<T> {
+        &self.remote
+    }
+
+    #[inline]
+    #[must_use]
+    /// Cheaply acquire ownership of the [`Reader`]'s latest "head" [`Commit`].
+    pub fn head_remote_ref(&self) -> CommitRef<T> {
+        Rc::clone(&self.remote)
+    }
+
+    #[inline]
+    /// All the [`Patch`]'s that **haven't** been [`commit()`](Writer::commit)'ed yet.
+    pub fn staged(&mut self) -> &mut Vec<Patch<T>> {
+        &mut self.patches
+    }
+
+    #[inline]
+    #[must_use]
+    /// All the [`Patch`]'s that **have** been [`commit()`](Writer::commit)'ed but not yet [`push()`](Writer::push)'ed.
+    pub const fn committed_patches(&self) -> &Vec<Patch<T>> {
+        &self.patches_old
+    }
+
+    #[inline]
+    #[must_use]
+    /// The current [`Timestamp`] of the [`Writer`]'s local [`Commit`].
+    pub const fn timestamp(&self) -> Timestamp {
+        self.local_as_ref().timestamp
+    }
+
+    #[inline]
+    #[must_use]
+    /// The current [`Timestamp`] of the [`Reader`]'s head [`Commit`].
+    pub fn timestamp_remote(&self) -> Timestamp {
+        self.remote.timestamp
+    }
+
+    #[inline]
+    #[must_use]
+    /// How many [`Commit`]'s is the [`Writer`] ahead of the [`Reader`]'s?
+    pub fn timestamp_diff(&self) -> usize {
+        self.timestamp() - self.timestamp_remote()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Is the [`Writer`]'s local [`Commit`] the same as the [`Reader`]'s?
+    ///
+    /// ```rust
+    /// # use someday::local::*;
+    /// let (_, mut w) = someday::local::new::<usize>(0);
+    /// assert!(w.synced());
+    ///
+    /// w.add_commit(|w, _| *w += 1);
+    /// assert!(!w.synced());
+    ///
+    /// w.push();
+    /// assert!(w.synced());
+    /// ```
+    pub fn synced(&self) -> bool {
+        self.timestamp_diff() == 0
+    }
+
+    #[allow(clippy::option_if_let_else, clippy::inline_always)]
+    #[inline(always)]
+    /// Borrow `self.local`.
+    pub(super) const fn local_as_ref(&self) -> &Commit<T> {
+        // INVARIANT: `local` must be initialized after push()
+        match self.local.as_ref() {
+            Some(local) => local,
+            None => panic!("the `Writer`'s local data <T> was not initialized"),
+        }
+    }
+
+    #[allow(clippy::inline_always)]
+    #[inline(always)]
+    /// Borrow `self.local`.
+    pub(super) fn local_as_mut(&mut self) -> &mut Commit<T> {
+        // INVARIANT: `local` must be initialized after push()
+        self.local.as_mut().unwrap()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Constructor
+impl<T: Clone> Writer<T> {
+    /// Inner function for constructors.
+    pub(super) fn new_inner(local: Commit<T>) -> Self {
+        let remote = Rc::new(local.clone());
+        let shared = Rc::new(Shared::new(Rc::clone(&remote)));
+
+        Self {
+            local: Some(local),
+            remote,
+            shared,
+            patches: Vec::with_capacity(INIT_VEC_CAP),
+            patches_old: Vec::with_capacity(INIT_VEC_CAP),
+            clone_sync: false,
+            sync_base: None,
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Trait
+impl<T> core::fmt::Debug for Writer<T>
+where
+    T: Clone + core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Writer")
+            .field("local", &self.local)
+            .field("remote", &self.remote)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Clone> core::ops::Deref for Writer<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.local_as_ref().data
+    }
+}
+
+impl<T: Clone> Drop for Writer<T> {
+    fn drop(&mut self) {
+        self.shared.writer_dropped.set(true);
+    }
+}
@@ -0,0 +1,50 @@
+//! Three-way merging of data.
+
+//---------------------------------------------------------------------------------------------------- Use
+#[allow(unused_imports)] // docs
+use crate::{Commit, Reader, Writer};
+
+//---------------------------------------------------------------------------------------------------- Merge
+/// Data that can be three-way merged.
+///
+/// This is used by [`Writer::merge_from`] to bring the changes of a
+/// forked [`Writer`] back into the original (or vice-versa).
+///
+/// The 3 inputs are:
+/// - `base`: the common ancestor, i.e. the data at the time of the fork,
+///   or of the last merge between the two `Writer`'s
+/// - `ours`: the data of the `Writer` being merged into
+/// - `theirs`: the pushed data of the `Writer` being merged from
+///
+/// If the changes from `base -> ours` and `base -> theirs`
+/// cannot be reconciled, a [`Merge::Conflict`] should be returned.
+///
+/// ```rust
+/// # use someday::*;
+/// /// A counter where each side's increments are summed.
+/// #[derive(Clone)]
+/// struct Counter(usize);
+///
+/// impl Merge for Counter {
+///     type Conflict = std::convert::Infallible;
+///
+///     fn merge(base: &Self, ours: &Self, theirs: &Self) -> Result<Self, Self::Conflict> {
+///         Ok(Self(ours.0 + theirs.0 - base.0))
+///     }
+/// }
+///
+/// let base = Counter(1);
+/// let ours = Counter(5);
+/// let theirs = Counter(3);
+/// assert_eq!(Counter::merge(&base, &ours, &theirs).unwrap().0, 7);
+/// ```
+pub trait Merge: Sized {
+    /// The conflict(s) reported when the data could not be merged.
+    type Conflict;
+
+    /// Merge `ours` and `theirs`, using `base` as the common ancestor.
+    ///
+    /// # Errors
+    /// This should return [`Merge::Conflict`] if the changes cannot be merged.
+    fn merge(base: &Self, ours: &Self, theirs: &Self) -> Result<Self, Self::Conflict>;
+}
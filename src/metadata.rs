@@ -0,0 +1,215 @@
+//! Wall-clock metadata of commits.
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::string::String;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::SystemTime;
+
+#[allow(unused_imports)] // docs
+use crate::{Commit, CommitHeader, CommitRef, Reader, Writer};
+
+//---------------------------------------------------------------------------------------------------- Metadata
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[derive(Clone, Debug, Hash, PartialEq, PartialOrd, Eq, Ord)]
+/// Optional metadata of a [`Commit`]
+///
+/// [`Timestamp`](crate::Timestamp)'s only say _which_ version a [`Commit`] is,
+/// `Metadata` records _when_ it was created, pushed, and optionally why.
+///
+/// This is attached with [`Writer::commit_with`], `Commit`'s
+/// created by any other operation have no metadata.
+///
+/// Times are stored as the [`Duration`] since the UNIX epoch, without `std`
+/// there is no clock, so they must be created with [`Metadata::at`].
+///
+/// It is kept in the `Commit`'s [`CommitHeader`], see [`Commit::header`].
+///
+/// ```rust
+/// # use someday::*;
+/// # use std::time::*;
+/// let (r, mut w) = someday::new::<usize>(0);
+///
+/// w.add(Patch::Ptr(|w, _| *w += 1));
+/// w.commit_with(Metadata::with_message("bump"));
+/// w.push();
+///
+/// let head = r.head();
+/// let meta = head.header.meta.as_ref().unwrap();
+/// assert_eq!(meta.message.as_deref(), Some("bump"));
+/// assert!(meta.pushed.unwrap() >= meta.created);
+///
+/// // Reject stale data.
+/// assert!(head.header.age().unwrap() < Duration::from_secs(30));
+/// ```
+pub struct Metadata {
+    /// When the [`Commit`] was created, since the UNIX epoch.
+    pub created: Duration,
+
+    /// When the [`Commit`] was last pushed to [`Reader`]'s, since the UNIX epoch.
+    ///
+    /// This is `None` until the `Commit` is pushed, or always without `std`.
+    pub pushed: Option<Duration>,
+
+    /// A user-supplied message or tag.
+    pub message: Option<String>,
+}
+
+impl Metadata {
+    #[must_use]
+    /// Create new [`Metadata`] created at `created` (since the UNIX epoch), without a message
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::time::*;
+    /// let meta = Metadata::at(Duration::from_secs(1));
+    /// assert_eq!(meta.created, Duration::from_secs(1));
+    /// assert!(meta.pushed.is_none());
+    /// assert!(meta.message.is_none());
+    /// ```
+    pub const fn at(created: Duration) -> Self {
+        Self {
+            created,
+            pushed: None,
+            message: None,
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[must_use]
+    /// Create new [`Metadata`] created now, without a message
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let meta = Metadata::new();
+    /// assert!(meta.pushed.is_none());
+    /// assert!(meta.message.is_none());
+    /// ```
+    pub fn new() -> Self {
+        Self::at(now())
+    }
+
+    #[cfg(feature = "std")]
+    #[must_use]
+    /// Create new [`Metadata`] created now, with a `message`
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let meta = Metadata::with_message("hello");
+    /// assert_eq!(meta.message.as_deref(), Some("hello"));
+    /// ```
+    pub fn with_message<S: Into<String>>(message: S) -> Self {
+        Self {
+            message: Some(message.into()),
+            ..Self::new()
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[must_use]
+    /// How long ago this was created
+    ///
+    /// This returns `None` if the system clock went backwards since creation.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::time::*;
+    /// let meta = Metadata::new();
+    /// assert!(meta.age().unwrap() < Duration::from_secs(30));
+    /// ```
+    pub fn age(&self) -> Option<Duration> {
+        now().checked_sub(self.created)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for Metadata {
+    /// Same as [`Metadata::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Free
+/// The current time since the UNIX epoch, `0` if the clock is before it.
+#[cfg(feature = "std")]
+pub(crate) fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+//---------------------------------------------------------------------------------------------------- Borsh
+// `borsh` cannot (de)serialize `Duration`, so they
+// are encoded as `(seconds, nanoseconds)` tuples.
+
+/// Seconds and nanoseconds since the UNIX epoch.
+#[cfg(feature = "borsh")]
+type Epoch = (u64, u32);
+
+/// `Duration` -> `Epoch`.
+#[cfg(feature = "borsh")]
+const fn to_epoch(duration: Duration) -> Epoch {
+    (duration.as_secs(), duration.subsec_nanos())
+}
+
+/// `Epoch` -> `Duration`, `None` if it overflows.
+#[cfg(feature = "borsh")]
+fn from_epoch((secs, nanos): Epoch) -> Option<Duration> {
+    Duration::from_secs(secs).checked_add(Duration::from_nanos(u64::from(nanos)))
+}
+
+/// The error for unrepresentable `Duration`'s.
+#[cfg(feature = "borsh")]
+fn invalid_duration() -> borsh::io::Error {
+    borsh::io::Error::new(borsh::io::ErrorKind::InvalidData, "invalid Duration")
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for Metadata {
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w) = someday::new(String::from("hello"));
+    /// w.add(Patch::Ptr(|w, _| w.push('!')));
+    /// w.commit_with(Metadata::with_message("bang"));
+    /// w.push();
+    ///
+    /// let header = r.head().header.clone();
+    /// let encoded = borsh::to_vec(&header).unwrap();
+    /// let decoded: CommitHeader = borsh::from_slice(&encoded).unwrap();
+    /// assert_eq!(decoded, header);
+    /// ```
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        let created = to_epoch(self.created);
+        let pushed = self.pushed.map(to_epoch);
+        borsh::BorshSerialize::serialize(&(created, pushed, &self.message), writer)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for Metadata {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let (created, pushed, message): (Epoch, Option<Epoch>, Option<String>) =
+            match borsh::BorshDeserialize::deserialize_reader(reader) {
+                Ok(tuple) => tuple,
+                Err(e) => return Err(e),
+            };
+
+        let Some(created) = from_epoch(created) else {
+            return Err(invalid_duration());
+        };
+
+        let pushed = match pushed.map(from_epoch) {
+            Some(Some(pushed)) => Some(pushed),
+            Some(None) => return Err(invalid_duration()),
+            None => None,
+        };
+
+        Ok(Self {
+            created,
+            pushed,
+            message,
+        })
+    }
+}
@@ -1,7 +1,9 @@
 //! `Writer<T>`
 
 //---------------------------------------------------------------------------------------------------- Use
-use std::sync::Arc;
+use alloc::{boxed::Box, sync::Arc};
+
+use crate::{apply::Apply, error::BoxError};
 
 #[allow(unused_imports)] // docs
 use crate::{Reader, Writer};
@@ -50,10 +52,16 @@ use crate::{Reader, Writer};
 /// # ⚠️ Non-deterministic `Patch`
 /// The `Patch`'s you use with [`Writer::add`] **must be deterministic**.
 ///
-/// The `Writer` may apply your `Patch` twice, so any state that gets
-/// modified or functions used in the `Patch` must result in the
+/// The `Writer` may apply your `Patch` more than once, so any state that
+/// gets modified or functions used in the `Patch` must result in the
 /// same values as the first time the `Patch` was called.
 ///
+/// A committed `Patch` is applied twice normally (once when committing, once
+/// when reclaiming data), and may be replayed onto other data as well, e.g.
+/// when rolling back a failed [`Writer::try_commit`], in [`Writer::rebase_onto`]
+/// or after losing a push race to a [`Writer::share`]'ed `Writer`. This is the
+/// same for all variants, including the [`FnMut`] [`Patch::Box`] and [`Patch::Try`].
+///
 /// Here is a **non-deterministic** example:
 /// ```rust
 /// # use someday::*;
@@ -79,6 +87,8 @@ use crate::{Reader, Writer};
 /// assert_eq!(w.reader().head().data, 10);
 /// ```
 ///
+/// [`Writer::enable_determinism_check`] can be used to catch these while testing.
+///
 /// # The 2nd apply
 /// Note that if/when the `Writer` applies your `Patch` for the 2nd time
 /// inside [`Writer::push`], the `Reader` side of the data has _just_ been updated.
@@ -99,7 +109,26 @@ use crate::{Reader, Writer};
 ///     // while `r` will be the data the `Writer` just pushed.
 /// }));
 /// ```
-pub enum Patch<T: Clone> {
+///
+/// # Non-exhaustive
+/// `Patch` is `#[non_exhaustive]`, so `match`'ing on it outside of
+/// `someday` requires a wildcard arm. This is a breaking change in
+/// `0.7` (see the `CHANGELOG.md`), it is made once such that
+/// new variants (like [`Patch::Try`]) do not break code again.
+///
+/// ```rust
+/// # use someday::*;
+/// fn can_fail<T>(patch: &Patch<T>) -> bool {
+///     match patch {
+///         Patch::Try(_) => true,
+///         Patch::Box(_) | Patch::Arc(_) | Patch::Ptr(_) => false,
+///         // Required, there may be more variants in the future.
+///         _ => false,
+///     }
+/// }
+/// ```
+#[non_exhaustive]
+pub enum Patch<T> {
     /// Dynamically dispatched, potentially capturing, boxed function.
     ///
     /// ```rust
@@ -155,6 +184,25 @@ pub enum Patch<T: Clone> {
     /// ptr3();
     /// ```
     Ptr(fn(&mut T, &T)),
+
+    /// Dynamically dispatched, potentially capturing, boxed function that can fail.
+    ///
+    /// If this returns an error in [`Writer::try_commit`], the commit is
+    /// aborted, see [`Writer::try_add`] and [`PatchError`](crate::PatchError).
+    ///
+    /// Non-fallible operations (e.g. [`Writer::commit`]) ignore the error.
+    ///
+    /// Like all `Patch`'s, this must be deterministic, including the error.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let patch = Patch::<usize>::try_boxed(|w, _| {
+    ///     *w = w.checked_add(1).ok_or("overflow")?;
+    ///     Ok::<(), &str>(())
+    /// });
+    /// assert!(patch.is_try());
+    /// ```
+    Try(Box<dyn FnMut(&mut T, &T) -> Result<(), BoxError> + Send + 'static>),
 }
 
 impl<T: Clone + PartialEq> Patch<T> {
@@ -170,6 +218,9 @@ impl<T: Clone + PartialEq> Patch<T> {
 impl<T: Clone> Patch<T> {
     /// A [`Patch::Ptr`] that always clones the [`Reader`]'s data into the [`Writer`].
     pub const CLONE: Self = Self::Ptr(|w, r| *w = r.clone());
+}
+
+impl<T> Patch<T> {
     /// A [`Patch::Ptr`] that does nothing.
     pub const NOTHING: Self = Self::Ptr(|_, _| {});
 
@@ -212,13 +263,27 @@ impl<T: Clone> Patch<T> {
     }
 
     #[inline]
-    /// Apply the [`Patch`] onto the [`Writer`] data.
-    pub(crate) fn apply(&mut self, writer: &mut T, reader: &T) {
-        match self {
-            Self::Box(f) => f(writer, reader),
-            Self::Arc(f) => f(writer, reader),
-            Self::Ptr(f) => f(writer, reader),
-        }
+    /// Short-hand for `Self::Try(Box::new(patch))`.
+    ///
+    /// The error can be anything that converts into `Box<dyn Error + Send + Sync>`.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let try_patch = Patch::<String>::try_boxed(|w, _| {
+    ///     if w.is_empty() {
+    ///         Err("empty string")
+    ///     } else {
+    ///         Ok(w.push('!'))
+    ///     }
+    /// });
+    /// assert!(try_patch.is_try());
+    /// ```
+    pub fn try_boxed<P, E>(mut patch: P) -> Self
+    where
+        P: FnMut(&mut T, &T) -> Result<(), E> + Send + 'static,
+        E: Into<BoxError>,
+    {
+        Self::Try(Box::new(move |w, r| patch(w, r).map_err(Into::into)))
     }
 
     #[must_use]
@@ -247,16 +312,56 @@ impl<T: Clone> Patch<T> {
     pub const fn is_ptr(&self) -> bool {
         matches!(self, Self::Ptr(_))
     }
+
+    #[must_use]
+    #[allow(clippy::same_name_method)] // `Apply::is_try`, but `const`
+    /// If `self` is the `Patch::Try` variant.
+    pub const fn is_try(&self) -> bool {
+        matches!(self, Self::Try(_))
+    }
+}
+
+impl<T> Apply<T> for Patch<T> {
+    #[inline]
+    /// Apply the [`Patch`] onto the [`Writer`] data.
+    ///
+    /// Errors from [`Patch::Try`] are ignored.
+    fn apply(&mut self, writer: &mut T, reader: &T) {
+        match self {
+            Self::Box(f) => f(writer, reader),
+            Self::Arc(f) => f(writer, reader),
+            Self::Ptr(f) => f(writer, reader),
+            Self::Try(f) => drop(f(writer, reader)),
+        }
+    }
+
+    #[inline]
+    /// Apply the [`Patch`] onto the [`Writer`] data,
+    /// returning the error if it was a failing [`Patch::Try`].
+    fn try_apply(&mut self, writer: &mut T, reader: &T) -> Result<(), BoxError> {
+        match self {
+            Self::Box(f) => f(writer, reader),
+            Self::Arc(f) => f(writer, reader),
+            Self::Ptr(f) => f(writer, reader),
+            Self::Try(f) => return f(writer, reader),
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn is_try(&self) -> bool {
+        Self::is_try(self)
+    }
 }
 
-impl<T: Clone> Default for Patch<T> {
+impl<T> Default for Patch<T> {
     /// Returns [`Patch::NOTHING`].
     fn default() -> Self {
         Self::NOTHING
     }
 }
 
-impl<T: Clone> From<Box<dyn FnMut(&mut T, &T) + Send + 'static>> for Patch<T> {
+impl<T> From<Box<dyn FnMut(&mut T, &T) + Send + 'static>> for Patch<T> {
     /// ```rust
     /// # use someday::*;
     /// let string = String::new();
@@ -273,7 +378,7 @@ impl<T: Clone> From<Box<dyn FnMut(&mut T, &T) + Send + 'static>> for Patch<T> {
     }
 }
 
-impl<T: Clone> From<Arc<dyn Fn(&mut T, &T) + Send + Sync + 'static>> for Patch<T> {
+impl<T> From<Arc<dyn Fn(&mut T, &T) + Send + Sync + 'static>> for Patch<T> {
     /// ```rust
     /// # use someday::*;
     /// # use std::sync::*;
@@ -291,7 +396,7 @@ impl<T: Clone> From<Arc<dyn Fn(&mut T, &T) + Send + Sync + 'static>> for Patch<T
     }
 }
 
-impl<T: Clone> From<&Arc<dyn Fn(&mut T, &T) + Send + Sync + 'static>> for Patch<T> {
+impl<T> From<&Arc<dyn Fn(&mut T, &T) + Send + Sync + 'static>> for Patch<T> {
     /// ```rust
     /// # use someday::*;
     /// # use std::sync::*;
@@ -309,7 +414,7 @@ impl<T: Clone> From<&Arc<dyn Fn(&mut T, &T) + Send + Sync + 'static>> for Patch<
     }
 }
 
-impl<T: Clone> From<fn(&mut T, &T)> for Patch<T> {
+impl<T> From<fn(&mut T, &T)> for Patch<T> {
     /// ```rust
     /// # use someday::*;
     /// let ptr: fn(&mut String, &String) = |w, _| {
@@ -324,16 +429,19 @@ impl<T: Clone> From<fn(&mut T, &T)> for Patch<T> {
     }
 }
 
-impl<T: Clone> std::fmt::Debug for Patch<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<T> core::fmt::Debug for Patch<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Box(ptr) => {
-                f.write_fmt(format_args!("Patch::Box({:?})", std::ptr::addr_of!(**ptr)))
+                f.write_fmt(format_args!("Patch::Box({:?})", core::ptr::addr_of!(**ptr)))
             }
             Self::Arc(ptr) => {
-                f.write_fmt(format_args!("Patch::Arc({:?})", std::ptr::addr_of!(**ptr)))
+                f.write_fmt(format_args!("Patch::Arc({:?})", core::ptr::addr_of!(**ptr)))
             }
             Self::Ptr(ptr) => f.write_fmt(format_args!("Patch::Ptr({ptr:?})")),
+            Self::Try(ptr) => {
+                f.write_fmt(format_args!("Patch::Try({:?})", core::ptr::addr_of!(**ptr)))
+            }
         }
     }
 }
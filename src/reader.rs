@@ -1,13 +1,18 @@
 //! `Reader<T>`
 
 //---------------------------------------------------------------------------------------------------- Use
+use alloc::{boxed::Box, sync::Arc};
+use core::num::NonZeroUsize;
+
 use crate::{
     commit::{Commit, CommitRef},
-    free::INIT_VEC_CAP,
-    writer::{WriterReviveToken, WriterToken},
+    sync::ArcSwap,
+    writer::{ForkBase, WriterReviveToken, WriterState, WriterToken},
     Writer,
 };
-use std::{num::NonZeroUsize, sync::Arc};
+
+#[cfg(feature = "testing")]
+use crate::testing::{Fault, FaultInjector};
 
 //---------------------------------------------------------------------------------------------------- Reader
 /// Reader(s) who can read some data `T`.
@@ -115,18 +120,21 @@ use std::{num::NonZeroUsize, sync::Arc};
 /// drop(head_commit);
 /// ```
 #[derive(Clone, Debug)]
-pub struct Reader<T: Clone> {
+pub struct Reader<T> {
     /// The atomic pointer to the `Arc` that all readers enter through.
     ///
     /// This is `swap()` updated by the `Writer`.
-    pub(super) arc: Arc<arc_swap::ArcSwapAny<Arc<Commit<T>>>>,
+    pub(super) arc: Arc<ArcSwap<Commit<T>>>,
     /// Has the associated `Writer` to this `Reader` been dropped?
     pub(super) token: WriterToken,
     /// Optional cache of the latest `head()`.
     pub(super) cache: Option<Arc<Commit<T>>>,
+    /// Faults forced by a `FaultInjector`, see `Reader::set_fault_injector()`.
+    #[cfg(feature = "testing")]
+    pub(super) faults: Option<crate::testing::FaultInjector>,
 }
 
-impl<T: Clone> Reader<T> {
+impl<T> Reader<T> {
     #[inline]
     #[must_use]
     /// Acquire the latest [`CommitRef`] pushed by the [`Writer`].
@@ -367,6 +375,8 @@ impl<T: Clone> Reader<T> {
     /// This returns whether the associated [`Writer`] to this
     /// [`Reader`] has been dropped (or [`Writer::disconnect`]'ed).
     ///
+    /// With [`Writer::share`], this only returns `true` once all `Writer`'s are dropped.
+    ///
     /// Note that even if this returns `true`, [`Reader::try_into_writer`]
     /// is not guaranteed to succeed as other `Reader`'s could race towards
     /// becoming the new `Writer`.
@@ -385,6 +395,105 @@ impl<T: Clone> Reader<T> {
         self.token.is_dead()
     }
 
+    #[must_use]
+    /// The [`WriterState`] of the associated [`Writer`]
+    ///
+    /// This tells whether the `Writer` is still alive, finished with
+    /// [`Writer::close`], or was dropped without closing (e.g. it panicked).
+    ///
+    /// With [`Writer::share`], this is the state of the last `Writer` dropped.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, w) = someday::new(());
+    /// assert_eq!(r.state(), WriterState::Open);
+    ///
+    /// drop(w);
+    /// assert_eq!(r.state(), WriterState::Abandoned);
+    ///
+    /// let w = r.clone().try_into_writer().unwrap();
+    /// assert_eq!(r.state(), WriterState::Open);
+    ///
+    /// w.close("shutdown");
+    /// assert_eq!(r.state(), WriterState::Closed("shutdown".into()));
+    /// ```
+    pub fn state(&self) -> WriterState {
+        self.token.state()
+    }
+
+    /// Call `callback` once the associated [`Writer`] is dropped
+    ///
+    /// The `callback` is called on the thread that drops the last `Writer`
+    /// (or calls [`Writer::disconnect`]), right after [`Reader::writer_dropped`]
+    /// starts returning `true`. If the `Writer` is already dropped, the
+    /// `callback` is called immediately on this thread.
+    ///
+    /// Each `callback` is only called once, even if the `Writer` is revived
+    /// with [`Reader::try_into_writer`] and dropped again, register a new one
+    /// to be notified again.
+    ///
+    /// The `callback` is kept even if this `Reader` is dropped.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::sync::{*,atomic::*};
+    /// let (r, w) = someday::new(());
+    ///
+    /// let dropped = Arc::new(AtomicBool::new(false));
+    /// let dropped2 = Arc::clone(&dropped);
+    /// r.on_writer_dropped(move || dropped2.store(true, Ordering::Relaxed));
+    /// assert!(!dropped.load(Ordering::Relaxed));
+    ///
+    /// drop(w);
+    /// assert!(dropped.load(Ordering::Relaxed));
+    /// ```
+    pub fn on_writer_dropped<F>(&self, callback: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.token.on_dead(Box::new(callback));
+    }
+
+    #[cfg(feature = "std")]
+    /// Block the current thread until the associated [`Writer`] is dropped
+    ///
+    /// This returns immediately if the `Writer` is already dropped.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, w) = someday::new(());
+    ///
+    /// std::thread::spawn(move || {
+    ///     std::thread::sleep(std::time::Duration::from_millis(10));
+    ///     drop(w);
+    /// });
+    ///
+    /// r.wait_writer_dropped();
+    /// assert!(r.writer_dropped());
+    /// ```
+    pub fn wait_writer_dropped(&self) {
+        self.token.wait_dead();
+    }
+
+    #[cfg(feature = "std")]
+    #[must_use]
+    /// [`Reader::wait_writer_dropped`], but give up after `timeout`
+    ///
+    /// This returns `true` if the [`Writer`] was dropped.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::time::*;
+    /// let (r, w) = someday::new(());
+    /// assert!(!r.wait_writer_dropped_timeout(Duration::from_millis(1)));
+    ///
+    /// drop(w);
+    /// assert!(r.wait_writer_dropped_timeout(Duration::from_millis(1)));
+    /// ```
+    pub fn wait_writer_dropped_timeout(&self, timeout: core::time::Duration) -> bool {
+        self.token.wait_dead_timeout(timeout)
+    }
+
     #[must_use]
     /// Are both these [`Reader`]'s associated with the same [`Writer`]?
     ///
@@ -432,6 +541,39 @@ impl<T: Clone> Reader<T> {
         Arc::ptr_eq(&self.arc, &writer.arc)
     }
 
+    #[cfg(feature = "testing")]
+    /// Force [`Fault`]'s in this [`Reader`] with a [`FaultInjector`]
+    ///
+    /// This replaces the previous `FaultInjector`, e.g. the
+    /// one inherited from [`Writer::set_fault_injector`].
+    ///
+    /// Clones of this `Reader` use the same `FaultInjector`, and the
+    /// `Writer` returned by [`Reader::try_into_writer`] does as well.
+    ///
+    /// ```rust
+    /// # use someday::{*, testing::*};
+    /// let (mut r, w) = someday::new::<usize>(0);
+    /// r.set_fault_injector(FaultInjector::new().with(Fault::ReviveFail));
+    /// drop(w);
+    ///
+    /// // Only fails once.
+    /// let r = r.try_into_writer().unwrap_err();
+    /// let w = r.try_into_writer().unwrap();
+    /// ```
+    pub fn set_fault_injector(&mut self, injector: FaultInjector) {
+        self.faults = Some(injector);
+    }
+
+    #[cfg(feature = "testing")]
+    /// Remove the [`FaultInjector`] set with [`Reader::set_fault_injector`]
+    ///
+    /// This returns `true` if there was a `FaultInjector`.
+    pub fn clear_fault_injector(&mut self) -> bool {
+        self.faults.take().is_some()
+    }
+}
+
+impl<T: Clone> Reader<T> {
     /// Attempt to transform this [`Reader`] into an associated [`Writer`].
     ///
     /// If the original `Writer` associated with this `Reader` is gone,
@@ -476,7 +618,19 @@ impl<T: Clone> Reader<T> {
     /// assert_eq!(r2.head().data, "hello world!");
     /// ```
     pub fn try_into_writer(self) -> Result<Writer<T>, Self> {
+        #[cfg(feature = "testing")]
+        if self.writer_dropped()
+            && self
+                .faults
+                .as_ref()
+                .is_some_and(|faults| faults.trigger(Fault::ReviveFail))
+        {
+            return Err(self);
+        }
+
         let Some(writer_revive_token) = self.token.try_revive() else {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("try_into_writer failed, the `Writer` is still alive");
             return Err(self);
         };
 
@@ -487,23 +641,31 @@ impl<T: Clone> Reader<T> {
         //------------------------------------------------------------
 
         let remote = self.head();
-        let local = Some(remote.as_ref().clone());
-        let arc = self.arc;
-        let patches = Vec::with_capacity(INIT_VEC_CAP);
-        let patches_old = Vec::with_capacity(INIT_VEC_CAP);
+        let mut local = remote.as_ref().clone();
+        // The old `Writer`'s unpushed commits may still be around
+        // (e.g. serialized), so continue in a new lineage.
+        local.header.lineage = local.header.lineage.map(|l| l.branch(local.timestamp));
 
         // INVARIANT: We must tell the token that we have successfully revived the `Writer`.
         WriterReviveToken::revived(writer_revive_token);
 
-        let writer = Writer {
-            token: self.token,
+        let writer = Writer::from_parts(
+            self.token.into_owner(),
             local,
             remote,
-            arc,
-            patches,
-            patches_old,
+            self.arc,
+            Some(T::clone),
+        );
+        #[cfg(feature = "testing")]
+        let writer = {
+            let mut revived = writer;
+            revived.faults.injector.clone_from(&self.faults);
+            revived
         };
 
+        #[cfg(feature = "tracing")]
+        tracing::debug!(timestamp = writer.timestamp(), "try_into_writer");
+
         Ok(writer)
     }
 
@@ -541,22 +703,23 @@ impl<T: Clone> Reader<T> {
     /// ```
     pub fn fork(&self) -> Writer<T> {
         let remote = self.head();
-        let local = remote.as_ref().clone();
-        let arc = Arc::new(arc_swap::ArcSwap::new(Arc::clone(&remote)));
+        let mut local = remote.as_ref().clone();
+        local.header.lineage = local.header.lineage.map(|l| l.branch(local.timestamp));
 
-        Writer {
-            token: WriterToken::new(),
-            local: Some(local),
-            remote,
-            arc,
-            patches: Vec::with_capacity(INIT_VEC_CAP),
-            patches_old: Vec::with_capacity(INIT_VEC_CAP),
-        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(timestamp = local.timestamp, "fork");
+
+        let arc = Arc::new(ArcSwap::new(Arc::clone(&remote)));
+        let fork_base = Some(ForkBase::new(&self.arc, Arc::clone(&remote)));
+
+        let mut writer = Writer::from_parts(WriterToken::new(), local, remote, arc, Some(T::clone));
+        writer.fork_base = fork_base;
+        writer
     }
 }
 
 //---------------------------------------------------------------------------------------------------- Trait Impl
-impl<T: Clone> From<&Writer<T>> for Reader<T> {
+impl<T> From<&Writer<T>> for Reader<T> {
     #[inline]
     fn from(value: &Writer<T>) -> Self {
         value.reader()
@@ -603,7 +766,7 @@ where
     ///
     /// let encoded = bincode::encode_to_vec(&r, config).unwrap();
     /// let decoded: Commit<String> = bincode::decode_from_slice(&encoded, config).unwrap().0;
-    /// assert_eq!(decoded, Commit { timestamp: 0, data: String::from("hello") });
+    /// assert_eq!(decoded, Commit { timestamp: 0, data: String::from("hello"), header: CommitHeader::new() });
     /// ```
     fn encode<E: bincode::enc::Encoder>(
         &self,
@@ -628,9 +791,9 @@ where
     ///
     /// let encoded = borsh::to_vec(&r).unwrap();
     /// let decoded: Commit<String> = borsh::from_slice(&encoded).unwrap();
-    /// assert_eq!(decoded, Commit { timestamp: 0, data: String::from("hello") });
+    /// assert_eq!(decoded, Commit { timestamp: 0, data: String::from("hello"), header: CommitHeader::new() });
     /// ```
-    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
         CommitRef::serialize(&self.head(), writer)
     }
 }
@@ -0,0 +1,188 @@
+//! Synchronization primitives.
+//!
+//! These are `loom`'s under `cfg(loom)`, such that `loom` can
+//! model the `WriterToken` protocol, `std`'s with the `std`
+//! feature, and `spin`'s otherwise (`no_std` + `alloc` + `spin`).
+//!
+//! `CommitRef`'s are always `alloc::sync::Arc`'s, under `cfg(loom)` the
+//! [`ArcSwap`] holding them is a `loom` lock, so `loom` models `Reader`'s
+//! holding onto a head while the `Writer` pushes and tries to reclaim it.
+
+//---------------------------------------------------------------------------------------------------- Use
+#[cfg(loom)]
+pub(crate) use loom::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc, Condvar, Mutex, MutexGuard,
+};
+
+#[cfg(all(not(loom), feature = "std"))]
+pub(crate) use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc, Condvar, Mutex, MutexGuard,
+};
+
+#[cfg(all(not(loom), not(feature = "std")))]
+pub(crate) use {
+    alloc::sync::Arc,
+    core::sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    spin::{Mutex, MutexGuard},
+};
+
+//---------------------------------------------------------------------------------------------------- Free
+/// Lock `mutex`, ignoring poison.
+///
+/// `spin` locks cannot be poisoned, callers
+/// must not leave the data invalid either way.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    #[cfg(any(loom, feature = "std"))]
+    return mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+    #[cfg(not(any(loom, feature = "std")))]
+    return mutex.lock();
+}
+
+/// Is the current thread panicking?
+///
+/// This is always `false` without `std`.
+pub(crate) fn panicking() -> bool {
+    #[cfg(feature = "std")]
+    return std::thread::panicking();
+
+    #[cfg(not(feature = "std"))]
+    return false;
+}
+
+/// Block the current thread for `duration`.
+///
+/// Without `std` there is no clock, so this does nothing,
+/// the functions that sleep require `std` anyway.
+pub(crate) fn sleep(duration: core::time::Duration) {
+    #[cfg(feature = "std")]
+    std::thread::sleep(duration);
+
+    #[cfg(not(feature = "std"))]
+    let _ = duration;
+}
+
+/// Hint that the current thread is waiting on other threads.
+pub(crate) fn yield_now() {
+    #[cfg(feature = "std")]
+    std::thread::yield_now();
+
+    #[cfg(not(feature = "std"))]
+    core::hint::spin_loop();
+}
+
+//---------------------------------------------------------------------------------------------------- ArcSwap
+/// An atomically swappable `Arc<T>`, what `Reader`'s enter through.
+///
+/// With `std`, this is an `arc_swap::ArcSwap`, which is lock-free for `Reader`'s.
+///
+/// Without `std` (`arc_swap` requires thread-locals), this is
+/// a `spin` lock, only held while cloning/swapping the `Arc`.
+///
+/// Under `cfg(loom)`, this is a `loom` lock, such that `loom` models
+/// `push()` and `head()` racing, and so reclaiming the old `Arc`.
+///
+/// Each `ArcSwap` also has a unique ID, to identify related `Writer`'s
+/// and `Reader`'s by, addresses can be re-used after being freed.
+pub(crate) struct ArcSwap<T> {
+    /// The unique ID of this `ArcSwap`.
+    id: crate::LineageId,
+    /// The current `Arc`.
+    #[cfg(all(not(loom), feature = "std"))]
+    inner: arc_swap::ArcSwap<T>,
+    /// The current `Arc`.
+    #[cfg(any(loom, not(feature = "std")))]
+    inner: Mutex<alloc::sync::Arc<T>>,
+}
+
+impl<T> ArcSwap<T> {
+    /// Create a new `Self` pointing to `arc`.
+    pub(crate) fn new(arc: alloc::sync::Arc<T>) -> Self {
+        #[cfg(all(not(loom), feature = "std"))]
+        let inner = arc_swap::ArcSwap::new(arc);
+        #[cfg(any(loom, not(feature = "std")))]
+        let inner = Mutex::new(arc);
+
+        Self {
+            id: crate::lineage::new_id(),
+            inner,
+        }
+    }
+
+    /// The unique ID of this `ArcSwap`.
+    pub(crate) const fn id(&self) -> crate::LineageId {
+        self.id
+    }
+
+    /// Create a new `Self` pointing to a new `Arc` of `value`.
+    pub(crate) fn from_pointee(value: T) -> Self {
+        Self::new(alloc::sync::Arc::new(value))
+    }
+
+    /// Temporarily borrow the current `Arc`.
+    ///
+    /// This must not be held onto, without `std`, it blocks all other operations.
+    pub(crate) fn load(&self) -> impl core::ops::Deref<Target = alloc::sync::Arc<T>> + '_ {
+        #[cfg(all(not(loom), feature = "std"))]
+        return self.inner.load();
+
+        #[cfg(any(loom, not(feature = "std")))]
+        return lock(&self.inner);
+    }
+
+    /// Clone the current `Arc`.
+    pub(crate) fn load_full(&self) -> alloc::sync::Arc<T> {
+        alloc::sync::Arc::clone(&self.load())
+    }
+
+    /// Replace the current `Arc` with `new`.
+    pub(crate) fn store(&self, new: alloc::sync::Arc<T>) {
+        #[cfg(all(not(loom), feature = "std"))]
+        self.inner.store(new);
+
+        #[cfg(any(loom, not(feature = "std")))]
+        drop(core::mem::replace(&mut *lock(&self.inner), new));
+    }
+
+    /// Replace the current `Arc` with `new`, returning the previous `Arc`.
+    pub(crate) fn swap(&self, new: alloc::sync::Arc<T>) -> alloc::sync::Arc<T> {
+        #[cfg(all(not(loom), feature = "std"))]
+        return self.inner.swap(new);
+
+        #[cfg(any(loom, not(feature = "std")))]
+        return core::mem::replace(&mut *lock(&self.inner), new);
+    }
+
+    /// Replace the current `Arc` with `new`, only if it is `current`.
+    ///
+    /// This returns the previous `Arc`, i.e. it
+    /// was replaced if it is the same as `current`.
+    pub(crate) fn compare_and_swap(
+        &self,
+        current: &alloc::sync::Arc<T>,
+        new: alloc::sync::Arc<T>,
+    ) -> alloc::sync::Arc<T> {
+        #[cfg(all(not(loom), feature = "std"))]
+        return arc_swap::Guard::into_inner(self.inner.compare_and_swap(current, new));
+
+        #[cfg(any(loom, not(feature = "std")))]
+        {
+            let mut inner = lock(&self.inner);
+            if alloc::sync::Arc::ptr_eq(&inner, current) {
+                core::mem::replace(&mut *inner, new)
+            } else {
+                alloc::sync::Arc::clone(&inner)
+            }
+        }
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for ArcSwap<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("ArcSwap").field(&**self.load()).finish()
+    }
+}
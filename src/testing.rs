@@ -0,0 +1,693 @@
+//! Model-based testing of [`Patch`]'s.
+//!
+//! A [`Harness`] drives a [`Writer`] with randomly generated sequences
+//! of [`Op`]'s (`add()`, `commit()`, `push()`, `pull()`, `overwrite()`,
+//! `tx()`, `sync_patch()`, `fork()`), while [`Reader`]'s hold onto old [`CommitRef`]'s
+//! at random, such that both the reclaim and clone paths of `push()` run.
+//!
+//! The same `Patch`'s are applied to a plain `T` (the model), and after every
+//! operation, the `Writer` and `Reader` data is compared against the model.
+//!
+//! This catches `Patch`'s that are not deterministic, or that
+//! otherwise break when applied twice, see [`Patch`].
+//!
+//! ```rust
+//! # use someday::testing::*;
+//! let harness = Harness::new(Vec::<usize>::new())
+//!     .patch(|w, _| w.push(w.len()))
+//!     .patch(|w, _| {
+//!         w.pop();
+//!     })
+//!     .patch(|w, _| w.sort_unstable_by(|a, b| b.cmp(a)));
+//!
+//! for seed in 0..100 {
+//!     harness.run(seed).unwrap();
+//! }
+//! ```
+//!
+//! A non-deterministic `Patch`:
+//! ```rust
+//! # use someday::testing::*;
+//! # use std::sync::atomic::*;
+//! static COUNTER: AtomicUsize = AtomicUsize::new(0);
+//!
+//! let harness = Harness::new(Vec::<usize>::new())
+//!     .patch(|w, _| w.push(COUNTER.fetch_add(1, Ordering::Relaxed)));
+//!
+//! let failure = (0..100).find_map(|seed| harness.run(seed).err()).unwrap();
+//!
+//! // The failing sequence can be replayed.
+//! assert!(harness.run_ops(&failure.ops).is_err());
+//! ```
+//!
+//! A [`FaultInjector`] forces hard to trigger situations (reclaiming
+//! failing, a `Writer` dying mid-way, etc) to test code built on `someday`.
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::vec::Vec;
+
+use std::{
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use crate::{CommitRef, Patch};
+
+#[allow(unused_imports)] // docs
+use crate::{Reader, Writer};
+
+//---------------------------------------------------------------------------------------------------- Op
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// An operation done by a [`Harness`]
+///
+/// Indices refer to the [`Harness::patch`]'s, in the order they were added.
+pub enum Op {
+    /// [`Writer::add`] this `Patch`.
+    Add(usize),
+    /// [`Writer::commit`].
+    Commit,
+    /// [`Writer::push`].
+    Push,
+    /// [`Writer::pull`].
+    Pull,
+    /// [`Writer::overwrite`] with a copy of the data, with this `Patch` applied.
+    Overwrite(usize),
+    /// Apply this `Patch` within a [`Writer::tx`].
+    Tx(usize),
+    /// [`Op::Tx`], with a [`Transaction::sync_patch`](crate::Transaction::sync_patch) that copies `r`.
+    TxSync(usize),
+    /// [`Writer::fork`], check, and push to the fork.
+    Fork,
+    /// A [`Reader`] holds onto the current head, preventing `push()` from reclaiming it.
+    Hold,
+    /// Drop all held heads.
+    Release,
+}
+
+//---------------------------------------------------------------------------------------------------- Report
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A summary of a successful [`Harness::run`]
+pub struct Report {
+    /// How many [`Op`]'s were run.
+    pub ops: usize,
+    /// How many `push()`'s pushed something.
+    pub pushes: usize,
+    /// How many of those `push()`'s reclaimed the old data.
+    pub reclaimed: usize,
+    /// How many of those `push()`'s cloned the new data.
+    pub cloned: usize,
+}
+
+//---------------------------------------------------------------------------------------------------- Failure
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Which data diverged from the model in a [`Failure`]
+pub enum Mismatch {
+    /// [`Writer::data`].
+    Writer,
+    /// [`Reader::head`].
+    Reader,
+    /// [`Writer::data`] of a [`Writer::fork`].
+    Fork,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A failed [`Harness::run`]
+pub struct Failure<T> {
+    /// All [`Op`]'s, up to and including the failing one.
+    ///
+    /// These can be replayed with [`Harness::run_ops`].
+    pub ops: Vec<Op>,
+    /// Which data diverged.
+    pub mismatch: Mismatch,
+    /// The data of the model.
+    pub expected: T,
+    /// The data that diverged.
+    pub found: T,
+}
+
+impl<T> std::fmt::Display for Failure<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mismatch = match self.mismatch {
+            Mismatch::Writer => "`Writer`",
+            Mismatch::Reader => "`Reader`",
+            Mismatch::Fork => "forked `Writer`",
+        };
+        write!(
+            f,
+            "{mismatch} data diverged from the model after {} operations",
+            self.ops.len()
+        )
+    }
+}
+
+impl<T: std::fmt::Debug> std::error::Error for Failure<T> {}
+
+//---------------------------------------------------------------------------------------------------- Harness
+/// A `Patch` function shared by the `Writer` and model.
+type PatchFn<T> = Arc<dyn Fn(&mut T, &T) + Send + Sync + 'static>;
+
+/// A model-based test of a set of [`Patch`]'s
+///
+/// See the [module](self) documentation.
+pub struct Harness<T: Clone> {
+    /// The initial data.
+    initial: T,
+    /// The `Patch`'s, in order.
+    patches: Vec<PatchFn<T>>,
+    /// How many `Op`'s are generated per run.
+    ops: usize,
+}
+
+impl<T> Harness<T>
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+{
+    #[must_use]
+    /// Create a new [`Harness`] starting from `initial`, with no [`Patch`]'s
+    ///
+    /// Each run generates `100` operations by default, see [`Harness::ops`].
+    pub fn new(initial: T) -> Self {
+        Self {
+            initial,
+            patches: Vec::new(),
+            ops: 100,
+        }
+    }
+
+    #[must_use]
+    /// Add a [`Patch`] to test
+    ///
+    /// The `&T` input is the [`Reader`] data, like [`Writer::add`].
+    pub fn patch<P>(mut self, patch: P) -> Self
+    where
+        P: Fn(&mut T, &T) + Send + Sync + 'static,
+    {
+        self.patches.push(Arc::new(patch));
+        self
+    }
+
+    #[must_use]
+    /// Set how many [`Op`]'s are generated per run
+    pub const fn ops(mut self, ops: usize) -> Self {
+        self.ops = ops;
+        self
+    }
+
+    #[must_use]
+    /// Generate the [`Op`]'s of a run from a `seed`
+    ///
+    /// The same `seed` always generates the same `Op`'s.
+    ///
+    /// ```rust
+    /// # use someday::testing::*;
+    /// let harness = Harness::new(0_usize).patch(|w, _| *w += 1).ops(10);
+    /// assert_eq!(harness.generate(1), harness.generate(1));
+    /// assert_eq!(harness.generate(1).len(), 10);
+    /// ```
+    pub fn generate(&self, seed: u64) -> Vec<Op> {
+        let mut rng = Rng(seed);
+        let patches = self.patches.len();
+
+        std::iter::repeat_with(|| {
+            // Without `Patch`'s, only generate the other operations.
+            let roll = if patches == 0 {
+                30 + rng.below(70)
+            } else {
+                rng.below(100)
+            };
+            let patch = rng.below(patches.max(1));
+
+            match roll {
+                0..=29 => Op::Add(patch),
+                30..=44 => Op::Commit,
+                45..=59 => Op::Push,
+                60..=64 => Op::Pull,
+                65..=69 if patches != 0 => Op::Overwrite(patch),
+                70..=74 if patches != 0 => Op::Tx(patch),
+                75..=77 if patches != 0 => Op::TxSync(patch),
+                65..=79 => Op::Fork,
+                80..=89 => Op::Hold,
+                _ => Op::Release,
+            }
+        })
+        .take(self.ops)
+        .collect()
+    }
+
+    /// Generate [`Op`]'s from a `seed` and run them
+    ///
+    /// # Errors
+    /// This returns a [`Failure`] if the [`Writer`] or [`Reader`]
+    /// data diverged from the model.
+    pub fn run(&self, seed: u64) -> Result<Report, Failure<T>> {
+        self.run_ops(&self.generate(seed))
+    }
+
+    /// Run a specific sequence of [`Op`]'s
+    ///
+    /// This can be used to replay [`Failure::ops`].
+    ///
+    /// ```rust
+    /// # use someday::testing::*;
+    /// let harness = Harness::new(0_usize).patch(|w, _| *w += 1);
+    ///
+    /// let report = harness.run_ops(&[Op::Add(0), Op::Commit, Op::Push]).unwrap();
+    /// assert_eq!(report.pushes, 1);
+    /// assert_eq!(report.reclaimed, 1);
+    ///
+    /// let ops = [Op::Add(0), Op::Commit, Op::Hold, Op::Push];
+    /// let report = harness.run_ops(&ops).unwrap();
+    /// assert_eq!(report.cloned, 1);
+    ///
+    /// // Commits after a custom `sync_patch` are only applied once.
+    /// let ops = [Op::TxSync(0), Op::Add(0), Op::Commit, Op::Push, Op::Add(0), Op::Commit, Op::Push];
+    /// let report = harness.run_ops(&ops).unwrap();
+    /// assert_eq!(report.reclaimed, 2);
+    /// ```
+    ///
+    /// # Errors
+    /// This returns a [`Failure`] if the [`Writer`] or [`Reader`]
+    /// data diverged from the model.
+    ///
+    /// # Panics
+    /// This panics if an `Op` refers to a [`Harness::patch`] that does not exist.
+    pub fn run_ops(&self, ops: &[Op]) -> Result<Report, Failure<T>> {
+        let (r, mut w) = crate::new(self.initial.clone());
+
+        // The model.
+        let mut local = self.initial.clone();
+        let mut remote = self.initial.clone();
+        let mut staged = Vec::new();
+
+        // Old heads held by "readers", these are never read,
+        // they only prevent `push()` from reclaiming them.
+        #[allow(clippy::collection_is_never_read)]
+        let mut old_heads: Vec<CommitRef<T>> = Vec::new();
+
+        let mut report = Report::default();
+
+        for (step, op) in ops.iter().enumerate() {
+            let fail = |mismatch, expected: &T, found: &T| Failure {
+                ops: ops.iter().take(step + 1).copied().collect(),
+                mismatch,
+                expected: expected.clone(),
+                found: found.clone(),
+            };
+
+            match *op {
+                Op::Add(i) => {
+                    w.add(Patch::from(self.patch_fn(i)));
+                    staged.push(i);
+                }
+                Op::Commit => {
+                    w.commit();
+                    for i in std::mem::take(&mut staged) {
+                        self.patch_fn(i)(&mut local, &remote);
+                    }
+                }
+                Op::Push => {
+                    let push_info = w.push();
+                    remote.clone_from(&local);
+                    if push_info.commits != 0 {
+                        report.pushes += 1;
+                        if push_info.reclaimed {
+                            report.reclaimed += 1;
+                        } else {
+                            report.cloned += 1;
+                        }
+                    }
+                }
+                Op::Pull => {
+                    drop(w.pull());
+                    local.clone_from(&remote);
+                }
+                Op::Overwrite(i) => {
+                    self.patch_fn(i)(&mut local, &remote);
+                    drop(w.overwrite(local.clone()));
+                }
+                Op::Tx(i) => {
+                    self.patch_fn(i)(w.tx().data_mut(), &remote);
+                    self.patch_fn(i)(&mut local, &remote);
+                }
+                Op::TxSync(i) => {
+                    let mut tx = w.tx();
+                    self.patch_fn(i)(tx.data_mut(), &remote);
+                    tx.sync_patch(Patch::Ptr(T::clone_from));
+                    drop(tx);
+                    self.patch_fn(i)(&mut local, &remote);
+                }
+                Op::Fork => {
+                    let mut fork = w.fork();
+                    if *fork.data() != local {
+                        return Err(fail(Mismatch::Fork, &local, fork.data()));
+                    }
+                    // Must not be visible to our `Reader`'s.
+                    fork.push();
+                }
+                Op::Hold => old_heads.push(r.head()),
+                Op::Release => old_heads.clear(),
+            }
+
+            if *w.data() != local {
+                return Err(fail(Mismatch::Writer, &local, w.data()));
+            }
+            let head = r.head();
+            if head.data != remote {
+                return Err(fail(Mismatch::Reader, &remote, &head.data));
+            }
+
+            report.ops += 1;
+        }
+
+        Ok(report)
+    }
+}
+
+impl<T: Clone> Harness<T> {
+    /// The `Patch` at index `i`.
+    ///
+    /// # Panics
+    /// This panics if there is no such `Patch`.
+    fn patch_fn(&self, i: usize) -> &PatchFn<T> {
+        self.patches.get(i).expect("no such `Patch`")
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Fault
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A fault that a [`FaultInjector`] can force
+///
+/// Faults stay armed until [`FaultInjector::disarm`]'ed, except for
+/// [`Fault::PatchPanic`], [`Fault::WriterDeath`] and [`Fault::ReviveFail`],
+/// which are disarmed after they trigger once.
+pub enum Fault {
+    /// `push()`'s never reclaim the old data, they always clone.
+    ReclaimFail,
+    /// [`Writer::push_wait`] does not sleep, and times out without reclaiming.
+    PushWaitTimeout,
+    /// Every head replaced by a `push()` is held onto by the `Writer`, as if a
+    /// [`Reader`] never dropped it, until this is disarmed and the next `push()`.
+    ///
+    /// Unlike [`Fault::ReclaimFail`], the old data is actually kept alive,
+    /// and `push_wait()` actually sleeps before cloning.
+    ReaderHold,
+    /// The `Patch` at this index panics in the next [`Writer::commit`] or [`Writer::try_commit`].
+    ///
+    /// The `Writer` is left [poisoned](Writer::is_poisoned).
+    PatchPanic(usize),
+    /// The next `push()` or [`Writer::pull`] with commits to push/revert panics, before
+    /// anything is changed, as if the thread of the `Writer` died after committing.
+    ///
+    /// If the `Writer` is owned by the panicking thread, it is dropped without pushing,
+    /// even with [`DropPolicy::Push`](crate::DropPolicy::Push).
+    WriterDeath,
+    /// [`Reader::try_into_writer`] fails, as if another `Reader` revived the `Writer` first.
+    ReviveFail,
+}
+
+/// The armed [`Fault`]'s of a [`FaultInjector`].
+#[derive(Debug, Default)]
+#[allow(clippy::struct_excessive_bools)] // 1 per `Fault`
+struct Armed {
+    /// [`Fault::ReclaimFail`].
+    reclaim_fail: bool,
+    /// [`Fault::PushWaitTimeout`].
+    push_wait_timeout: bool,
+    /// [`Fault::ReaderHold`].
+    reader_hold: bool,
+    /// [`Fault::PatchPanic`].
+    patch_panic: Option<usize>,
+    /// [`Fault::WriterDeath`].
+    writer_death: bool,
+    /// [`Fault::ReviveFail`].
+    revive_fail: bool,
+    /// How many times a `Fault` triggered.
+    triggered: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+/// Forces [`Fault`]'s in a [`Writer`] and its [`Reader`]'s
+///
+/// This is a handle, clones share the same armed `Fault`'s, so
+/// they can be armed and disarmed while the `Writer` is in use.
+///
+/// It is set with [`Writer::set_fault_injector`] (which also
+/// sets it for [`Writer::reader`]'s created afterwards),
+/// and [`Reader::set_fault_injector`].
+///
+/// ```rust
+/// # use someday::{*, testing::*};
+/// let (r, mut w) = someday::new::<usize>(0);
+/// let faults = FaultInjector::new();
+/// w.set_fault_injector(faults.clone());
+///
+/// faults.arm(Fault::ReclaimFail);
+/// w.add_commit(|w, _| *w += 1);
+/// assert!(!w.push().reclaimed);
+///
+/// faults.disarm(Fault::ReclaimFail);
+/// w.add_commit(|w, _| *w += 1);
+/// assert!(w.push().reclaimed);
+///
+/// assert_eq!(faults.triggered(), 1);
+/// ```
+///
+/// The `Writer` dying between a commit and a push:
+/// ```rust
+/// # use someday::{*, testing::*};
+/// let (_, mut w) = someday::new::<usize>(0);
+/// let faults = FaultInjector::new().with(Fault::WriterDeath);
+/// w.set_fault_injector(faults.clone());
+/// let r = w.reader();
+///
+/// let thread = std::thread::spawn(move || {
+///     w.add_commit(|w, _| *w += 1);
+///     w.push();
+///     unreachable!();
+/// });
+/// assert!(thread.join().is_err());
+///
+/// // The commit was never pushed.
+/// assert!(r.writer_dropped());
+/// assert_eq!(r.head().data, 0);
+///
+/// // The recovery path, with a `Reader` losing the race first.
+/// faults.arm(Fault::ReviveFail);
+/// let r = r.try_into_writer().unwrap_err();
+/// let w = r.try_into_writer().unwrap();
+/// assert_eq!(*w.data(), 0);
+/// ```
+pub struct FaultInjector {
+    /// The armed `Fault`'s, shared between clones.
+    armed: Arc<Mutex<Armed>>,
+}
+
+impl FaultInjector {
+    #[must_use]
+    /// Create a new [`FaultInjector`] with no armed [`Fault`]'s
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    /// [`FaultInjector::arm`] a [`Fault`], and return `self`
+    ///
+    /// ```rust
+    /// # use someday::testing::*;
+    /// let faults = FaultInjector::new()
+    ///     .with(Fault::ReclaimFail)
+    ///     .with(Fault::PatchPanic(2));
+    ///
+    /// assert!(faults.is_armed(Fault::ReclaimFail));
+    /// assert!(faults.is_armed(Fault::PatchPanic(2)));
+    /// ```
+    pub fn with(self, fault: Fault) -> Self {
+        self.arm(fault);
+        self
+    }
+
+    /// Arm a [`Fault`]
+    ///
+    /// Arming [`Fault::PatchPanic`] replaces the index of a previously armed one.
+    pub fn arm(&self, fault: Fault) {
+        self.set(fault, true);
+    }
+
+    /// Disarm a [`Fault`]
+    ///
+    /// This returns `true` if it was armed.
+    ///
+    /// [`Fault::PatchPanic`] is disarmed regardless of the index.
+    ///
+    /// ```rust
+    /// # use someday::testing::*;
+    /// let faults = FaultInjector::new().with(Fault::PatchPanic(2));
+    /// assert!(faults.disarm(Fault::PatchPanic(0)));
+    /// assert!(!faults.disarm(Fault::PatchPanic(2)));
+    /// ```
+    #[allow(clippy::must_use_candidate)] // disarming is the point
+    pub fn disarm(&self, fault: Fault) -> bool {
+        self.set(fault, false)
+    }
+
+    /// Disarm all [`Fault`]'s
+    pub fn disarm_all(&self) {
+        let mut armed = self.lock();
+        *armed = Armed {
+            triggered: armed.triggered,
+            ..Armed::default()
+        };
+    }
+
+    #[must_use]
+    /// Is this [`Fault`] armed?
+    ///
+    /// [`Fault::PatchPanic`] must have the same index.
+    pub fn is_armed(&self, fault: Fault) -> bool {
+        let armed = self.lock();
+        match fault {
+            Fault::ReclaimFail => armed.reclaim_fail,
+            Fault::PushWaitTimeout => armed.push_wait_timeout,
+            Fault::ReaderHold => armed.reader_hold,
+            Fault::PatchPanic(index) => armed.patch_panic == Some(index),
+            Fault::WriterDeath => armed.writer_death,
+            Fault::ReviveFail => armed.revive_fail,
+        }
+    }
+
+    #[must_use]
+    /// How many times [`Fault`]'s triggered
+    ///
+    /// [`Fault::ReaderHold`] triggers once per held head.
+    pub fn triggered(&self) -> usize {
+        self.lock().triggered
+    }
+
+    /// If `fault` is armed, count it as triggered, disarm it if
+    /// it only triggers once, and return `true`.
+    pub(crate) fn trigger(&self, fault: Fault) -> bool {
+        if !self.is_armed(fault) {
+            return false;
+        }
+        if matches!(
+            fault,
+            Fault::PatchPanic(_) | Fault::WriterDeath | Fault::ReviveFail
+        ) {
+            self.set(fault, false);
+        }
+        self.lock().triggered += 1;
+        true
+    }
+
+    /// Set whether `fault` is armed, returning if it was.
+    fn set(&self, fault: Fault, on: bool) -> bool {
+        let mut armed = self.lock();
+        let flag = match fault {
+            Fault::ReclaimFail => &mut armed.reclaim_fail,
+            Fault::PushWaitTimeout => &mut armed.push_wait_timeout,
+            Fault::ReaderHold => &mut armed.reader_hold,
+            Fault::WriterDeath => &mut armed.writer_death,
+            Fault::ReviveFail => &mut armed.revive_fail,
+            Fault::PatchPanic(index) => {
+                let old = armed.patch_panic.take();
+                armed.patch_panic = on.then_some(index);
+                return old.is_some();
+            }
+        };
+        let was = std::mem::replace(flag, on);
+        drop(armed);
+        was
+    }
+
+    /// Lock the armed `Fault`'s, ignoring poison (they cannot be left invalid).
+    fn lock(&self) -> MutexGuard<'_, Armed> {
+        self.armed.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The [`FaultInjector`] state of a `Writer`.
+pub(crate) struct Faults<T> {
+    /// The injector, if set.
+    pub(crate) injector: Option<FaultInjector>,
+    /// The heads held onto by [`Fault::ReaderHold`].
+    pub(crate) held: Vec<CommitRef<T>>,
+}
+
+impl<T> Faults<T> {
+    /// [`FaultInjector::trigger`], `false` if there is no injector.
+    pub(crate) fn trigger(&self, fault: Fault) -> bool {
+        self.injector
+            .as_ref()
+            .is_some_and(|injector| injector.trigger(fault))
+    }
+
+    /// [`Fault::WriterDeath`], this must only be called
+    /// if there are commits to push/revert.
+    pub(crate) fn writer_death(&self) {
+        assert!(
+            !self.trigger(Fault::WriterDeath),
+            "injected fault: the `Writer` died"
+        );
+    }
+
+    /// [`Fault::PatchPanic`], called before applying the `Patch` at `index`.
+    pub(crate) fn patch_panic(&self, index: usize) {
+        assert!(
+            !self.trigger(Fault::PatchPanic(index)),
+            "injected fault: `Patch` {index} panicked"
+        );
+    }
+
+    /// [`Fault::ReclaimFail`] and [`Fault::PushWaitTimeout`], `true` if reclaiming must fail.
+    pub(crate) fn reclaim_fail(&self, duration: Option<Duration>) -> bool {
+        self.trigger(Fault::ReclaimFail)
+            || (duration.is_some() && self.trigger(Fault::PushWaitTimeout))
+    }
+
+    /// [`Fault::ReaderHold`], called with the head replaced by a `push()`.
+    ///
+    /// If disarmed, this releases all held heads.
+    pub(crate) fn reader_hold(&mut self, old: &CommitRef<T>) {
+        if self.trigger(Fault::ReaderHold) {
+            self.held.push(Arc::clone(old));
+        } else {
+            self.held.clear();
+        }
+    }
+}
+
+impl<T> Default for Faults<T> {
+    fn default() -> Self {
+        Self {
+            injector: None,
+            held: Vec::new(),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Rng
+/// A small `SplitMix64` PRNG, good enough for generating `Op`'s.
+struct Rng(u64);
+
+impl Rng {
+    /// The next random `u64`.
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A random `usize` in `0..n`, `n` must not be `0`.
+    #[allow(
+        clippy::as_conversions,
+        clippy::cast_possible_truncation,
+        clippy::cast_lossless
+    )] // the result is less than `n`, a `usize`
+    fn below(&mut self, n: usize) -> usize {
+        // Multiply-shift instead of `%`, see:
+        // <https://lemire.me/blog/2016/06/27/a-fast-alternative-to-the-modulo-reduction>.
+        ((u128::from(self.next()) * n as u128) >> 64) as usize
+    }
+}
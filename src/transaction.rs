@@ -1,7 +1,7 @@
 //! `Writer<T>`
 
 //---------------------------------------------------------------------------------------------------- Use
-use std::{
+use core::{
     borrow::{Borrow, BorrowMut},
     ops::{Deref, DerefMut},
 };
@@ -10,6 +10,7 @@ use crate::{info::CommitInfo, patch::Patch, writer::Writer, Timestamp};
 
 #[allow(unused_imports)] // docs
 use crate::Reader;
+#[cfg(feature = "std")]
 #[allow(unused_imports)] // docs
 use std::sync::{Arc, Mutex};
 
@@ -71,6 +72,31 @@ use std::sync::{Arc, Mutex};
 /// assert_eq!(r.head().timestamp, 4);
 /// ```
 ///
+/// ## Panics
+/// If something panics while the data is mutably borrowed, the data may
+/// be half-modified, so instead of committing it, the `Transaction` is
+/// dropped leaving the [`Writer`] [poisoned](Writer::is_poisoned).
+///
+/// Without the `std` feature, panics cannot be detected,
+/// so the `Transaction` commits regardless.
+///
+/// ```rust
+/// # use someday::*;
+/// # use std::panic::*;
+/// let (r, mut w) = someday::new(Vec::<usize>::new());
+///
+/// let result = catch_unwind(AssertUnwindSafe(|| {
+///     let mut tx = w.tx();
+///     tx.push(1);
+///     panic!();
+/// }));
+/// assert!(result.is_err());
+/// assert!(w.is_poisoned());
+///
+/// w.recover().unwrap();
+/// assert!(w.data().is_empty());
+/// ```
+///
 /// ## `Transaction` vs `Patch`
 /// Using `Transaction` instead of `Patch` when you are
 /// just cloning data anyway may be preferred as it avoids:
@@ -136,13 +162,17 @@ use std::sync::{Arc, Mutex};
 /// assert_eq!(reader.head().data, "hello world!");
 /// assert_eq!(reader.head().timestamp, 4);
 /// ```
-pub struct Transaction<'writer, T: Clone> {
+pub struct Transaction<'writer, T> {
     /// TODO
     pub(crate) writer: &'writer mut Writer<T>,
     /// TODO
     pub(crate) original_timestamp: Timestamp,
     /// TODO
     pub(crate) sync_patch: Patch<T>,
+    /// If `sync_patch` is still the default `Patch::CLONE`.
+    pub(crate) clone_sync: bool,
+    /// `T::clone`, to keep the data for a custom `sync_patch`, see [`SyncBase`].
+    pub(crate) clone_fn: fn(&T) -> T,
 }
 
 impl<'writer, T: Clone> Transaction<'writer, T> {
@@ -154,9 +184,13 @@ impl<'writer, T: Clone> Transaction<'writer, T> {
             original_timestamp: writer.timestamp(),
             writer,
             sync_patch: Patch::CLONE,
+            clone_sync: true,
+            clone_fn: T::clone,
         }
     }
+}
 
+impl<T> Transaction<'_, T> {
     #[must_use]
     /// Immutably borrow the [`Writer`]'s data `T`.
     ///
@@ -177,8 +211,10 @@ impl<'writer, T: Clone> Transaction<'writer, T> {
         // Increment local timestamp assuming
         // each `deref_mut()` will actually mutate
         // the inner value.
+        let header = self.writer.new_header(None);
         let commit = self.writer.local_as_mut();
         commit.timestamp += 1;
+        commit.header = header;
 
         &mut commit.data
     }
@@ -271,6 +307,22 @@ impl<'writer, T: Clone> Transaction<'writer, T> {
     /// assert_eq!(commit_info.patches, 3);
     /// ```
     pub fn commit(self) -> CommitInfo {
+        let commit_info = self.commit_info();
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            patches = commit_info.patches,
+            timestamp_diff = commit_info.timestamp_diff,
+            "transaction commit"
+        );
+
+        commit_info
+
+        /* drop code */
+    }
+
+    /// The [`CommitInfo`] of the changes made so far.
+    fn commit_info(&self) -> CommitInfo {
         CommitInfo {
             patches: self
                 .current_timestamp()
@@ -279,8 +331,6 @@ impl<'writer, T: Clone> Transaction<'writer, T> {
                 .current_timestamp()
                 .saturating_sub(self.writer.timestamp_remote()),
         }
-
-        /* drop code */
     }
 
     /// Customize the synchronization function used by [`Transaction`].
@@ -336,8 +386,68 @@ impl<'writer, T: Clone> Transaction<'writer, T> {
     /// assert_eq!(r.head().data, "hello world!");
     /// assert_eq!(r.head().timestamp, 4);
     /// ```
+    ///
+    /// # Replaying
+    /// A custom `sync_patch` must turn the old data into the data as of this
+    /// `Transaction`, the `Patch`'s committed after it are re-applied after it.
+    ///
+    /// Its `r` is the data as of this `Transaction` as well, so both copying
+    /// `r` and redoing the `Transaction`'s changes work. If something is
+    /// committed after the `Transaction` before the next `push()`, the
+    /// `Writer` clones the data once to keep it as that `r`.
+    ///
+    /// The default [`Patch::CLONE`] is special cased, it turns the old data
+    /// into the pushed data, so the `Patch`'s committed after it are not re-applied.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w) = someday::new(String::new());
+    ///
+    /// // Copying `r`.
+    /// let mut tx = w.tx();
+    /// tx.push_str("a");
+    /// tx.sync_patch(Patch::Ptr(|w, r| w.clone_from(r)));
+    /// drop(tx);
+    /// w.add_commit(|w, _| w.push('b'));
+    /// assert!(w.push().reclaimed);
+    /// w.add_commit_push(|w, _| w.push('c'));
+    /// assert_eq!(w.data(), "abc");
+    /// assert_eq!(r.head().data, "abc");
+    ///
+    /// // Redoing the changes.
+    /// let mut tx = w.tx();
+    /// tx.push_str("d");
+    /// tx.sync_patch(Patch::Ptr(|w, _| w.push('d')));
+    /// drop(tx);
+    /// w.add_commit(|w, _| w.push('e'));
+    /// assert!(w.push().reclaimed);
+    /// w.add_commit_push(|w, _| w.push('f'));
+    /// assert_eq!(w.data(), "abcdef");
+    /// assert_eq!(r.head().data, "abcdef");
+    ///
+    /// // `add_commit_push()` right after the `Transaction`.
+    /// let mut tx = w.tx();
+    /// tx.push_str("g");
+    /// tx.sync_patch(Patch::Ptr(|w, r| w.clone_from(r)));
+    /// drop(tx);
+    /// w.add_commit_push(|w, _| w.push('h'));
+    /// w.add_commit_push(|w, _| w.push('i'));
+    /// assert_eq!(w.data(), "abcdefghi");
+    /// assert_eq!(r.head().data, "abcdefghi");
+    ///
+    /// // The default `Patch::CLONE`.
+    /// let mut tx = w.tx();
+    /// tx.push_str("j");
+    /// drop(tx);
+    /// w.add_commit(|w, _| w.push('k'));
+    /// assert!(w.push().reclaimed);
+    /// w.add_commit_push(|w, _| w.push('l'));
+    /// assert_eq!(w.data(), "abcdefghijkl");
+    /// assert_eq!(r.head().data, "abcdefghijkl");
+    /// ```
     pub fn sync_patch(&mut self, sync_patch: Patch<T>) -> Patch<T> {
-        std::mem::replace(&mut self.sync_patch, sync_patch)
+        self.clone_sync = false;
+        core::mem::replace(&mut self.sync_patch, sync_patch)
     }
 
     /// Attempt to abort the `Transaction`.
@@ -372,7 +482,12 @@ impl<'writer, T: Clone> Transaction<'writer, T> {
     /// assert!(tx.abort().is_err());
     /// ```
     pub fn abort(self) -> Result<(), Self> {
-        if self.original_timestamp == self.current_timestamp() {
+        let aborted = self.original_timestamp == self.current_timestamp();
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(aborted, "transaction abort");
+
+        if aborted {
             Ok(())
         } else {
             Err(self)
@@ -380,24 +495,63 @@ impl<'writer, T: Clone> Transaction<'writer, T> {
     }
 }
 
+//---------------------------------------------------------------------------------------------------- SyncBase
+/// The `r` a custom [`Transaction::sync_patch`] is re-applied with on reclaim.
+///
+/// A sync `Patch` like `w.clone_from(r)` expects `r` to be the data as of the
+/// `Transaction`, the pushed data already contains the `Patch`'s committed
+/// after it, which would then be applied twice.
+pub(crate) enum SyncBase<T> {
+    /// Nothing was committed after the `Transaction`, `r` is the pushed data.
+    ///
+    /// This is `T::clone`, to copy the data once something is.
+    Pending(fn(&T) -> T),
+    /// The data as of the `Transaction`.
+    Data(T),
+}
+
 //---------------------------------------------------------------------------------------------------- Drop
-impl<T: Clone> Drop for Transaction<'_, T> {
+impl<T> Drop for Transaction<'_, T> {
     fn drop(&mut self) {
+        let changed = self.original_timestamp != self.current_timestamp();
+
+        // Something panicked while the data was mutably
+        // borrowed, it may be half-modified, don't commit it.
+        if changed && crate::sync::panicking() {
+            self.writer.poisoned = true;
+            return;
+        }
+
         // If we made changes, force a `clone` commit.
-        if self.original_timestamp != self.current_timestamp() {
+        if changed {
             // Clear old patches, they don't matter
             // anymore since we are cloning regardless.
             self.writer.patches_old.clear();
 
             // Take the sync `Patch`, add it.
-            let patch = std::mem::take(&mut self.sync_patch);
+            let patch = core::mem::take(&mut self.sync_patch);
             self.writer.patches_old.push(patch);
+            self.writer.replayable = false;
+            self.writer.rebased = false;
+            self.writer.clone_sync = self.clone_sync;
+            self.writer.sync_base = (!self.clone_sync).then_some(SyncBase::Pending(self.clone_fn));
+
+            #[cfg(feature = "stats")]
+            self.writer.stats.transaction();
+            let commit_info = self.commit_info();
+            #[cfg(feature = "tracing")]
+            tracing::trace!(
+                patches = commit_info.patches,
+                timestamp_diff = commit_info.timestamp_diff,
+                "transaction drop"
+            );
+            self.writer.call_commit_hooks(&commit_info);
         }
     }
 }
 
 //---------------------------------------------------------------------------------------------------- Trait
-impl<T: Clone> Deref for Transaction<'_, T> {
+impl<T> Deref for Transaction<'_, T> {
     type Target = T;
 
     #[inline]
@@ -406,35 +560,35 @@ impl<T: Clone> Deref for Transaction<'_, T> {
     }
 }
 
-impl<T: Clone> DerefMut for Transaction<'_, T> {
+impl<T> DerefMut for Transaction<'_, T> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
         self.data_mut()
     }
 }
 
-impl<T: Clone> Borrow<T> for Transaction<'_, T> {
+impl<T> Borrow<T> for Transaction<'_, T> {
     #[inline]
     fn borrow(&self) -> &T {
         self.writer.data()
     }
 }
 
-impl<T: Clone> BorrowMut<T> for Transaction<'_, T> {
+impl<T> BorrowMut<T> for Transaction<'_, T> {
     #[inline]
     fn borrow_mut(&mut self) -> &mut T {
         self.data_mut()
     }
 }
 
-impl<T: Clone> AsRef<T> for Transaction<'_, T> {
+impl<T> AsRef<T> for Transaction<'_, T> {
     #[inline]
     fn as_ref(&self) -> &T {
         self.writer.data()
     }
 }
 
-impl<T: Clone> AsMut<T> for Transaction<'_, T> {
+impl<T> AsMut<T> for Transaction<'_, T> {
     #[inline]
     fn as_mut(&mut self) -> &mut T {
         self.data_mut()
@@ -2,23 +2,29 @@
 
 //---------------------------------------------------------------------------------------------------- Use
 use crate::{
+    apply::Apply,
+    error::{BoxError, CommitError, DivergenceError, PatchError, PoisonError},
+    index::Operate,
     info::{CommitInfo, PushInfo},
+    metadata::Metadata,
     patch::Patch,
-    writer::Writer,
+    writer::{determinism::double_apply, Writer},
 };
-use std::time::Duration;
+use core::time::Duration;
 
 #[allow(unused_imports)] // docs
-use crate::{Commit, Reader, Timestamp};
+use crate::{Commit, CommitHeader, Reader, Timestamp};
 
 //---------------------------------------------------------------------------------------------------- Writer
-impl<T: Clone> Writer<T> {
+impl<T, P: Apply<T>> Writer<T, P> {
     #[inline]
     /// Add a [`Patch`] to apply to the data `T`
     ///
     /// This does not execute the `Patch` immediately,
     /// it will only store it for later usage.
     ///
+    /// For a `Writer<T, P>`, this adds a `P` instead, see [`Apply`].
+    ///
     /// [`Commit`]-like operations are when these
     /// functions are applied to your data, e.g. [`Writer::commit()`].
     ///
@@ -36,7 +42,7 @@ impl<T: Clone> Writer<T> {
     /// w.commit();
     /// assert_eq!(w.staged().len(), 0);
     /// ```
-    pub fn add(&mut self, patch: Patch<T>) {
+    pub fn add(&mut self, patch: P) {
         self.patches.push(patch);
     }
 
@@ -95,6 +101,47 @@ impl<T: Clone> Writer<T> {
     /// assert_eq!(w.head().data, 0);
     /// ```
     pub fn commit(&mut self) -> CommitInfo {
+        self.commit_inner(None)
+    }
+
+    /// [`commit()`](Writer::commit), and attach [`Metadata`] to the new [`Commit`]
+    ///
+    /// The `Metadata` is stored in the `Commit`'s [`CommitHeader::meta`], i.e.
+    /// in [`Writer::head`] and, after a [`Writer::push`], in [`Reader::head`].
+    ///
+    /// `push()` sets [`Metadata::pushed`] to when the `Commit` was pushed (requires `std`).
+    ///
+    /// `Commit`'s created by any other operation, e.g. [`Writer::commit`]
+    /// or [`Writer::add_commit`], have no `Metadata`.
+    ///
+    /// If there are no staged [`Patch`]'s, this does nothing and `meta` is dropped.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w) = someday::new::<usize>(0);
+    ///
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.commit_with(Metadata::with_message("first"));
+    ///
+    /// let meta = w.head().header.meta.clone().unwrap();
+    /// assert_eq!(meta.message.as_deref(), Some("first"));
+    /// assert_eq!(meta.pushed, None);
+    ///
+    /// w.push();
+    /// let meta = r.head().header.meta.clone().unwrap();
+    /// assert_eq!(meta.message.as_deref(), Some("first"));
+    /// assert!(meta.pushed.is_some());
+    ///
+    /// // Later commits have no metadata.
+    /// w.add_commit(|w, _| *w += 1);
+    /// assert_eq!(w.head().header.meta, None);
+    /// ```
+    pub fn commit_with(&mut self, meta: Metadata) -> CommitInfo {
+        self.commit_inner(Some(meta))
+    }
+
+    /// Inner `commit()`, attaching `meta` to the new `Commit`.
+    fn commit_inner(&mut self, meta: Option<Metadata>) -> CommitInfo {
         let patch_len = self.patches.len();
 
         // Early return if there was nothing to do.
@@ -105,122 +152,62 @@ impl<T: Clone> Writer<T> {
             };
         }
 
-        self.local_as_mut().timestamp += 1;
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("commit", patches = patch_len).entered();
+
+        self.bump_local(meta);
+
+        self.sync_snapshot();
 
         // Apply the patches and add to the old vector.
         //
         // Pre-allocate some space for the new patches.
         self.patches_old.reserve_exact(patch_len);
 
-        for mut patch in self.patches.drain(..) {
-            patch.apply(
-                // We can't use `self.local_as_mut()` here
-                // We can't have `&mut self` and `&self`.
-                //
-                // INVARIANT: local must be initialized after push()
-                &mut self.local.as_mut().unwrap().data,
+        // If a `Patch` panics, our data is half-applied.
+        //
+        // A `Writer` that was already poisoned stays poisoned.
+        let poisoned = self.poisoned;
+        self.poisoned = true;
+
+        for (index, mut patch) in self.patches.drain(..).enumerate() {
+            // We can't use `self.local_as_mut()` here
+            // We can't have `&mut self` and `&self`.
+            //
+            #[cfg(feature = "testing")]
+            self.faults.patch_panic(index);
+
+            // INVARIANT: local must be initialized after push()
+            let local = self.local.as_mut().unwrap();
+            let ((), deterministic) = double_apply(
+                self.determinism,
+                &mut local.data,
                 &self.remote.data,
+                |w, r| {
+                    patch.apply(w, r);
+                },
+            );
+            assert!(
+                deterministic,
+                "{}",
+                DivergenceError {
+                    index: Some(index),
+                    timestamp: local.timestamp,
+                }
             );
             self.patches_old.push(patch);
         }
 
-        CommitInfo {
+        self.poisoned = poisoned;
+
+        let commit_info = CommitInfo {
             patches: patch_len,
             timestamp_diff: self.timestamp_diff(),
-        }
-    }
-
-    #[inline]
-    #[allow(clippy::missing_panics_doc)]
-    /// [`add()`](Writer::add) and [`commit()`](Writer::commit)
-    ///
-    /// This function combines `add()` and `commit()` together.
-    /// Since these actions are done together, a return value is allowed to be specified.
-    ///
-    /// This function will apply your [`Writer::staged()`] patches
-    /// first, then your input `patch`, then `commit()` them.
-    ///
-    /// # Example
-    /// If you'd like to receive a large chunk of data
-    /// from your `T` instead of throwing it away:
-    /// ```rust
-    /// # use someday::*;
-    /// // Very expensive data.
-    /// let vec = (0..100_000).map(|i| format!("{i}")).collect();
-    ///
-    /// let (_, mut w) = someday::new::<Vec<String>>(vec);
-    /// assert_eq!(w.timestamp(), 0);
-    /// assert_eq!(w.timestamp_remote(), 0);
-    ///
-    /// // Add some patches normally.
-    /// // These will be applied in `add_commit()` below.
-    /// for i in 100_000..200_000 {
-    ///     w.add(Patch::boxed(move |w: &mut Vec<String>, _| {
-    ///         w.push(format!("{i}"));
-    ///     }));
-    /// }
-    ///
-    /// let (commit_info, r) = w.add_commit(|w, _| {
-    ///     // Swap our value, and get back the strings.
-    ///     // This implicitly becomes our <Output> (Vec<String>).
-    ///     std::mem::take(w)
-    /// });
-    ///
-    /// // We got our 200,000 `String`'s back
-    /// // instead of dropping them!
-    /// let r: Vec<String> = r;
-    /// assert_eq!(r.len(), 200_000);
-    /// assert_eq!(commit_info.patches, 100_001); // 100_000 normal patches + 1 `add_commit()`
-    /// assert_eq!(commit_info.timestamp_diff, 1);
-    ///
-    /// // We got back our original strings.
-    /// for (i, string) in r.into_iter().enumerate() {
-    ///     assert_eq!(format!("{i}"), string);
-    /// }
-    ///
-    /// // And the `Patch` got applied to the `Writer`'s data,
-    /// // but hasn't been `push()`'ed yet.
-    /// assert!(w.data().is_empty());
-    /// assert_eq!(w.timestamp(), 1);
-    /// assert_eq!(w.timestamp_remote(), 0);
-    /// ```
-    ///
-    /// # Generics
-    /// The generic inputs are:
-    /// - `Patch`
-    /// - `Output`
-    ///
-    /// `Patch` is the same as [`Writer::add()`] however, it has a
-    /// `-> Output` value associated with it, this is defined by
-    /// you, using the `Output` generic.
-    ///
-    /// # Timestamp
-    /// This function will always increment the [`Writer`]'s local [`Timestamp`] by `1`.
-    pub fn add_commit<P, Output>(&mut self, mut patch: P) -> (CommitInfo, Output)
-    where
-        P: FnMut(&mut T, &T) -> Output + Send + 'static,
-    {
-        // Commit the current patches.
-        let mut commit_info = self.commit();
-
-        // `commit()` won't update the timestamp
-        // if there we no previous patches,
-        // so make sure we do that.
-        if commit_info.patches == 0 {
-            self.local_as_mut().timestamp += 1;
-            commit_info.timestamp_diff += 1;
-        }
-        // We're adding 1 more patch regardless.
-        commit_info.patches += 1;
-
-        // Commit the _input_ patch to our local data.
-        let r = patch(&mut self.local.as_mut().unwrap().data, &self.remote.data);
+        };
 
-        // Convert patch to immediately drop return value.
-        self.patches_old
-            .push(Patch::boxed(move |w, r| drop(patch(w, r))));
+        self.call_commit_hooks(&commit_info);
 
-        (commit_info, r)
+        commit_info
     }
 
     #[inline]
@@ -299,7 +286,10 @@ impl<T: Clone> Writer<T> {
     /// The `Option<Output>` is `Some` if the `Writer` reclaimed the `Reader`'s
     /// side of the data, and re-applied your `Patch` - it returns it instead
     /// of dropping it. This means that if `PushInfo`'s `reclaimed` is
-    /// `true`, this `Option<Output>` will _always_ be `Some`.
+    /// `true`, this `Option<Output>` will _always_ be `Some`, unless the
+    /// `Writer`'s data was replaced since the last push (by `overwrite()`, `just_clone()`
+    /// or a [`Transaction`](crate::Transaction) using [`Patch::CLONE`]), or `PushInfo`'s `compacted`
+    /// is `true`, as the reclaimed data is then synced without re-applying your `Patch`.
     ///
     /// # Timestamp
     /// This function will always increment the [`Writer`]'s local [`Timestamp`] by `1`.
@@ -310,11 +300,17 @@ impl<T: Clone> Writer<T> {
     where
         Patch: FnMut(&mut T, &T) -> Output,
     {
-        let (push_info, return_1, return_2, _) =
-            self.add_commit_push_inner::<false, Patch, Output, ()>(patch, None, None::<fn()>);
+        let (push_info, return_1, return_2, _) = self
+            .add_commit_push_inner::<false, Patch, Output, ()>(
+                patch,
+                None,
+                None::<fn()>,
+                self.clone_fn,
+            );
         (push_info, return_1, return_2)
     }
 
+    #[cfg(feature = "std")]
     #[inline]
     /// This is the same as [`Self::add_commit_push()`] with [`Self::push_wait()`] semantics.
     ///
@@ -356,7 +352,12 @@ impl<T: Clone> Writer<T> {
         Patch: FnMut(&mut T, &T) -> Output,
     {
         let (push_info, return_1, return_2, _) = self
-            .add_commit_push_inner::<false, Patch, Output, ()>(patch, Some(duration), None::<fn()>);
+            .add_commit_push_inner::<false, Patch, Output, ()>(
+                patch,
+                Some(duration),
+                None::<fn()>,
+                self.clone_fn,
+            );
         (push_info, return_1, return_2)
     }
 
@@ -432,14 +433,246 @@ impl<T: Clone> Writer<T> {
         Patch: FnMut(&mut T, &T) -> Output,
         F: FnOnce() -> R,
     {
-        let (push_info, return_1, return_2, r) =
-            self.add_commit_push_inner::<false, Patch, Output, R>(patch, None, Some(f));
+        let (push_info, return_1, return_2, r) = self
+            .add_commit_push_inner::<false, Patch, Output, R>(patch, None, Some(f), self.clone_fn);
         // INVARIANT: we _know_ `R` will be a `Some`
         // because we provided a `Some`. `add_commit_push_inner()`
         // will always return a Some(value).
         (push_info, return_1, return_2, r.unwrap())
     }
 
+    /// Generic function to handle all the different types of `add_commit_push`'s.
+    fn add_commit_push_inner<const CLONE: bool, Patch, Output, R>(
+        &mut self,
+        mut patch: Patch,
+        duration: Option<Duration>,
+        function: Option<impl FnOnce() -> R>,
+        clone_fn: Option<fn(&T) -> T>,
+    ) -> (PushInfo, Output, Option<Output>, Option<R>)
+    where
+        // We're never storing this `Patch` so it
+        // doesn't have to be `Send + 'static`.
+        Patch: FnMut(&mut T, &T) -> Output,
+    {
+        // Commit `Patch` to our local data.
+        self.sync_snapshot();
+        self.bump_local(None);
+        let poisoned = self.poisoned;
+        self.poisoned = true;
+        let local = self.local.as_mut().unwrap();
+        let (return_1, deterministic) = double_apply(
+            self.determinism,
+            &mut local.data,
+            &self.remote.data,
+            &mut patch,
+        );
+        assert!(
+            deterministic,
+            "{}",
+            DivergenceError {
+                index: Some(0),
+                timestamp: local.timestamp,
+            }
+        );
+        self.poisoned = poisoned;
+
+        self.call_commit_hooks(&CommitInfo {
+            patches: 1,
+            timestamp_diff: self.timestamp_diff(),
+        });
+
+        // `Patch` is not in `patches_old`, so our data cannot be
+        // re-created on top of a `share()`'ed `Writer`'s push.
+        self.replayable = false;
+        self.rebased = false;
+
+        // Push all commits so far.
+        let clone_sync = self.clone_sync;
+        let (push_info, r, old) = self.push_inner::<CLONE, R>(duration, function, clone_fn);
+
+        // If the `Writer` reclaimed data, we must re-apply
+        // since we did not push the Patch onto the `patches_old` Vec
+        // (since we want the return value).
+        //
+        // Unless a `Patch::CLONE` already synced the reclaimed
+        // data, or the `Patch`'s were compacted into a clone.
+        let poisoned = self.poisoned;
+        self.poisoned = true;
+        let return_2 = (!CLONE && push_info.reclaimed && !push_info.compacted && !clone_sync)
+            .then(|| patch(&mut self.local.as_mut().unwrap().data, &self.remote.data));
+        self.poisoned = poisoned;
+
+        // Only call hooks after our local data is fully synced.
+        self.check_reclaimed(&push_info);
+        self.call_push_hooks(&push_info, old.as_ref());
+
+        (push_info, return_1, return_2, r)
+    }
+}
+
+impl<T: Clone, P: Apply<T>> Writer<T, P> {
+    #[allow(clippy::missing_panics_doc)]
+    /// [`commit()`](Writer::commit), but abort if a [`Patch`] fails or the data is invalid
+    ///
+    /// This applies the staged `Patch`'s in order, stopping at the first
+    /// [`Patch::Try`] that returns an error, then checks the data against
+    /// the validator set with [`Writer::set_validator`].
+    ///
+    /// If everything succeeds, this is the same as [`Writer::commit`].
+    ///
+    /// If something fails, the local data is rolled back to what
+    /// it was before this function was called and a [`CommitError`]
+    /// is returned. None of the `Patch`'s are committed, they are all
+    /// kept in [`Writer::staged`], so they can be inspected, removed,
+    /// or re-committed after being fixed.
+    ///
+    /// If there is no validator and no `Patch::Try`'s
+    /// are staged, this is the same as [`Writer::commit`].
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, mut w) = someday::new::<Vec<usize>>(vec![]);
+    ///
+    /// w.add(Patch::Ptr(|w, _| w.push(0)));
+    /// w.try_add(|w, _| {
+    ///     w.push(1);
+    ///     Ok::<(), &str>(())
+    /// });
+    /// let commit_info = w.try_commit().unwrap();
+    /// assert_eq!(commit_info.patches, 2);
+    ///
+    /// w.add(Patch::Ptr(|w, _| w.push(2)));
+    /// w.try_add(|w, _| {
+    ///     if w.len() > 2 {
+    ///         return Err("too many elements");
+    ///     }
+    ///     w.push(3);
+    ///     Ok(())
+    /// });
+    /// let Err(CommitError::Patch(error)) = w.try_commit() else {
+    ///     panic!();
+    /// };
+    /// assert_eq!(error.index, 1);
+    /// assert_eq!(error.timestamp, 2);
+    /// assert_eq!(error.error.to_string(), "too many elements");
+    ///
+    /// // The data and timestamp were rolled back...
+    /// assert_eq!(*w.data(), [0, 1]);
+    /// assert_eq!(w.timestamp(), 1);
+    ///
+    /// // ...but the patches are still staged.
+    /// assert_eq!(w.staged().len(), 2);
+    /// w.staged().remove(error.index);
+    /// w.try_commit().unwrap();
+    /// assert_eq!(*w.data(), [0, 1, 2]);
+    /// ```
+    ///
+    /// ## Rollback
+    /// To roll back, the `Writer` re-applies the already committed
+    /// `Patch`'s onto [`Writer::head_remote`], or, if that isn't
+    /// possible (e.g. after [`Writer::overwrite`]), clones the data
+    /// beforehand. In either case, a failed `try_commit()` costs
+    /// more than a successful one.
+    ///
+    /// # Errors
+    /// This returns:
+    /// - [`CommitError::Poison`] if the `Writer` is [poisoned](Writer::is_poisoned)
+    /// - [`CommitError::Patch`] if a `Patch::Try` returns an error
+    /// - [`CommitError::Validate`] if the validator rejects the new data
+    /// - [`CommitError::Diverged`] if a non-deterministic `Patch` was detected, see [`Writer::enable_determinism_check`]
+    ///
+    /// # Timestamp
+    /// On success, this increments the [`Writer`]'s local [`Timestamp`]
+    /// by `1` (if there were `Patch`'s to apply), on failure it is unchanged.
+    pub fn try_commit(&mut self) -> Result<CommitInfo, CommitError> {
+        if self.is_poisoned() {
+            return Err(CommitError::Poison(PoisonError));
+        }
+
+        let patch_len = self.patches.len();
+
+        // Nothing can fail, or nothing to do.
+        if patch_len == 0
+            || (self.validator.is_none()
+                && self.determinism.is_none()
+                && !self.patches.iter().any(Apply::is_try))
+        {
+            return Ok(self.commit());
+        }
+
+        let timestamp = self.timestamp();
+        self.sync_snapshot();
+
+        // If the old patches can't re-create our
+        // data, keep a copy to roll back to.
+        let backup = if self.replayable {
+            None
+        } else {
+            Some(self.local_as_ref().clone())
+        };
+
+        self.local_as_mut().timestamp += 1;
+
+        // If a `Patch` or the validator panics, our data is half-applied.
+        self.poisoned = true;
+
+        // Apply the patches, but keep them staged until everything succeeds.
+        let mut result = Ok(());
+        for (index, patch) in self.patches.iter_mut().enumerate() {
+            #[cfg(feature = "testing")]
+            self.faults.patch_panic(index);
+
+            // INVARIANT: local must be initialized after push()
+            let local = self.local.as_mut().unwrap();
+            let (applied, deterministic) = double_apply(
+                self.determinism,
+                &mut local.data,
+                &self.remote.data,
+                |w, r| patch.try_apply(w, r),
+            );
+            if let Err(error) = applied {
+                result = Err(CommitError::Patch(PatchError {
+                    index,
+                    timestamp: local.timestamp,
+                    error,
+                }));
+                break;
+            }
+            if !deterministic {
+                result = Err(CommitError::Diverged(DivergenceError {
+                    index: Some(index),
+                    timestamp: local.timestamp,
+                }));
+                break;
+            }
+        }
+
+        if let Err(error) = result.and_then(|()| self.validate().map_err(CommitError::Validate)) {
+            match backup {
+                Some(backup) => self.local = Some(backup),
+                None => self.replay_local(timestamp),
+            }
+            self.poisoned = false;
+            return Err(error);
+        }
+
+        self.poisoned = false;
+        let header = self.new_header(None);
+        self.local_as_mut().header = header;
+
+        self.patches_old.reserve_exact(patch_len);
+        self.patches_old.append(&mut self.patches);
+
+        let commit_info = CommitInfo {
+            patches: patch_len,
+            timestamp_diff: self.timestamp_diff(),
+        };
+
+        self.call_commit_hooks(&commit_info);
+
+        Ok(commit_info)
+    }
+
     #[inline]
     /// This is the same as [`Self::add_commit_push()`] with [`Self::push_clone()`] semantics.
     ///
@@ -463,45 +696,200 @@ impl<T: Clone> Writer<T> {
     where
         Patch: FnMut(&mut T, &T) -> Output,
     {
-        let (push_info, return_1, return_2, _) =
-            self.add_commit_push_inner::<true, Patch, Output, ()>(patch, None, None::<fn()>);
+        let (push_info, return_1, return_2, _) = self
+            .add_commit_push_inner::<true, Patch, Output, ()>(
+                patch,
+                None,
+                None::<fn()>,
+                Some(T::clone),
+            );
         (push_info, return_1, return_2)
     }
+}
 
-    /// Generic function to handle all the different types of `add_commit_push`'s.
-    fn add_commit_push_inner<const CLONE: bool, Patch, Output, R>(
-        &mut self,
-        mut patch: Patch,
-        duration: Option<Duration>,
-        function: Option<impl FnOnce() -> R>,
-    ) -> (PushInfo, Output, Option<Output>, Option<R>)
+impl<T> Writer<T> {
+    #[inline]
+    /// Add a fallible [`Patch::Try`] to apply to the data `T`
+    ///
+    /// This is short-hand for `w.add(Patch::try_boxed(patch))`.
+    ///
+    /// If the `Patch` returns an error in [`Writer::try_commit`], the
+    /// commit is aborted and rolled back, see [`PatchError`].
+    ///
+    /// Non-fallible operations such as [`Writer::commit`] ignore the error.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, mut w) = someday::new::<u8>(u8::MAX);
+    ///
+    /// w.try_add(|w, _| {
+    ///     *w = w.checked_add(1).ok_or("overflow")?;
+    ///     Ok::<(), &str>(())
+    /// });
+    ///
+    /// let error = w.try_commit().unwrap_err();
+    /// assert_eq!(error.to_string(), "patch 0 of commit 1 failed: overflow");
+    /// assert_eq!(*w.data(), u8::MAX);
+    /// ```
+    pub fn try_add<P, E>(&mut self, patch: P)
     where
-        // We're never storing this `Patch` so it
-        // doesn't have to be `Send + 'static`.
-        Patch: FnMut(&mut T, &T) -> Output,
+        P: FnMut(&mut T, &T) -> Result<(), E> + Send + 'static,
+        E: Into<BoxError>,
     {
-        // Commit `Patch` to our local data.
-        self.local_as_mut().timestamp += 1;
-        let return_1 = patch(&mut self.local.as_mut().unwrap().data, &self.remote.data);
+        self.patches.push(Patch::try_boxed(patch));
+    }
 
-        // Push all commits so far.
-        let (push_info, r) = if CLONE {
-            (self.push_clone(), None)
-        } else if let Some(duration) = duration {
-            (self.push_wait(duration), None)
-        } else if let Some(function) = function {
-            let (push_info, r) = self.push_do(function);
-            (push_info, Some(r))
-        } else {
-            (self.push(), None)
-        };
+    #[inline]
+    /// Add an operation to apply to the data `T`
+    ///
+    /// This is the same as [`Writer::add`], except the
+    /// operation is a value instead of a function, see [`Operate`].
+    ///
+    /// The operation is stored in a [`Patch::Arc`] that
+    /// calls [`Operate::operate`], so it can be cheaply re-applied.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// #[derive(Clone)]
+    /// struct Counter(usize);
+    ///
+    /// impl Operate for Counter {
+    ///     type Op = usize;
+    ///     fn operate(&mut self, op: &usize) {
+    ///         self.0 += op;
+    ///     }
+    /// }
+    ///
+    /// let (r, mut w) = someday::new(Counter(0));
+    ///
+    /// w.add_op(1);
+    /// w.add_op(2);
+    /// assert_eq!(w.staged().len(), 2);
+    ///
+    /// w.commit();
+    /// w.push();
+    /// assert_eq!(w.data().0, 3);
+    /// assert_eq!(r.head().data.0, 3);
+    /// ```
+    pub fn add_op(&mut self, op: T::Op)
+    where
+        T: Operate,
+        T::Op: Send + Sync + 'static,
+    {
+        self.patches
+            .push(Patch::arc(move |w: &mut T, _| w.operate(&op)));
+    }
 
-        // If the `Writer` reclaimed data, we must re-apply
-        // since we did not push the Patch onto the `patches_old` Vec
-        // (since we want the return value).
-        let return_2 = (!CLONE && push_info.reclaimed)
-            .then(|| patch(&mut self.local.as_mut().unwrap().data, &self.remote.data));
+    #[inline]
+    #[allow(clippy::missing_panics_doc)]
+    /// [`add()`](Writer::add) and [`commit()`](Writer::commit)
+    ///
+    /// This function combines `add()` and `commit()` together.
+    /// Since these actions are done together, a return value is allowed to be specified.
+    ///
+    /// This function will apply your [`Writer::staged()`] patches
+    /// first, then your input `patch`, then `commit()` them.
+    ///
+    /// # Example
+    /// If you'd like to receive a large chunk of data
+    /// from your `T` instead of throwing it away:
+    /// ```rust
+    /// # use someday::*;
+    /// // Very expensive data.
+    /// let vec = (0..100_000).map(|i| format!("{i}")).collect();
+    ///
+    /// let (_, mut w) = someday::new::<Vec<String>>(vec);
+    /// assert_eq!(w.timestamp(), 0);
+    /// assert_eq!(w.timestamp_remote(), 0);
+    ///
+    /// // Add some patches normally.
+    /// // These will be applied in `add_commit()` below.
+    /// for i in 100_000..200_000 {
+    ///     w.add(Patch::boxed(move |w: &mut Vec<String>, _| {
+    ///         w.push(format!("{i}"));
+    ///     }));
+    /// }
+    ///
+    /// let (commit_info, r) = w.add_commit(|w, _| {
+    ///     // Swap our value, and get back the strings.
+    ///     // This implicitly becomes our <Output> (Vec<String>).
+    ///     std::mem::take(w)
+    /// });
+    ///
+    /// // We got our 200,000 `String`'s back
+    /// // instead of dropping them!
+    /// let r: Vec<String> = r;
+    /// assert_eq!(r.len(), 200_000);
+    /// assert_eq!(commit_info.patches, 100_001); // 100_000 normal patches + 1 `add_commit()`
+    /// assert_eq!(commit_info.timestamp_diff, 1);
+    ///
+    /// // We got back our original strings.
+    /// for (i, string) in r.into_iter().enumerate() {
+    ///     assert_eq!(format!("{i}"), string);
+    /// }
+    ///
+    /// // And the `Patch` got applied to the `Writer`'s data,
+    /// // but hasn't been `push()`'ed yet.
+    /// assert!(w.data().is_empty());
+    /// assert_eq!(w.timestamp(), 1);
+    /// assert_eq!(w.timestamp_remote(), 0);
+    /// ```
+    ///
+    /// # Generics
+    /// The generic inputs are:
+    /// - `Patch`
+    /// - `Output`
+    ///
+    /// `Patch` is the same as [`Writer::add()`] however, it has a
+    /// `-> Output` value associated with it, this is defined by
+    /// you, using the `Output` generic.
+    ///
+    /// # Timestamp
+    /// This function will always increment the [`Writer`]'s local [`Timestamp`] by `1`.
+    pub fn add_commit<P, Output>(&mut self, mut patch: P) -> (CommitInfo, Output)
+    where
+        P: FnMut(&mut T, &T) -> Output + Send + 'static,
+    {
+        // Commit the current patches.
+        let mut commit_info = self.commit();
 
-        (push_info, return_1, return_2, r)
+        // `commit()` won't update the timestamp
+        // if there we no previous patches,
+        // so make sure we do that.
+        if commit_info.patches == 0 {
+            self.bump_local(None);
+            commit_info.timestamp_diff += 1;
+        }
+        // We're adding 1 more patch regardless.
+        commit_info.patches += 1;
+
+        // Commit the _input_ patch to our local data.
+        self.sync_snapshot();
+        let poisoned = self.poisoned;
+        self.poisoned = true;
+        let local = self.local.as_mut().unwrap();
+        let (r, deterministic) = double_apply(
+            self.determinism,
+            &mut local.data,
+            &self.remote.data,
+            &mut patch,
+        );
+        assert!(
+            deterministic,
+            "{}",
+            DivergenceError {
+                index: Some(commit_info.patches - 1),
+                timestamp: local.timestamp,
+            }
+        );
+        self.poisoned = poisoned;
+
+        // Convert patch to immediately drop return value.
+        self.patches_old
+            .push(Patch::boxed(move |w, r| drop(patch(w, r))));
+
+        self.call_commit_hooks(&commit_info);
+
+        (commit_info, r)
     }
 }
@@ -0,0 +1,140 @@
+//! `Writer<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::string::String;
+
+use crate::{apply::Apply, info::PushInfo, writer::Writer};
+
+#[allow(unused_imports)] // docs
+use crate::{Commit, Reader};
+
+//---------------------------------------------------------------------------------------------------- WriterState
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// The state of a [`Writer`], as seen by its [`Reader`]'s
+///
+/// See [`Reader::state`].
+pub enum WriterState {
+    /// The `Writer` is alive.
+    Open,
+    /// The `Writer` finished with [`Writer::close`], this is the reason given.
+    Closed(String),
+    /// The `Writer` was dropped (or [`Writer::disconnect`]'ed) without [`Writer::close`].
+    Abandoned,
+}
+
+//---------------------------------------------------------------------------------------------------- DropPolicy
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// What a [`Writer`] does with unpushed [`Commit`]'s when dropped
+///
+/// See [`Writer::set_drop_policy`].
+///
+/// The policy does not apply if the `Writer` has no unpushed `Commit`'s,
+/// is [poisoned](Writer::is_poisoned), or is dropped while panicking.
+pub enum DropPolicy {
+    #[default]
+    /// Discard the unpushed `Commit`'s.
+    Discard,
+    /// [`Writer::push`] the unpushed `Commit`'s.
+    ///
+    /// If a [`Writer::share`]'ed `Writer` pushed first and the `Commit`'s
+    /// cannot be re-applied, they are discarded, see [`PushInfo::conflict`].
+    Push,
+    /// Panic in debug builds (with `debug_assertions`), same as [`DropPolicy::Discard`] otherwise.
+    Panic,
+}
+
+//---------------------------------------------------------------------------------------------------- Writer
+impl<T, P: Apply<T>> Writer<T, P> {
+    /// Push the remaining [`Commit`]'s and close the [`Writer`] with a `reason`
+    ///
+    /// Unlike dropping the `Writer`, which [`Reader`]'s see as
+    /// [`WriterState::Abandoned`], this makes them see [`WriterState::Closed`]
+    /// with `reason`, so that they can tell a crashed `Writer` apart from
+    /// a `Writer` that finished.
+    ///
+    /// Staged `Patch`'s that were not [`commit()`](Writer::commit)'ed are discarded.
+    ///
+    /// This returns the [`PushInfo`] of the final push, if a [`Writer::share`]'ed
+    /// `Writer` pushed first, the remaining `Commit`'s are discarded, see [`PushInfo::conflict`].
+    ///
+    /// With [`Writer::share`], the state is only `Closed` if
+    /// this is the last `Writer`, and only with this `reason`.
+    ///
+    /// ## Panics
+    /// This panics if the `Writer` is [poisoned](Writer::is_poisoned),
+    /// like [`Writer::push`], in which case it is `Abandoned`.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w) = someday::new::<usize>(0);
+    /// assert_eq!(r.state(), WriterState::Open);
+    ///
+    /// w.add_commit(|w, _| *w += 1);
+    /// let push_info = w.close("done");
+    /// assert_eq!(push_info.commits, 1);
+    ///
+    /// assert_eq!(r.head().data, 1);
+    /// assert_eq!(r.state(), WriterState::Closed("done".into()));
+    /// ```
+    pub fn close<S: Into<String>>(mut self, reason: S) -> PushInfo {
+        let push_info = self.push();
+        self.token.close(reason.into());
+        push_info
+    }
+
+    /// Set what happens to unpushed [`Commit`]'s when the [`Writer`] is dropped
+    ///
+    /// The default is [`DropPolicy::Discard`].
+    ///
+    /// [`Writer::close`] always pushes, regardless of the policy.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w) = someday::new::<usize>(0);
+    /// w.set_drop_policy(DropPolicy::Push);
+    ///
+    /// w.add_commit(|w, _| *w += 1);
+    /// drop(w);
+    ///
+    /// // The commit was pushed on drop.
+    /// assert_eq!(r.head().data, 1);
+    /// assert_eq!(r.state(), WriterState::Abandoned);
+    /// ```
+    ///
+    /// ```rust,should_panic
+    /// # use someday::*;
+    /// let (r, mut w) = someday::new::<usize>(0);
+    /// w.set_drop_policy(DropPolicy::Panic);
+    ///
+    /// w.add_commit(|w, _| *w += 1);
+    /// drop(w); // panics in debug builds
+    /// # if !cfg!(debug_assertions) { panic!() }
+    /// ```
+    pub fn set_drop_policy(&mut self, drop_policy: DropPolicy) {
+        self.drop_policy = drop_policy;
+    }
+
+    #[must_use]
+    /// The current [`DropPolicy`]
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, w) = someday::new(());
+    /// assert_eq!(w.drop_policy(), DropPolicy::Discard);
+    /// ```
+    pub const fn drop_policy(&self) -> DropPolicy {
+        self.drop_policy
+    }
+}
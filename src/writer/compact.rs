@@ -0,0 +1,221 @@
+//! `Writer<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+#[cfg(feature = "std")]
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use crate::{apply::Apply, transaction::SyncBase, writer::Writer};
+
+#[allow(unused_imports)] // docs
+use crate::{Patch, PushInfo, Reader};
+
+//---------------------------------------------------------------------------------------------------- CompactPolicy
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// When a [`Writer`] compacts its committed [`Patch`]'s into a clone
+///
+/// After a `push()` reclaims the old [`Reader`] data, the committed
+/// `Patch`'s are re-applied to it. If many `Patch`'s were committed,
+/// this can be slower than cloning the pushed data.
+///
+/// Compacting replaces the committed `Patch`'s with a single clone
+/// of the pushed data, i.e. what [`Writer::just_clone`] does manually,
+/// [`PushInfo::compacted`] reports if this happened.
+///
+/// `Writer`'s that cannot clone (see [`crate::new_with`]) never compact.
+///
+/// See [`Writer::set_compact_policy`].
+///
+/// ```rust
+/// # use someday::*;
+/// let (r, mut w) = someday::new::<usize>(0);
+/// w.set_compact_policy(CompactPolicy::Measure);
+///
+/// // Re-applying a `Patch` gets measured...
+/// w.add(Patch::Ptr(|w, _| *w += 1));
+/// w.commit();
+/// assert!(!w.push().compacted);
+///
+/// // ...so re-applying 100,000 of them is
+/// // estimated to be slower than cloning a `usize`.
+/// for _ in 0..100_000 {
+///     w.add(Patch::Ptr(|w, _| *w += 1));
+/// }
+/// w.commit();
+/// assert!(w.push().compacted);
+///
+/// assert_eq!(*w.data(), 100_001);
+/// assert_eq!(r.head().data, 100_001);
+/// ```
+pub enum CompactPolicy {
+    #[default]
+    /// Never compact, always re-apply the `Patch`'s.
+    Never,
+    /// Compact if more than this many `Patch`'s were committed.
+    Limit(usize),
+    /// Compact if re-applying the `Patch`'s is estimated to be slower than cloning.
+    ///
+    /// The `Writer` times how long re-applying a `Patch` takes on average, and
+    /// how long cloning takes, then compacts if `Patch` count * average `Patch`
+    /// time is greater than the clone time. Both are re-measured when they happen.
+    ///
+    /// This requires the `std` feature. Without it, nothing can be measured
+    /// and this is the same as [`CompactPolicy::Never`], i.e. the committed
+    /// `Patch`'s are always re-applied.
+    Measure,
+}
+
+//---------------------------------------------------------------------------------------------------- Costs
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, Default)]
+/// The latest measured costs for [`CompactPolicy::Measure`].
+pub(crate) struct Costs {
+    /// How long re-applying a single `Patch` took on average.
+    replay: Option<Duration>,
+    /// How long cloning the data took.
+    clone: Option<Duration>,
+}
+
+//---------------------------------------------------------------------------------------------------- Writer
+impl<T, P: Apply<T>> Writer<T, P> {
+    /// Set the [`CompactPolicy`] used after a `push()` reclaims data
+    ///
+    /// Setting [`CompactPolicy::Measure`] clones the data once to measure how long it takes.
+    ///
+    /// Without the `std` feature there is no clock to measure with, so
+    /// `CompactPolicy::Measure` never compacts, use [`CompactPolicy::Limit`] instead.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w) = someday::new::<usize>(0);
+    /// w.set_compact_policy(CompactPolicy::Limit(2));
+    ///
+    /// // 2 `Patch`'s are re-applied.
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.commit();
+    /// let push_info = w.push();
+    /// assert!(push_info.reclaimed);
+    /// assert!(!push_info.compacted);
+    ///
+    /// // 3 `Patch`'s are compacted into a clone.
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.commit();
+    /// let push_info = w.push();
+    /// assert!(push_info.reclaimed);
+    /// assert!(push_info.compacted);
+    ///
+    /// assert_eq!(*w.data(), 5);
+    /// assert_eq!(r.head().data, 5);
+    /// ```
+    pub fn set_compact_policy(&mut self, compact: CompactPolicy) {
+        self.compact = compact;
+
+        #[cfg(feature = "std")]
+        if let (CompactPolicy::Measure, Some(clone_fn)) = (compact, self.clone_fn) {
+            let start = Instant::now();
+            drop(clone_fn(&self.remote.data));
+            self.costs.clone = Some(start.elapsed());
+        }
+    }
+
+    #[must_use]
+    /// The current [`CompactPolicy`]
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, w) = someday::new(());
+    /// assert_eq!(w.compact_policy(), CompactPolicy::Never);
+    /// ```
+    pub const fn compact_policy(&self) -> CompactPolicy {
+        self.compact
+    }
+
+    /// Turn reclaimed `data` into the pushed data.
+    ///
+    /// This re-applies the committed `Patch`'s, or clones
+    /// the pushed data with `clone_fn` if the [`CompactPolicy`]
+    /// says so, in which case this returns `true`.
+    pub(crate) fn sync_reclaimed(&mut self, data: &mut T, clone_fn: Option<fn(&T) -> T>) -> bool {
+        // A `Patch::CLONE` already turns it into the pushed
+        // data, so the rest must not be re-applied after it.
+        let len = if self.clone_sync {
+            1
+        } else {
+            self.patches_old.len()
+        };
+        let compact = clone_fn.filter(|_| !self.clone_sync && self.compact_now(len));
+
+        #[cfg(feature = "std")]
+        let start =
+            (self.compact == CompactPolicy::Measure || cfg!(feature = "stats")).then(Instant::now);
+
+        // A custom `Transaction::sync_patch` gets the data as of the `Transaction`.
+        let sync_base = match self.sync_base.take() {
+            Some(SyncBase::Data(base)) => Some(base),
+            Some(SyncBase::Pending(_)) | None => None,
+        };
+
+        if let Some(clone_fn) = compact {
+            *data = clone_fn(&self.remote.data);
+            self.patches_old.clear();
+        } else {
+            for (index, mut patch) in self.patches_old.drain(..).take(len).enumerate() {
+                let r = match (index, &sync_base) {
+                    (0, Some(base)) => base,
+                    _ => &self.remote.data,
+                };
+                patch.apply(data, r);
+            }
+        }
+
+        #[cfg(feature = "std")]
+        if let Some(start) = start {
+            let elapsed = start.elapsed();
+            #[cfg(feature = "stats")]
+            if compact.is_none() {
+                self.stats.replay(elapsed);
+            }
+
+            match (self.compact, compact) {
+                (CompactPolicy::Measure, Some(_)) => self.costs.clone = Some(elapsed),
+                (CompactPolicy::Measure, None) => {
+                    // Keep the previous measurement if nothing was re-applied.
+                    let replay = u32::try_from(len).ok().and_then(|n| elapsed.checked_div(n));
+                    self.costs.replay = replay.or(self.costs.replay);
+                }
+                // Only measured for the `stats`.
+                _ => (),
+            }
+        }
+
+        compact.is_some()
+    }
+
+    /// If `len` committed `Patch`'s should be compacted.
+    fn compact_now(&self, len: usize) -> bool {
+        match self.compact {
+            CompactPolicy::Never => false,
+            CompactPolicy::Limit(limit) => len > limit,
+            #[cfg(feature = "std")]
+            CompactPolicy::Measure => match (self.costs.replay, self.costs.clone) {
+                (Some(replay), Some(clone)) => {
+                    u32::try_from(len).map_or(true, |len| replay.saturating_mul(len) > clone)
+                }
+                _ => false,
+            },
+            // No clock to measure with, see `CompactPolicy::Measure`.
+            #[cfg(not(feature = "std"))]
+            CompactPolicy::Measure => false,
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! `Writer<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use crate::{apply::Apply, error::DivergenceError, info::PushInfo, writer::Writer};
+
+#[allow(unused_imports)] // docs
+use crate::{CommitError, Patch, Reader, Transaction};
+
+//---------------------------------------------------------------------------------------------------- Writer
+impl<T, P: Apply<T>> Writer<T, P> {
+    /// Check that [`Patch`]'s are deterministic
+    ///
+    /// The `Writer` applies `Patch`'s twice, once when committing, and
+    /// once again onto the old [`Reader`] data when reclaiming it in [`Writer::push`].
+    /// If a `Patch` (or [`Transaction::sync_patch`]) is not deterministic, the
+    /// `Writer` and `Reader` data silently diverge, see [`Patch`].
+    ///
+    /// After this is called, the `Writer` checks for this:
+    /// - When committing, each `Patch` is applied twice (onto the data and a copy of it),
+    ///   if the results differ, [`Writer::commit`] panics with a [`DivergenceError`] that
+    ///   reports the index of the `Patch` and the [`Timestamp`](crate::Timestamp) of the commit,
+    ///   [`Writer::try_commit`] rolls back and returns [`CommitError::Diverged`] instead
+    /// - When `push()` reclaims data, it is compared with the data just pushed,
+    ///   if they differ, the `Writer`'s data is replaced with a copy of the
+    ///   pushed data and `push()` panics with a `DivergenceError`
+    ///
+    /// This is meant for debugging and testing, it clones the data `T`
+    /// for every committed `Patch` and compares the entire data on every `push()`.
+    ///
+    /// This is carried over to [`Writer::share`]'d `Writer`'s, but not [`Writer::fork`]'s.
+    ///
+    /// ```rust,should_panic
+    /// # use someday::*;
+    /// # use std::sync::*;
+    /// static STATE: Mutex<usize> = Mutex::new(1);
+    ///
+    /// let (_, mut w) = someday::new::<usize>(0);
+    /// w.enable_determinism_check();
+    ///
+    /// w.add(Patch::boxed(move |w, _| {
+    ///     let mut state = STATE.lock().unwrap();
+    ///     *state *= 10;
+    ///     *w = *state;
+    /// }));
+    ///
+    /// // panics: patch 0 of commit 1 is not deterministic
+    /// w.commit();
+    /// ```
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::sync::*;
+    /// static STATE: Mutex<usize> = Mutex::new(1);
+    ///
+    /// let (_, mut w) = someday::new::<usize>(0);
+    /// w.enable_determinism_check();
+    ///
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.add(Patch::boxed(move |w, _| {
+    ///     let mut state = STATE.lock().unwrap();
+    ///     *state *= 10;
+    ///     *w = *state;
+    /// }));
+    ///
+    /// let Err(CommitError::Diverged(error)) = w.try_commit() else {
+    ///     panic!();
+    /// };
+    /// assert_eq!(error.index, Some(1));
+    /// assert_eq!(error.timestamp, 1);
+    ///
+    /// // Rolled back.
+    /// assert_eq!(w.timestamp(), 0);
+    /// assert_eq!(w.staged().len(), 2);
+    /// ```
+    ///
+    /// ```rust,should_panic
+    /// # use someday::*;
+    /// let (_, mut w) = someday::new(String::new());
+    /// w.enable_determinism_check();
+    ///
+    /// let mut tx = w.tx();
+    /// tx.push_str("hello");
+    /// // This does not sync the `Reader` data.
+    /// tx.sync_patch(Patch::Ptr(|_, _| {}));
+    /// drop(tx);
+    ///
+    /// // panics: the reclaimed data diverged
+    /// w.push();
+    /// ```
+    pub fn enable_determinism_check(&mut self)
+    where
+        T: Clone + PartialEq,
+    {
+        self.determinism = Some(Determinism {
+            eq: <T as PartialEq>::eq,
+            clone: <T as Clone>::clone,
+        });
+    }
+
+    /// Stop checking that [`Patch`]'s are deterministic.
+    ///
+    /// This returns `true` if the check was enabled.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, mut w) = someday::new::<usize>(0);
+    /// assert!(!w.disable_determinism_check());
+    ///
+    /// w.enable_determinism_check();
+    /// assert!(w.disable_determinism_check());
+    /// ```
+    pub fn disable_determinism_check(&mut self) -> bool {
+        self.determinism.take().is_some()
+    }
+
+    /// Compare reclaimed `local` data with the just pushed `remote` data.
+    ///
+    /// If enabled and they differ, `local` is replaced with
+    /// a copy of `remote` so the divergence does not propagate,
+    /// then this panics.
+    ///
+    /// This must be called after `local` is fully synced, before the push hooks.
+    pub(crate) fn check_reclaimed(&mut self, push_info: &PushInfo) {
+        let Some(determinism) = self.determinism else {
+            return;
+        };
+        if !push_info.reclaimed {
+            return;
+        }
+
+        if (determinism.eq)(&self.local_as_ref().data, &self.remote.data) {
+            return;
+        }
+
+        // The rest of `local` was already synced with `remote`.
+        self.local_as_mut().data = (determinism.clone)(&self.remote.data);
+        let error = DivergenceError {
+            index: None,
+            timestamp: self.remote.timestamp,
+        };
+        panic!("{error}");
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Determinism
+/// `T`'s `PartialEq::eq` and `Clone::clone`, see [`Writer::enable_determinism_check`].
+///
+/// These are captured when enabled, so that `T: Clone`
+/// is not required for the rest of the `Writer`.
+pub(crate) struct Determinism<T> {
+    /// `<T as PartialEq>::eq`.
+    pub(crate) eq: fn(&T, &T) -> bool,
+    /// `<T as Clone>::clone`.
+    pub(crate) clone: fn(&T) -> T,
+}
+
+impl<T> Clone for Determinism<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Determinism<T> {}
+
+//---------------------------------------------------------------------------------------------------- Free
+/// Apply `patch` onto `writer`, and if `determinism` is `Some`, apply
+/// it again onto a copy of the original `writer` and compare the results.
+///
+/// This returns the output of the 1st apply, and `false` if the results differ.
+pub(crate) fn double_apply<T, O>(
+    determinism: Option<Determinism<T>>,
+    writer: &mut T,
+    reader: &T,
+    mut patch: impl FnMut(&mut T, &T) -> O,
+) -> (O, bool) {
+    let Some(determinism) = determinism else {
+        return (patch(writer, reader), true);
+    };
+
+    let mut copy = (determinism.clone)(writer);
+    let output = patch(writer, reader);
+    drop(patch(&mut copy, reader));
+
+    (output, (determinism.eq)(writer, &copy))
+}
@@ -0,0 +1,115 @@
+//! `Writer<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::vec::Vec;
+
+use crate::{apply::Apply, commit::Commit, digest::Digest, writer::Writer};
+
+#[allow(unused_imports)] // docs
+use crate::{CommitHeader, Reader};
+
+//---------------------------------------------------------------------------------------------------- Writer
+impl<T, P: Apply<T>> Writer<T, P> {
+    /// Compute a [`Digest`] for every pushed [`Commit`]
+    ///
+    /// After this is called, every [`Writer::push`] that pushes new
+    /// `Commit`'s will compute the `Digest` of the new head `Commit`
+    /// _before_ [`Reader`]'s can see it, chained with the current head's
+    /// digest, and stores it in the `Commit`'s [`CommitHeader::digest`], see [`Commit::id`].
+    ///
+    /// If the current head has no `Digest` and is not the first `Commit`
+    /// (with [`Timestamp`](crate::Timestamp) `0`), its history is unknown,
+    /// so no `Digest`'s are computed until the `Writer` is re-created
+    /// from a `Commit` that has one, see [`from_commit`](crate::from_commit).
+    ///
+    /// `encode` turns the data `T` into bytes, it must be deterministic,
+    /// e.g. one of the existing (`serde`, `bincode`, `borsh`) encodings.
+    /// The same function must be used to [`Commit::verify`] the `Commit`'s.
+    ///
+    /// Note that this encodes and hashes the entire data `T` on every `push()`.
+    ///
+    /// [`Writer::fork`]'s do not compute `Digest`'s unless this is called on them too.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// fn encode(data: &Vec<usize>) -> Vec<u8> {
+    ///     borsh::to_vec(data).unwrap()
+    /// }
+    ///
+    /// let (r, mut w) = someday::new::<Vec<usize>>(vec![]);
+    /// w.enable_digest(encode);
+    ///
+    /// w.add_commit_push(|w, _| w.push(0));
+    /// let head_1 = r.head();
+    /// let digest_1 = head_1.header.digest.unwrap();
+    /// // Chained onto the first `Commit`.
+    /// let root = Digest::new([0; 32], 0, &encode(&vec![]));
+    /// assert_eq!(digest_1.parent, root.hash);
+    /// assert!(head_1.verify(encode));
+    ///
+    /// w.add_commit_push(|w, _| w.push(1));
+    /// let head_2 = r.head();
+    /// let digest_2 = head_2.header.digest.unwrap();
+    /// assert_eq!(digest_2.parent, digest_1.hash);
+    /// assert!(head_2.verify(encode));
+    ///
+    /// // A replica with identical history has identical IDs.
+    /// let (r2, mut w2) = someday::new::<Vec<usize>>(vec![]);
+    /// w2.enable_digest(encode);
+    /// w2.add_commit_push(|w, _| w.push(0));
+    /// w2.add_commit_push(|w, _| w.push(1));
+    /// assert_eq!(r2.head().id(), head_2.id());
+    ///
+    /// // The local head changed, it has no ID until pushed.
+    /// assert_eq!(w.head().id(), head_2.id());
+    /// w.add_commit(|w, _| w.push(2));
+    /// assert_eq!(w.head().id(), None);
+    ///
+    /// // The history of this head is unknown, so it can't be chained onto.
+    /// let (r3, mut w3) = someday::new::<Vec<usize>>(vec![]);
+    /// w3.add_commit_push(|w, _| w.push(0));
+    /// w3.enable_digest(encode);
+    /// w3.add_commit_push(|w, _| w.push(1));
+    /// assert_eq!(r3.head().header.digest, None);
+    /// ```
+    pub fn enable_digest(&mut self, encode: fn(&T) -> Vec<u8>) {
+        self.encode = Some(encode);
+    }
+
+    /// Stop computing [`Digest`]'s for pushed [`Commit`]'s.
+    ///
+    /// This returns `true` if digests were enabled.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w) = someday::new::<usize>(0);
+    /// assert!(!w.disable_digest());
+    ///
+    /// w.enable_digest(|n| n.to_le_bytes().to_vec());
+    /// assert!(w.disable_digest());
+    ///
+    /// w.add_commit_push(|w, _| *w += 1);
+    /// assert_eq!(r.head().header.digest, None);
+    /// ```
+    pub fn disable_digest(&mut self) -> bool {
+        self.encode.take().is_some()
+    }
+
+    /// Set the [`Digest`] of `local` (if enabled), chained onto the current head's.
+    ///
+    /// If the head has no `Digest` and is not the first `Commit`, its
+    /// history is unknown, so `local` gets no `Digest` either.
+    pub(crate) fn set_digest(&self, local: &mut Commit<T>) {
+        local.header.digest = self.encode.and_then(|encode| {
+            let parent = match self.remote.id() {
+                Some(id) => id,
+                // The first `Commit` has no history.
+                None if self.remote.timestamp == 0 => {
+                    Digest::new([0; 32], 0, &encode(&self.remote.data)).hash
+                }
+                None => return None,
+            };
+            Some(Digest::new(parent, local.timestamp, &encode(&local.data)))
+        });
+    }
+}
@@ -0,0 +1,74 @@
+//! `Writer<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use crate::{apply::Apply, testing::FaultInjector, writer::Writer};
+
+#[allow(unused_imports)] // docs
+use crate::{testing::Fault, Reader};
+
+//---------------------------------------------------------------------------------------------------- Writer
+impl<T, P: Apply<T>> Writer<T, P> {
+    /// Force [`Fault`]'s in this [`Writer`] with a [`FaultInjector`]
+    ///
+    /// This replaces the previous `FaultInjector`.
+    ///
+    /// [`Writer::reader`]'s created after this and [`Writer::share`]'d
+    /// `Writer`'s use the same `FaultInjector`, [`Writer::fork`]'s do not.
+    ///
+    /// ```rust
+    /// # use someday::{*, testing::*};
+    /// let (_, mut w) = someday::new::<usize>(0);
+    /// w.set_fault_injector(FaultInjector::new().with(Fault::PatchPanic(1)));
+    ///
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    ///
+    /// let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| w.commit()));
+    /// assert!(result.is_err());
+    ///
+    /// // The 1st `Patch` was applied, the 2nd was not.
+    /// assert!(w.is_poisoned());
+    /// w.recover().unwrap();
+    /// assert_eq!(*w.data(), 0);
+    /// ```
+    ///
+    /// A [`Reader`] holding onto old data forever:
+    /// ```rust
+    /// # use someday::{*, testing::*};
+    /// # use std::time::*;
+    /// let (_, mut w) = someday::new::<usize>(0);
+    /// let faults = FaultInjector::new().with(Fault::ReaderHold);
+    /// w.set_fault_injector(faults.clone());
+    ///
+    /// w.add_commit(|w, _| *w += 1);
+    /// let push_info = w.push_wait(Duration::from_millis(1));
+    /// assert!(!push_info.reclaimed);
+    ///
+    /// // Released on the next `push()`.
+    /// faults.disarm(Fault::ReaderHold);
+    /// w.add_commit(|w, _| *w += 1);
+    /// assert!(w.push().reclaimed);
+    /// ```
+    pub fn set_fault_injector(&mut self, injector: FaultInjector) {
+        self.faults.injector = Some(injector);
+    }
+
+    /// Remove the [`FaultInjector`] set with [`Writer::set_fault_injector`]
+    ///
+    /// This also drops the heads held with [`Fault::ReaderHold`].
+    ///
+    /// This returns `true` if there was a `FaultInjector`.
+    ///
+    /// ```rust
+    /// # use someday::{*, testing::*};
+    /// let (_, mut w) = someday::new::<usize>(0);
+    /// assert!(!w.clear_fault_injector());
+    ///
+    /// w.set_fault_injector(FaultInjector::new());
+    /// assert!(w.clear_fault_injector());
+    /// ```
+    pub fn clear_fault_injector(&mut self) -> bool {
+        self.faults.held.clear();
+        self.faults.injector.take().is_some()
+    }
+}
@@ -1,15 +1,20 @@
 //! `Writer<T>`
 
 //---------------------------------------------------------------------------------------------------- Use
-use std::sync::Arc;
+use alloc::sync::Arc;
 
-use crate::{writer::token::WriterToken, writer::Writer};
+use crate::{
+    apply::Apply,
+    sync::ArcSwap,
+    writer::token::WriterToken,
+    writer::{ForkBase, Writer},
+};
 
 #[allow(unused_imports)] // docs
-use crate::{Patch, Reader};
+use crate::{Commit, Patch, Reader};
 
 //---------------------------------------------------------------------------------------------------- Writer
-impl<T: Clone> Writer<T> {
+impl<T: Clone, P: Apply<T>> Writer<T, P> {
     #[must_use]
     #[allow(clippy::missing_panics_doc)]
     /// Fork off from the current [`Reader::head`] commit and create a [`Writer`].
@@ -18,6 +23,8 @@ impl<T: Clone> Writer<T> {
     /// - will contain no [`Patch`]'s
     /// - is disconnected, meaning it has absolutely no
     /// relation to `self` or any other previous `Reader`'s.
+    /// - remembers the forked from [`Commit`], see [`Writer::merge_from`]
+    /// - continues in a new [`Lineage`](crate::Lineage) (if lineages are being tracked)
     /// - has the latest [`Writer::head`] as the base for `Writer` and `Reader`'s
     ///
     /// ```rust
@@ -62,17 +69,18 @@ impl<T: Clone> Writer<T> {
     /// assert_eq!(r.head().timestamp, 0);
     /// ```
     pub fn fork(&self) -> Self {
-        let local = self.local.as_ref().unwrap().clone();
+        let mut local = self.local.as_ref().unwrap().clone();
+        local.header.lineage = self.lineage.map(|l| l.branch(local.timestamp));
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(timestamp = local.timestamp, "fork");
+
         let remote = Arc::new(local.clone());
-        let arc = Arc::new(arc_swap::ArcSwap::new(Arc::clone(&remote)));
+        let arc = Arc::new(ArcSwap::new(Arc::clone(&remote)));
+        let fork_base = Some(ForkBase::new(&self.arc, Arc::clone(&remote)));
 
-        Self {
-            token: WriterToken::new(),
-            local: Some(local),
-            remote,
-            arc,
-            patches: Vec::with_capacity(self.patches.capacity()),
-            patches_old: Vec::with_capacity(self.patches_old.capacity()),
-        }
+        let mut fork = Self::from_parts(WriterToken::new(), local, remote, arc, Some(T::clone));
+        fork.fork_base = fork_base;
+        fork
     }
 }
@@ -1,22 +1,23 @@
 //! `Writer<T>`
 
 //---------------------------------------------------------------------------------------------------- Use
-use std::{num::NonZeroUsize, sync::Arc};
+use alloc::{sync::Arc, vec::Vec};
+use core::num::NonZeroUsize;
 
 use crate::{
+    apply::Apply,
     commit::{Commit, CommitRef},
     info::StatusInfo,
-    patch::Patch,
     reader::Reader,
     transaction::Transaction,
     writer::Writer,
 };
 
 #[allow(unused_imports)] // docs
-                         // use crate::Commit;
+use crate::Patch;
 
 //---------------------------------------------------------------------------------------------------- Writer
-impl<T: Clone> Writer<T> {
+impl<T, P: Apply<T>> Writer<T, P> {
     #[inline]
     /// Cheaply construct a [`Reader`] connected to this [`Writer`]
     ///
@@ -37,6 +38,8 @@ impl<T: Clone> Writer<T> {
             arc: Arc::clone(&self.arc),
             token: self.token.clone(),
             cache: None,
+            #[cfg(feature = "testing")]
+            faults: self.faults.injector.clone(),
         }
     }
 
@@ -74,21 +77,6 @@ impl<T: Clone> Writer<T> {
         &self.local_as_ref().data
     }
 
-    /// Mutate the [`Writer`]'s local data _without_ going through a [`Patch`].
-    ///
-    /// This function gives you _direct_ access to the
-    /// underlying local `T` via a [`Transaction`].
-    ///
-    /// In order to prevent out-of-sync situations, `Transaction` will
-    /// unconditionally add a `Patch` that clones data after it has been [`drop`]'ed.
-    ///
-    /// This is cheaper than `Patch` if you had already planned to clone data anyway.
-    ///
-    /// See `Transaction` for more details.
-    pub fn tx(&mut self) -> Transaction<'_, T> {
-        Transaction::new(self)
-    }
-
     #[inline]
     /// View the latest copy of data [`Reader`]'s have access to
     ///
@@ -230,7 +218,7 @@ impl<T: Clone> Writer<T> {
     /// let removed = w.staged().remove(0);
     /// assert_eq!(w.staged().len(), 0);
     /// ```
-    pub fn staged(&mut self) -> &mut Vec<Patch<T>> {
+    pub fn staged(&mut self) -> &mut Vec<P> {
         &mut self.patches
     }
 
@@ -253,7 +241,7 @@ impl<T: Clone> Writer<T> {
     /// // We can see but not mutate functions.
     /// assert_eq!(w.committed_patches().len(), 1);
     /// ```
-    pub const fn committed_patches(&self) -> &Vec<Patch<T>> {
+    pub const fn committed_patches(&self) -> &Vec<P> {
         &self.patches_old
     }
 
@@ -373,7 +361,7 @@ impl<T: Clone> Writer<T> {
     ///
     /// If you only need 1 or a few of the fields in [`StatusInfo`],
     /// consider using their individual methods instead.
-    pub fn status(&self) -> StatusInfo<'_, T> {
+    pub fn status(&self) -> StatusInfo<'_, T, P> {
         StatusInfo {
             staged_patches: &self.patches,
             committed_patches: self.committed_patches(),
@@ -386,3 +374,20 @@ impl<T: Clone> Writer<T> {
         }
     }
 }
+
+impl<T: Clone> Writer<T> {
+    /// Mutate the [`Writer`]'s local data _without_ going through a [`Patch`].
+    ///
+    /// This function gives you _direct_ access to the
+    /// underlying local `T` via a [`Transaction`].
+    ///
+    /// In order to prevent out-of-sync situations, `Transaction` will
+    /// unconditionally add a `Patch` that clones data after it has been [`drop`]'ed.
+    ///
+    /// This is cheaper than `Patch` if you had already planned to clone data anyway.
+    ///
+    /// See `Transaction` for more details.
+    pub fn tx(&mut self) -> Transaction<'_, T> {
+        Transaction::new(self)
+    }
+}
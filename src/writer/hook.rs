@@ -0,0 +1,198 @@
+//! `Writer<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::boxed::Box;
+
+use crate::{
+    apply::Apply,
+    commit::{Commit, CommitRef},
+    info::{CommitInfo, PushInfo},
+    writer::Writer,
+};
+
+#[allow(unused_imports)] // docs
+use crate::{Reader, Transaction};
+
+//---------------------------------------------------------------------------------------------------- Hook
+/// A function called after a [`Writer::push`].
+pub(crate) type PushHook<T> =
+    Box<dyn FnMut(&PushInfo, &CommitRef<T>, Option<&CommitRef<T>>) + Send + 'static>;
+
+/// A function called after a [`Writer::commit`].
+pub(crate) type CommitHook<T> = Box<dyn FnMut(&CommitInfo, &Commit<T>) + Send + 'static>;
+
+//---------------------------------------------------------------------------------------------------- Writer
+impl<T, P: Apply<T>> Writer<T, P> {
+    /// Add a function to be called after every [`Writer::push`]
+    ///
+    /// This hook is called after the new head [`Commit`] has been
+    /// atomically made visible to [`Reader`]'s, and after the [`Writer`]
+    /// has re-initialized its local data, i.e. the `Writer` is in a
+    /// completely valid state when the hook runs (even if it panics).
+    ///
+    /// It is only called if there was something to push (`PushInfo::commits > 0`).
+    ///
+    /// The inputs are:
+    /// 1. The [`PushInfo`] of the `push()`
+    /// 2. The new head `CommitRef` that `Reader`'s can now see
+    /// 3. The previous head `CommitRef`, this is `None` if the
+    ///    `Writer` reclaimed it (as it no longer exists)
+    ///
+    /// This is useful for side effects tied to publication, e.g. metrics, logs, cache invalidation.
+    ///
+    /// Multiple hooks can be added, they are called in the order they were added.
+    ///
+    /// Hooks are not carried over to [`Writer::fork`]'s.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::sync::*;
+    /// let (r, mut w) = someday::new::<usize>(0);
+    ///
+    /// let log = Arc::new(Mutex::new(vec![]));
+    /// let log2 = Arc::clone(&log);
+    /// w.on_push(move |push_info, new, old| {
+    ///     let old = old.map(|old| old.timestamp);
+    ///     log2.lock().unwrap().push((push_info.commits, new.timestamp, old));
+    /// });
+    ///
+    /// // Nothing to push, the hook isn't called.
+    /// w.push();
+    /// assert!(log.lock().unwrap().is_empty());
+    ///
+    /// // The old head was reclaimed.
+    /// w.add_commit_push(|w, _| *w += 1);
+    /// assert_eq!(log.lock().unwrap()[0], (1, 1, None));
+    ///
+    /// // A `Reader` is holding onto the old head,
+    /// // so it is still around and passed to the hook.
+    /// let head = r.head();
+    /// w.add_commit_push(|w, _| *w += 1);
+    /// assert_eq!(log.lock().unwrap()[1], (1, 2, Some(1)));
+    /// ```
+    ///
+    /// ## Panics
+    /// A panicking hook will not leave the `Writer` in an invalid state.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::panic::*;
+    /// let (r, mut w) = someday::new::<usize>(0);
+    /// w.on_push(|_, _, _| panic!());
+    ///
+    /// let result = catch_unwind(AssertUnwindSafe(|| {
+    ///     w.add_commit_push(|w, _| *w += 1);
+    /// }));
+    /// assert!(result.is_err());
+    ///
+    /// // The push went through, and the `Writer` is still usable.
+    /// assert_eq!(*w.data(), 1);
+    /// assert_eq!(r.head().data, 1);
+    /// ```
+    pub fn on_push<F>(&mut self, hook: F)
+    where
+        F: FnMut(&PushInfo, &CommitRef<T>, Option<&CommitRef<T>>) + Send + 'static,
+    {
+        self.on_push.push(Box::new(hook));
+    }
+
+    /// Add a function to be called after every commit
+    ///
+    /// This hook is called after a new local [`Commit`] has been created by:
+    /// - [`Writer::commit`] (if there were `Patch`'s to apply)
+    /// - [`Writer::add_commit`]
+    /// - [`Writer::add_commit_push`] (and its variants)
+    /// - [`Transaction::commit`] (or dropping a [`Transaction`] that mutated data)
+    /// - [`Writer::merge_from`]
+    ///
+    /// The inputs are the [`CommitInfo`] of the commit,
+    /// and the [`Writer`]'s new local head `Commit`.
+    ///
+    /// Multiple hooks can be added, they are called in the order they were added.
+    ///
+    /// Hooks are not carried over to [`Writer::fork`]'s.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::sync::*;
+    /// let (_, mut w) = someday::new::<usize>(0);
+    ///
+    /// let log = Arc::new(Mutex::new(vec![]));
+    /// let log2 = Arc::clone(&log);
+    /// w.on_commit(move |commit_info, commit| {
+    ///     log2.lock().unwrap().push((commit_info.patches, commit.timestamp, commit.data));
+    /// });
+    ///
+    /// // Nothing to commit, the hook isn't called.
+    /// w.commit();
+    /// assert!(log.lock().unwrap().is_empty());
+    ///
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.commit();
+    /// assert_eq!(log.lock().unwrap()[0], (2, 1, 2));
+    ///
+    /// w.add_commit(|w, _| *w += 1);
+    /// assert_eq!(log.lock().unwrap()[1], (1, 2, 3));
+    ///
+    /// let mut tx = w.tx();
+    /// *tx += 1;
+    /// tx.commit();
+    /// assert_eq!(log.lock().unwrap()[2], (1, 3, 4));
+    /// ```
+    pub fn on_commit<F>(&mut self, hook: F)
+    where
+        F: FnMut(&CommitInfo, &Commit<T>) + Send + 'static,
+    {
+        self.on_commit.push(Box::new(hook));
+    }
+
+    /// Remove all hooks added with [`Writer::on_push`] and [`Writer::on_commit`].
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, mut w) = someday::new::<usize>(0);
+    ///
+    /// w.on_push(|_, _, _| panic!());
+    /// w.on_commit(|_, _| panic!());
+    /// w.clear_hooks();
+    ///
+    /// // No panic.
+    /// w.add_commit_push(|w, _| *w += 1);
+    /// ```
+    pub fn clear_hooks(&mut self) {
+        self.on_push.clear();
+        self.on_commit.clear();
+    }
+
+    /// Call all the [`Writer::on_push`] hooks, if something was pushed.
+    pub(crate) fn call_push_hooks(&mut self, push_info: &PushInfo, old: Option<&CommitRef<T>>) {
+        if push_info.commits == 0 {
+            return;
+        }
+
+        for hook in &mut self.on_push {
+            hook(push_info, &self.remote, old);
+        }
+    }
+
+    /// Call all the [`Writer::on_commit`] hooks.
+    ///
+    /// This also records the commit in the `stats` and traces it.
+    pub(crate) fn call_commit_hooks(&mut self, commit_info: &CommitInfo) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            patches = commit_info.patches,
+            timestamp_diff = commit_info.timestamp_diff,
+            "commit"
+        );
+        #[cfg(feature = "stats")]
+        self.stats.commit(commit_info);
+
+        // INVARIANT: local must be initialized after push()
+        let local = self.local.as_ref().unwrap();
+        for hook in &mut self.on_commit {
+            hook(commit_info, local);
+        }
+    }
+}
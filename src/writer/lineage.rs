@@ -0,0 +1,81 @@
+//! `Writer<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use crate::{apply::Apply, lineage::Lineage, writer::Writer};
+
+#[allow(unused_imports)] // docs
+use crate::{Commit, CommitHeader, Reader};
+
+//---------------------------------------------------------------------------------------------------- Writer
+impl<T, P: Apply<T>> Writer<T, P> {
+    #[inline]
+    #[must_use]
+    /// The [`Lineage`] of the [`Writer`]'s local [`Commit`]'s
+    ///
+    /// This is `None` unless [`Writer::track_lineage`] was called
+    /// (on this `Writer` or the one it was created from).
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, mut w) = someday::new::<usize>(0);
+    /// assert_eq!(w.lineage(), None);
+    ///
+    /// w.track_lineage();
+    /// assert!(w.lineage().is_some());
+    /// ```
+    pub const fn lineage(&self) -> Option<Lineage> {
+        self.lineage
+    }
+
+    /// Start tracking the [`Lineage`] of [`Commit`]'s
+    ///
+    /// If the `Writer` has no `Lineage`, this creates a new root
+    /// `Lineage` with [`Lineage::new`], otherwise this does nothing.
+    ///
+    /// The `Lineage` is returned.
+    ///
+    /// From then on:
+    /// - The [`CommitHeader`] of all `Commit`'s created by this `Writer` carries the `Lineage`
+    /// - [`Writer::fork`], [`Reader::fork`] and [`Reader::try_into_writer`]
+    ///   create `Writer`'s that branch off into their own `Lineage`
+    ///
+    /// [`Reader`]'s will see the `Lineage` in the [`Commit::header`]
+    /// of the head after the next [`Writer::push`] with new commits.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w) = someday::new::<usize>(0);
+    /// let lineage = w.track_lineage();
+    /// assert_eq!(w.track_lineage(), lineage);
+    ///
+    /// w.add_commit_push(|w, _| *w += 1);
+    /// assert_eq!(r.head().header.lineage, Some(lineage));
+    ///
+    /// // The fork is based on `w`'s head.
+    /// let mut fork = w.fork();
+    /// assert_ne!(w.lineage(), fork.lineage());
+    /// assert!(w.head().comparable(fork.head()));
+    ///
+    /// // These have the same timestamp and data,
+    /// // but belong to different lineages.
+    /// w.add_commit(|w, _| *w += 1);
+    /// fork.add_commit(|w, _| *w += 1);
+    /// assert_eq!(w.head().timestamp, fork.head().timestamp);
+    ///
+    /// // They diverged, so they can't be compared, but
+    /// // their common ancestor is still known.
+    /// assert!(!w.head().comparable(fork.head()));
+    /// assert!(!w.ahead_of(fork.head()));
+    /// let ancestor = w.head().common_ancestor(fork.head()).unwrap();
+    /// assert_eq!(ancestor, CommitId { lineage: lineage.id, timestamp: 1 });
+    ///
+    /// // An unrelated `Writer`.
+    /// let (_, mut other) = someday::new::<usize>(2);
+    /// other.track_lineage();
+    /// other.add_commit(|w, _| *w += 0);
+    /// assert!(!w.head().comparable(other.head()));
+    /// ```
+    pub fn track_lineage(&mut self) -> Lineage {
+        *self.lineage.get_or_insert_with(Lineage::new)
+    }
+}
@@ -0,0 +1,273 @@
+//! `Writer<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::sync::{Arc, Weak};
+
+use crate::{
+    commit::{Commit, CommitRef},
+    error::MergeError,
+    info::CommitInfo,
+    lineage::LineageId,
+    merge::Merge,
+    sync::{lock, ArcSwap, Mutex},
+    writer::Writer,
+};
+
+#[allow(unused_imports)] // docs
+use crate::Reader;
+
+//---------------------------------------------------------------------------------------------------- ForkBase
+/// The ancestry of a forked [`Writer`].
+pub(crate) struct ForkBase<T> {
+    /// The ID of the `arc` of the `Writer` that was forked from.
+    pub(crate) parent: LineageId,
+
+    /// The `arc` of the `Writer` that was forked from, only used to
+    /// check if it (or any of its `Reader`'s) still exists.
+    pub(crate) parent_arc: Weak<ArcSwap<Commit<T>>>,
+
+    /// The common ancestor `Commit`.
+    ///
+    /// This is the `Commit` that was forked from, until a merge in either
+    /// direction, after which it is the pushed head that was merged from.
+    ///
+    /// This is shared with the `Writer`'s and `Reader`'s holding the same
+    /// `Commit`, it is never a copy of its own.
+    ///
+    /// Both the fork and the parent update this, so it is behind a lock.
+    pub(crate) commit: Mutex<CommitRef<T>>,
+}
+
+impl<T> ForkBase<T> {
+    #[allow(clippy::missing_const_for_fn)] // `loom`'s `Mutex::new` is not `const`
+    /// Create a new `ForkBase` for a fork of the `Writer` with `parent_arc` at `commit`.
+    pub(crate) fn new(parent_arc: &Arc<ArcSwap<Commit<T>>>, commit: CommitRef<T>) -> Self {
+        Self {
+            parent: parent_arc.id(),
+            parent_arc: Arc::downgrade(parent_arc),
+            commit: Mutex::new(commit),
+        }
+    }
+
+    /// If the `Writer` that was forked from and all its `Reader`'s are gone.
+    ///
+    /// Nothing can be merged with it anymore, so the base is no longer needed.
+    pub(crate) fn orphaned(&self) -> bool {
+        self.parent_arc.strong_count() == 0
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Writer
+impl<T: Clone> Writer<T> {
+    /// Three-way merge the data of another [`Writer`] into this one
+    ///
+    /// This finds the common ancestor of `self` and `other`, which is the
+    /// [`Commit`] that one was [forked](Writer::fork) from the other at,
+    /// then calls [`Merge::merge`] with:
+    /// - `base`: the forked from `Commit`'s data
+    /// - `ours`: `self`'s local data
+    /// - `theirs`: `other`'s pushed data, i.e. [`Writer::head_remote`]
+    ///
+    /// Only what `other` has [pushed](Writer::push) is merged, its unpushed
+    /// commits are not. This way, the merged from `Commit` is shared with
+    /// `other`'s [`Reader`]'s and becomes the next common ancestor
+    /// without copying it.
+    ///
+    /// The merged data becomes a new local commit, as if
+    /// [`Writer::overwrite`] was called, which is then [pushed](Writer::push),
+    /// such that `other` can merge it back. Staged `Patch`'s are kept.
+    ///
+    /// `other` is left as is, it can continue to be used or be dropped.
+    ///
+    /// This works in both directions, i.e. a fork can be merged into the
+    /// `Writer` it forked from, and the `Writer` into the fork.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::collections::BTreeMap;
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// struct Config(BTreeMap<&'static str, usize>);
+    ///
+    /// impl Merge for Config {
+    ///     // The conflicting keys.
+    ///     type Conflict = Vec<&'static str>;
+    ///
+    ///     fn merge(base: &Self, ours: &Self, theirs: &Self) -> Result<Self, Self::Conflict> {
+    ///         let mut merged = ours.0.clone();
+    ///         let mut conflicts = vec![];
+    ///
+    ///         for (key, value) in &theirs.0 {
+    ///             let base = base.0.get(key);
+    ///             let ours = ours.0.get(key);
+    ///             if Some(value) == ours || Some(value) == base {
+    ///                 // Nothing changed on their side.
+    ///             } else if ours == base {
+    ///                 // Only they changed it.
+    ///                 merged.insert(key, *value);
+    ///             } else {
+    ///                 // Both changed it.
+    ///                 conflicts.push(*key);
+    ///             }
+    ///         }
+    ///
+    ///         if conflicts.is_empty() {
+    ///             Ok(Self(merged))
+    ///         } else {
+    ///             Err(conflicts)
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let (r, mut w) = someday::new(Config(BTreeMap::from([("a", 0), ("b", 0)])));
+    ///
+    /// // Speculatively edit a fork.
+    /// let mut fork = w.fork();
+    /// fork.add_commit_push(|c, _| { c.0.insert("a", 1); });
+    ///
+    /// // Meanwhile, the original continues.
+    /// w.add_commit(|c, _| { c.0.insert("b", 2); });
+    ///
+    /// // Land the fork's changes.
+    /// let commit_info = w.merge_from(&fork).unwrap();
+    /// assert_eq!(commit_info.patches, 1);
+    /// assert_eq!(w.data().0, BTreeMap::from([("a", 1), ("b", 2)]));
+    /// assert_eq!(w.timestamp(), 2);
+    ///
+    /// // Both sides changed the same key.
+    /// let mut fork = w.fork();
+    /// fork.add_commit_push(|c, _| { c.0.insert("a", 3); });
+    /// w.add_commit(|c, _| { c.0.insert("a", 4); });
+    ///
+    /// let error = w.merge_from(&fork).unwrap_err();
+    /// assert!(matches!(error, MergeError::Conflict(keys) if keys == ["a"]));
+    /// assert_eq!(w.data().0["a"], 4);
+    ///
+    /// // Unrelated `Writer`'s have no common ancestor.
+    /// let (_, other) = someday::new(Config(BTreeMap::new()));
+    /// assert!(matches!(w.merge_from(&other), Err(MergeError::NoCommonAncestor)));
+    /// ```
+    ///
+    /// ## Common ancestor
+    /// The common ancestor is the `Commit` at the time of the fork until the
+    /// first successful merge, in either direction. After that, it is the
+    /// data that was merged from (`theirs`), so merging the same fork again
+    /// only sees the changes made since the last merge.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # #[derive(Clone)]
+    /// # struct Counter(usize);
+    /// # impl Merge for Counter {
+    /// #     type Conflict = std::convert::Infallible;
+    /// #     fn merge(base: &Self, ours: &Self, theirs: &Self) -> Result<Self, Self::Conflict> {
+    /// #         Ok(Self(ours.0 + theirs.0 - base.0))
+    /// #     }
+    /// # }
+    /// let (_, mut w) = someday::new(Counter(0));
+    /// let mut fork = w.fork();
+    ///
+    /// fork.add_commit_push(|c, _| c.0 += 1);
+    /// w.merge_from(&fork).unwrap();
+    /// assert_eq!(w.data().0, 1);
+    ///
+    /// // The fork's `+1` is not merged twice.
+    /// fork.add_commit_push(|c, _| c.0 += 10);
+    /// w.merge_from(&fork).unwrap();
+    /// assert_eq!(w.data().0, 11);
+    ///
+    /// // Nor is it merged back into the fork.
+    /// w.add_commit_push(|c, _| c.0 += 100);
+    /// fork.merge_from(&w).unwrap();
+    /// assert_eq!(fork.data().0, 111);
+    ///
+    /// // Unpushed commits are not merged.
+    /// fork.add_commit(|c, _| c.0 += 1000);
+    /// w.merge_from(&fork).unwrap();
+    /// assert_eq!(w.data().0, 111);
+    /// ```
+    ///
+    /// ## Releasing the common ancestor
+    /// The common ancestor `Commit` is kept alive by the fork, so the
+    /// `Writer` (or fork) holding it cannot reclaim it on its next push.
+    ///
+    /// It is released after the next merge (which replaces it), or on
+    /// the fork's next push once the `Writer` it was forked from and
+    /// all of that `Writer`'s `Reader`'s have been dropped.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, w) = someday::new(0_usize);
+    /// let mut fork = w.fork();
+    ///
+    /// // Nothing can be merged with `w` anymore.
+    /// drop((r, w));
+    ///
+    /// // So the fork's first push reclaims the forked from `Commit`.
+    /// fork.add_commit(|w, _| *w += 1);
+    /// assert!(fork.push().reclaimed);
+    /// ```
+    ///
+    /// ## Timestamp
+    /// On success, this increments the `Writer`'s local [`Timestamp`](crate::Timestamp) by `1`.
+    ///
+    /// ## `Writer::share`
+    /// If a [`Writer::share`]'ed `Writer` pushed first, the merge commit stays
+    /// local (see [`PushInfo::conflict`](crate::PushInfo::conflict)) and the
+    /// common ancestor is not advanced, so the next merge sees the same
+    /// changes of `other` again.
+    ///
+    /// # Errors
+    /// This returns:
+    /// - [`MergeError::NoCommonAncestor`] if neither `Writer` was forked from the other
+    /// - [`MergeError::Conflict`] if [`Merge::merge`] failed
+    pub fn merge_from(&mut self, other: &Self) -> Result<CommitInfo, MergeError<T::Conflict>>
+    where
+        T: Merge,
+    {
+        let Some(fork_base) = self.fork_base(other) else {
+            return Err(MergeError::NoCommonAncestor);
+        };
+
+        let base = lock(&fork_base.commit);
+        let theirs = Arc::clone(&other.remote);
+
+        let merged = match T::merge(&base.data, &self.head().data, &theirs.data) {
+            Ok(merged) => merged,
+            Err(conflict) => return Err(MergeError::Conflict(conflict)),
+        };
+        drop(base);
+
+        drop(self.overwrite(merged));
+
+        let commit_info = CommitInfo {
+            patches: 1,
+            timestamp_diff: self.timestamp_diff(),
+        };
+
+        self.call_commit_hooks(&commit_info);
+
+        // Both sides' pushed data now contains `theirs`,
+        // the next merge only needs the changes made after it.
+        if !self.push().conflict {
+            if let Some(fork_base) = self.fork_base(other) {
+                *lock(&fork_base.commit) = theirs;
+            }
+        }
+
+        Ok(commit_info)
+    }
+
+    /// Find the [`ForkBase`] holding the common ancestor of `self` and `other`.
+    fn fork_base<'a>(&'a self, other: &'a Self) -> Option<&'a ForkBase<T>> {
+        // `other` was forked from `self`.
+        let other_base = other.fork_base.as_ref();
+        if let Some(fork_base) = other_base.filter(|f| f.parent == self.arc.id()) {
+            return Some(fork_base);
+        }
+
+        // `self` was forked from `other`.
+        self.fork_base
+            .as_ref()
+            .filter(|f| f.parent == other.arc.id())
+    }
+}
@@ -1,16 +1,13 @@
 //! `Writer<T>`
 
 //---------------------------------------------------------------------------------------------------- Use
-use std::sync::Arc;
+use alloc::sync::Arc;
 
 use crate::{
-    commit::Commit, info::WriterInfo, patch::Patch, reader::Reader, writer::token::WriterToken,
-    writer::Writer,
+    apply::Apply, commit::Commit, header::CommitHeader, info::WriterInfo, patch::Patch,
+    reader::Reader, sync::ArcSwap, writer::token::WriterToken, writer::Writer,
 };
 
-#[allow(unused_imports)] // docs
-                         // use crate::Commit;
-
 //---------------------------------------------------------------------------------------------------- Writer
 impl<T: Clone> Writer<T> {
     /// Same as [`crate::free::new`] but without creating a [`Reader`].
@@ -24,7 +21,11 @@ impl<T: Clone> Writer<T> {
     /// assert_eq!(w.timestamp(), w2.timestamp());
     /// ```
     pub fn new(data: T) -> Self {
-        crate::free::new_inner(Commit { data, timestamp: 0 })
+        crate::free::new_inner(Commit {
+            data,
+            timestamp: 0,
+            header: CommitHeader::new(),
+        })
     }
 
     #[inline]
@@ -35,7 +36,8 @@ impl<T: Clone> Writer<T> {
     /// clones the [`Reader`]'s data into the [`Writer`].
     ///
     /// This could be used in the situation the `Patch`(s)
-    /// are actually more expensive than just cloning.
+    /// are actually more expensive than just cloning,
+    /// [`CompactPolicy`](crate::CompactPolicy) can do this automatically.
     ///
     /// The committed `Patch`'s that were removed are returned.
     ///
@@ -56,14 +58,28 @@ impl<T: Clone> Writer<T> {
     /// w.push();
     /// assert_eq!(w.data().len(), 100_000 * 3);
     /// assert_eq!(*w.data(), r.head().data);
+    ///
+    /// // Commits after it are only applied once.
+    /// w.just_clone();
+    /// w.add_commit(|w, _| w.push_str("d"));
+    /// assert!(w.push().reclaimed);
+    /// w.add_commit_push(|w, _| w.push_str("e"));
+    /// assert!(w.data().ends_with("abcde"));
+    /// assert_eq!(*w.data(), r.head().data);
     /// ```
-    pub fn just_clone(&mut self) -> std::vec::Drain<'_, Patch<T>> {
+    pub fn just_clone(&mut self) -> alloc::vec::Drain<'_, Patch<T>> {
         self.patches_old.push(Patch::CLONE);
+        self.replayable = false;
+        self.rebased = false;
+        self.clone_sync = true;
+        self.sync_base = None;
 
         // Drain all but the Clone patch.
         self.patches_old.drain(..self.patches_old.len() - 1)
     }
+}
 
+impl<T, P: Apply<T>> Writer<T, P> {
     #[inline]
     #[allow(clippy::type_complexity)]
     /// Restore all the staged changes.
@@ -86,7 +102,7 @@ impl<T: Clone> Writer<T> {
     /// let drain = w.restore();
     /// assert_eq!(drain.count(), 1);
     /// ```
-    pub fn restore(&mut self) -> std::vec::Drain<'_, Patch<T>> {
+    pub fn restore(&mut self) -> alloc::vec::Drain<'_, P> {
         self.patches.drain(..)
     }
 
@@ -225,7 +241,10 @@ impl<T: Clone> Writer<T> {
     /// ```
     pub fn disconnect(&mut self) {
         self.token = WriterToken::new();
-        self.arc = Arc::new(arc_swap::ArcSwap::new(Arc::clone(&self.remote)));
+        self.arc = Arc::new(ArcSwap::new(Arc::clone(&self.remote)));
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(timestamp = self.remote.timestamp, "disconnect");
     }
 
     #[allow(clippy::missing_panics_doc, clippy::type_complexity)]
@@ -255,13 +274,15 @@ impl<T: Clone> Writer<T> {
     /// assert_eq!(staged.len(), 1);
     /// assert_eq!(committed_patches.len(), 1);
     /// ```
-    pub fn into_inner(self) -> WriterInfo<T> {
+    pub fn into_inner(mut self) -> WriterInfo<T, P> {
+        // `Writer` implements `Drop`, so the fields are taken instead of moved,
+        // leaving `local` as `None` so the `DropPolicy` does not apply.
         WriterInfo {
             // INVARIANT: local must be initialized after push()
-            writer: self.local.unwrap(),
-            reader: self.remote,
-            staged: self.patches,
-            committed_patches: self.patches_old,
+            writer: self.local.take().unwrap(),
+            reader: Arc::clone(&self.remote),
+            staged: core::mem::take(&mut self.patches),
+            committed_patches: core::mem::take(&mut self.patches_old),
         }
     }
 }
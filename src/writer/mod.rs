@@ -3,14 +3,44 @@
 mod writer;
 pub use writer::Writer;
 
+mod close;
+pub use close::{DropPolicy, WriterState};
+
+mod shared;
+pub use shared::RetryPolicy;
+
+mod compact;
+pub use compact::CompactPolicy;
+#[cfg(feature = "std")]
+pub(crate) use compact::Costs;
+
+#[cfg(feature = "stats")]
+mod stats;
+#[cfg(feature = "stats")]
+pub(crate) use stats::Stats;
+#[cfg(feature = "stats")]
+pub use stats::{Histogram, WriterStats};
+
 mod token;
 pub(crate) use token::{WriterReviveToken, WriterToken};
 
+pub(crate) use merge::ForkBase;
+
 mod add_commit_push;
+mod determinism;
+#[cfg(feature = "sha2")]
+mod digest;
+#[cfg(feature = "testing")]
+mod fault;
 mod fork;
 mod get;
+mod hook;
+mod lineage;
+mod merge;
 mod misc;
+mod poison;
 mod pull;
 mod push;
 mod serde;
 mod timestamp;
+mod validate;
@@ -0,0 +1,170 @@
+//! `Writer<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::sync::Arc;
+
+use crate::{apply::Apply, error::PoisonError, writer::Writer};
+
+#[allow(unused_imports)] // docs
+use crate::{Commit, Patch, Reader, Transaction};
+
+//---------------------------------------------------------------------------------------------------- Writer
+impl<T, P: Apply<T>> Writer<T, P> {
+    #[inline]
+    #[must_use]
+    /// Is the [`Writer`] poisoned?
+    ///
+    /// Similar to [`std::sync::Mutex`], the `Writer` becomes poisoned
+    /// if something panics while its local data is being modified, e.g:
+    /// - A [`Patch`] panicking in [`Writer::commit`]
+    /// - A `Patch` panicking while being re-applied in [`Writer::push`]
+    /// - A panic while a [`Transaction`] has mutably borrowed the data
+    /// - A [`Writer::push`] that gave up waiting on `Reader`'s to reclaim
+    ///   the old head, for `Writer`'s that cannot clone (see [`crate::new_with`])
+    ///
+    /// Only [`Writer::recover`] clears the poison, e.g. a
+    /// successful [`Writer::commit`] afterwards does not.
+    ///
+    /// The local data is then in an unknown state (or missing entirely).
+    ///
+    /// A poisoned `Writer`:
+    /// - Returns [`PoisonError`] from fallible functions, e.g. [`Writer::try_commit`]
+    /// - Panics in [`Writer::push`] (and variants) so [`Reader`]'s never see half-applied data
+    /// - May panic in other functions that access the local data
+    ///
+    /// Use [`Writer::recover`] to make it usable again.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::panic::*;
+    /// let (r, mut w) = someday::new::<usize>(0);
+    /// assert!(!w.is_poisoned());
+    ///
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.add(Patch::Ptr(|_, _| panic!()));
+    ///
+    /// let result = catch_unwind(AssertUnwindSafe(|| w.commit()));
+    /// assert!(result.is_err());
+    /// assert!(w.is_poisoned());
+    ///
+    /// // Committing on top of the half-applied data does not clear it.
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.commit();
+    /// assert!(w.is_poisoned());
+    ///
+    /// let error = w.try_push().unwrap_err();
+    /// assert!(matches!(error, PushError::Poison(PoisonError)));
+    /// ```
+    pub const fn is_poisoned(&self) -> bool {
+        self.poisoned || self.local.is_none()
+    }
+
+    /// Recover a poisoned [`Writer`]
+    ///
+    /// This rebuilds the `Writer`'s local data from the current head [`Commit`]
+    /// that [`Reader`]'s can see, similar to [`Writer::pull`].
+    ///
+    /// All committed and staged [`Patch`]'s are discarded as they
+    /// may have been half-applied (and may panic again).
+    ///
+    /// This returns `Ok(true)` if the `Writer` was poisoned,
+    /// or `Ok(false)` (and does nothing) if it wasn't.
+    ///
+    /// ## `new_with()`
+    /// A `Writer` that cannot clone (see [`crate::new_with`]) has no
+    /// copy of the head to rebuild from, instead, it can only recover
+    /// from a `push()` that gave up waiting on `Reader`'s, by
+    /// reclaiming the old head once they let go of it.
+    ///
+    /// # Errors
+    /// This returns [`PoisonError`] (and stays poisoned) if the `Writer`
+    /// cannot clone and there is no old head to reclaim, or `Reader`'s
+    /// are still holding onto it.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// #[derive(Debug, PartialEq)]
+    /// struct Counter(usize);
+    ///
+    /// let (r, mut w) = someday::new_with(|| Counter(0));
+    ///
+    /// // A `Reader` holds onto the old head
+    /// // for longer than the `Writer` waits.
+    /// let head = r.head();
+    /// w.add_commit(|w, _| w.0 += 1);
+    /// let push_info = w.push();
+    /// assert!(!push_info.reclaimed);
+    /// assert!(w.is_poisoned());
+    /// assert_eq!(r.head().data, Counter(1));
+    ///
+    /// // It is still held.
+    /// assert!(matches!(w.recover(), Err(PoisonError)));
+    ///
+    /// // Now it can be reclaimed.
+    /// drop(head);
+    /// assert_eq!(w.recover(), Ok(true));
+    /// assert_eq!(*w.data(), Counter(1));
+    /// ```
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::panic::*;
+    /// let (r, mut w) = someday::new::<usize>(0);
+    /// w.add_commit_push(|w, _| *w += 1);
+    /// assert_eq!(w.recover(), Ok(false));
+    ///
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.add(Patch::Ptr(|_, _| panic!()));
+    /// let result = catch_unwind(AssertUnwindSafe(|| w.commit()));
+    /// assert!(result.is_err());
+    /// assert!(w.is_poisoned());
+    ///
+    /// // The `Writer` is usable again, and
+    /// // is synced with the `Reader`'s.
+    /// assert_eq!(w.recover(), Ok(true));
+    /// assert!(!w.is_poisoned());
+    /// assert_eq!(*w.data(), 1);
+    /// assert_eq!(w.timestamp(), 1);
+    /// assert!(w.staged().is_empty());
+    /// assert!(w.committed_patches().is_empty());
+    ///
+    /// w.add_commit_push(|w, _| *w += 1);
+    /// assert_eq!(r.head().data, 2);
+    /// ```
+    pub fn recover(&mut self) -> Result<bool, PoisonError> {
+        if !self.is_poisoned() {
+            return Ok(false);
+        }
+
+        let local = match self.unreclaimed.take().map(Arc::try_unwrap) {
+            // A `push()` gave up reclaiming the old head, try again.
+            Some(Ok(mut local)) => {
+                self.sync_reclaimed(&mut local.data, self.clone_fn);
+                local.timestamp = self.remote.timestamp;
+                local.header.clone_from(&self.remote.header);
+                local
+            }
+            // Else, the `Writer` may have panicked in the middle
+            // of `push()`, so use what `Reader`'s can see.
+            reclaim => {
+                let Some(clone_fn) = self.clone_fn else {
+                    self.unreclaimed = reclaim.and_then(Result::err);
+                    return Err(PoisonError);
+                };
+                self.remote = self.arc.load_full();
+                self.clone_remote(clone_fn)
+            }
+        };
+        self.local = Some(local);
+
+        self.patches.clear();
+        self.patches_old.clear();
+        self.replayable = true;
+        self.rebased = false;
+        self.clone_sync = false;
+        self.sync_base = None;
+        self.poisoned = false;
+
+        Ok(true)
+    }
+}
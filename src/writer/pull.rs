@@ -1,13 +1,22 @@
 //! `Writer<T>`
 
 //---------------------------------------------------------------------------------------------------- Use
-use crate::{commit::Commit, info::PullInfo, patch::Patch, writer::Writer};
+use alloc::vec::Vec;
+
+use crate::{
+    apply::Apply,
+    commit::{Commit, CommitRef},
+    error::{PatchError, PoisonError, RebaseError},
+    info::{CommitInfo, PullInfo, RebaseInfo},
+    patch::Patch,
+    writer::Writer,
+};
 
 #[allow(unused_imports)] // docs
 use crate::{Reader, Timestamp};
 
 //---------------------------------------------------------------------------------------------------- Writer
-impl<T: Clone> Writer<T> {
+impl<T: Clone, P: Apply<T>> Writer<T, P> {
     #[inline]
     #[allow(clippy::missing_panics_doc)]
     /// Conditionally overwrite the [`Writer`]'s local [`Commit`] with the current [`Reader`] `Commit`
@@ -60,9 +69,12 @@ impl<T: Clone> Writer<T> {
             return None;
         }
 
+        #[cfg(feature = "testing")]
+        self.faults.writer_death();
+
         // INVARIANT: if we're not synced, that
         // means `timestamp_diff` is non-zero.
-        let commits_reverted = std::num::NonZeroUsize::new(self.timestamp_diff()).unwrap();
+        let commits_reverted = core::num::NonZeroUsize::new(self.timestamp_diff()).unwrap();
 
         // INVARIANT: `local` must be initialized after push()
         let old_writer_commit = self.local.take().unwrap();
@@ -72,13 +84,29 @@ impl<T: Clone> Writer<T> {
         // them anymore since we just overwrote
         // our data anyway.
         self.patches_old.clear();
+        self.replayable = true;
+        self.rebased = false;
+        self.clone_sync = false;
+        self.sync_base = None;
+
+        #[cfg(feature = "stats")]
+        self.stats.pull();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            commits_reverted = commits_reverted.get(),
+            old_timestamp = old_writer_commit.timestamp,
+            timestamp = self.timestamp(),
+            "pull"
+        );
 
         Some(PullInfo {
             commits_reverted,
             old_writer_commit,
         })
     }
+}
 
+impl<T: Clone> Writer<T> {
     #[inline]
     #[allow(clippy::missing_panics_doc)]
     /// Overwrite the [`Writer`]'s local data with `data`.
@@ -138,6 +166,13 @@ impl<T: Clone> Writer<T> {
     /// // 5 commits total.
     /// assert_eq!(w.timestamp(), 5);
     /// assert_eq!(r.head().timestamp, 5);
+    ///
+    /// // Commits after an overwrite are only applied once.
+    /// w.overwrite(String::from("a"));
+    /// w.add_commit(|w, _| w.push('b'));
+    /// w.push();
+    /// assert_eq!(w.data(), "ab");
+    /// assert_eq!(r.head().data, "ab");
     /// ```
     ///
     /// ## Timestamp
@@ -147,12 +182,20 @@ impl<T: Clone> Writer<T> {
         // them anymore since we just overwrote
         // our data anyway.
         self.patches_old.clear();
+        self.replayable = false;
+        self.rebased = false;
+        self.clone_sync = true;
+        self.sync_base = None;
 
         // INVARIANT: `local` must be initialized after push()
         let timestamp = self.timestamp() + 1;
         let old_data = self.local.take().unwrap();
 
-        self.local = Some(Commit { timestamp, data });
+        self.local = Some(Commit {
+            timestamp,
+            data,
+            header: self.new_header(None),
+        });
 
         // Add a `Patch` that clones the new data
         // to the _old_ patches, meaning they are
@@ -173,6 +216,163 @@ impl<T: Clone> Writer<T> {
             *w = r.clone();
         }));
 
+        #[cfg(feature = "tracing")]
+        tracing::debug!(old_timestamp = old_data.timestamp, timestamp, "overwrite");
+
         old_data
     }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Replay the committed and staged [`Patch`]'s on top of a different [`Commit`]
+    ///
+    /// Unlike [`Writer::pull`] and [`Writer::overwrite`], this keeps the
+    /// `Writer`'s local commits, applying them onto `commit` instead of the
+    /// `Commit` they were originally applied to.
+    ///
+    /// Staged `Patch`'s are replayed and committed as well, as if
+    /// [`Writer::commit`] was called after the rebase.
+    ///
+    /// The data of `commit` becomes the `Writer`'s new base, the
+    /// [`Reader`]'s will see the result on the next [`Writer::push`].
+    ///
+    /// ## Replayed `Patch`'s
+    /// All `Patch`'s (including [`Patch::Box`]) are replayed with the data
+    /// of `commit` as the `Reader` data, like every `Patch`, they must be
+    /// deterministic, see [`Patch`]'s "Non-deterministic" section.
+    ///
+    /// The base set by a previous `rebase_onto()` is replaced by `commit`.
+    ///
+    /// ## Errors
+    /// The replay happens on a copy, on any error, the `Writer` is left untouched.
+    ///
+    /// - [`RebaseError::Poison`] if the `Writer` is [poisoned](Writer::is_poisoned)
+    /// - [`RebaseError::Replaced`] if the `Writer`'s data was replaced since the last
+    ///   `push()` (e.g. by `overwrite()`, a [`Transaction`](crate::Transaction) or
+    ///   `just_clone()`), that replacement cannot be replayed on top of `commit`
+    /// - [`RebaseError::Patch`] if a [`Patch::Try`] failed
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w) = someday::new::<Vec<usize>>(vec![]);
+    ///
+    /// // Local commits.
+    /// w.add(Patch::Ptr(|w, _| w.push(1)));
+    /// w.commit();
+    /// w.add(Patch::boxed(|w: &mut Vec<usize>, _| w.push(2)));
+    /// w.commit();
+    /// w.add(Patch::arc(|w: &mut Vec<usize>, _| w.push(3)));
+    /// w.commit();
+    /// assert_eq!(*w.data(), [1, 2, 3]);
+    ///
+    /// // A staged `Patch` that reads the `Reader` data.
+    /// w.add(Patch::Ptr(|w, r| w.push(r.len())));
+    ///
+    /// // Some other newer data.
+    /// let mut other = r.fork();
+    /// other.add_commit_push(|w, _| w.push(0));
+    /// let base = other.reader().head();
+    ///
+    /// // Move our commits on top of it.
+    /// let rebase_info = w.rebase_onto(base.clone()).unwrap();
+    /// assert_eq!(rebase_info.replayed, 4);
+    /// assert!(w.staged().is_empty());
+    /// assert_eq!(*w.data(), [0, 1, 2, 3, 1]);
+    ///
+    /// // `other.timestamp() + our 4 local commits`
+    /// assert_eq!(w.timestamp(), 5);
+    ///
+    /// w.push();
+    /// assert_eq!(r.head().data, [0, 1, 2, 3, 1]);
+    ///
+    /// // Replaced data cannot be replayed.
+    /// w.overwrite(vec![5]);
+    /// assert!(matches!(w.rebase_onto(base), Err(RebaseError::Replaced)));
+    /// assert_eq!(*w.data(), [5]);
+    /// ```
+    ///
+    /// ## Timestamp
+    /// The local [`Timestamp`] becomes `commit`'s timestamp (or the
+    /// [`Reader`]'s, whichever is greater) plus the amount of local
+    /// commits, and is always at least `1` ahead of the `Reader`'s.
+    pub fn rebase_onto(&mut self, commit: CommitRef<T>) -> Result<RebaseInfo, RebaseError>
+    where
+        T: Send + Sync + 'static,
+    {
+        if self.is_poisoned() {
+            return Err(RebaseError::Poison(PoisonError));
+        }
+
+        // If the first patch does not apply on top of `remote`, it
+        // can only be skipped if it is our own base from a previous rebase.
+        let skip = match (self.replayable, self.rebased) {
+            (true, _) => 0,
+            (false, true) => 1,
+            (false, false) => return Err(RebaseError::Replaced),
+        };
+
+        let staged = usize::from(!self.patches.is_empty());
+        let commits = (self.timestamp_diff() + staged).max(1);
+        let timestamp = commit.timestamp.max(self.remote.timestamp) + commits;
+
+        // Replay onto a separate copy, if a `Patch`
+        // fails or panics, the `Writer` is left untouched.
+        let mut data = commit.data.clone();
+        for (index, patch) in self
+            .patches_old
+            .iter_mut()
+            .chain(self.patches.iter_mut())
+            .skip(skip)
+            .enumerate()
+        {
+            if let Err(error) = patch.try_apply(&mut data, &commit.data) {
+                return Err(RebaseError::Patch(PatchError {
+                    index,
+                    timestamp,
+                    error,
+                }));
+            }
+        }
+
+        let patches = self.patches.len();
+        let replayed = self.patches_old.len() - skip + patches;
+
+        // Add a `Patch` that sets the base, such that
+        // reclaimed `Reader` data can be turned into our data.
+        let base = commit;
+        let mut patches_old = Vec::with_capacity(self.patches_old.capacity() + patches);
+        patches_old.push(Patch::arc(move |w: &mut T, _| w.clone_from(&base.data)));
+        patches_old.extend(self.patches_old.drain(skip..));
+        patches_old.append(&mut self.patches);
+        self.patches_old = patches_old;
+
+        let header = if staged == 0 {
+            self.local
+                .take()
+                .map(|local| local.header)
+                .unwrap_or_default()
+        } else {
+            self.new_header(None)
+        };
+        self.local = Some(Commit {
+            timestamp,
+            data,
+            header,
+        });
+        self.replayable = false;
+        self.rebased = true;
+        self.clone_sync = false;
+        self.sync_base = None;
+
+        if staged != 0 {
+            self.call_commit_hooks(&CommitInfo {
+                patches,
+                timestamp_diff: self.timestamp_diff(),
+            });
+        }
+
+        Ok(RebaseInfo {
+            replayed,
+            timestamp_diff: self.timestamp_diff(),
+        })
+    }
 }
@@ -1,15 +1,22 @@
 //! `Writer<T>`
 
 //---------------------------------------------------------------------------------------------------- Use
-use std::{sync::Arc, time::Duration};
+use alloc::sync::Arc;
+use core::time::Duration;
 
-use crate::{info::PushInfo, writer::Writer};
+use crate::{
+    apply::Apply,
+    commit::CommitRef,
+    error::{ConflictError, PoisonError, PushError},
+    info::PushInfo,
+    writer::{ForkBase, Writer},
+};
 
 #[allow(unused_imports)] // docs
 use crate::{Commit, Reader};
 
 //---------------------------------------------------------------------------------------------------- Writer
-impl<T: Clone> Writer<T> {
+impl<T, P: Apply<T>> Writer<T, P> {
     #[inline]
     /// Conditionally push [`Writer`]'s local _committed_ data to the [`Reader`]'s.
     ///
@@ -56,10 +63,21 @@ impl<T: Clone> Writer<T> {
     ///     unreachable!();
     /// }
     /// ```
+    ///
+    /// If a [`Writer::share`]'ed `Writer` pushed first and the commits could
+    /// not be re-applied, nothing is pushed, see [`PushInfo::conflict`].
+    ///
+    /// # Panics
+    /// This panics if the `Writer` is [poisoned](Writer::is_poisoned),
+    /// see [`Writer::try_push`] to handle that instead.
     pub fn push(&mut self) -> PushInfo {
-        self.push_inner::<false, ()>(None, None::<fn()>).0
+        let (push_info, _, old) = self.push_inner::<false, ()>(None, None::<fn()>, self.clone_fn);
+        self.check_reclaimed(&push_info);
+        self.call_push_hooks(&push_info, old.as_ref());
+        push_info
     }
 
+    #[cfg(feature = "std")]
     #[inline]
     /// This function is the same as [`Writer::push()`]
     /// but it will [`std::thread::sleep()`] for at least `duration`
@@ -98,7 +116,11 @@ impl<T: Clone> Writer<T> {
     /// assert_eq!(commit_info.reclaimed, true);
     /// ```
     pub fn push_wait(&mut self, duration: Duration) -> PushInfo {
-        self.push_inner::<false, ()>(Some(duration), None::<fn()>).0
+        let (push_info, _, old) =
+            self.push_inner::<false, ()>(Some(duration), None::<fn()>, self.clone_fn);
+        self.check_reclaimed(&push_info);
+        self.call_push_hooks(&push_info, old.as_ref());
+        push_info
     }
 
     #[inline]
@@ -177,7 +199,9 @@ impl<T: Clone> Writer<T> {
     where
         F: FnOnce() -> R,
     {
-        let (push_info, r) = self.push_inner::<false, R>(None, Some(f));
+        let (push_info, r, old) = self.push_inner::<false, R>(None, Some(f), self.clone_fn);
+        self.check_reclaimed(&push_info);
+        self.call_push_hooks(&push_info, old.as_ref());
 
         // INVARIANT: we _know_ `R` will be a `Some`
         // because we provided a `Some`. `push_inner()`
@@ -185,128 +209,272 @@ impl<T: Clone> Writer<T> {
         (push_info, r.unwrap())
     }
 
-    #[inline]
-    /// This function is the same as [`Writer::push()`]
-    /// but it will **always** clone the data
-    /// and not attempt to reclaim any old data.
-    ///
-    /// This is useful if you know reclaiming old data
-    /// and re-applying your commits would take longer and/or
-    /// be more expensive than cloning the data itself.
+    /// Generic function to handle all the different types of pushes.
     ///
-    /// Or if you know your `Reader`'s will be holding
-    /// onto the data for a long time, and reclaiming data
-    /// will be unlikely.
+    /// `clone_fn` is used to copy the data if it isn't reclaimed, if `None`,
+    /// this waits until it is reclaimed, see [`Writer::push_reclaim`].
     ///
-    /// ```rust
-    /// # use someday::*;
-    /// # use std::{thread::*,time::*};
-    /// let (r, mut w) = someday::new::<String>("".into());
-    /// w.add(Patch::Ptr(|w, _| w.push_str("abc")));
-    /// w.commit();
+    /// This does not call the [`Writer::on_push`] hooks, the caller must
+    /// call them with the returned old head (if it wasn't reclaimed).
     ///
-    /// let commit = r.head();
-    /// spawn(move || {
-    ///     // This `Reader` will hold onto the old data forever.
-    ///     let moved = commit;
-    ///     loop { std::thread::park(); }
-    /// });
-    ///
-    /// // Always clone data, don't wait.
-    /// let push_info = w.push_clone();
-    /// // We pushed 1 commit.
-    /// assert_eq!(push_info.commits, 1);
-    /// assert_eq!(push_info.reclaimed, false);
-    /// ```
-    pub fn push_clone(&mut self) -> PushInfo {
-        self.push_inner::<true, ()>(None, None::<fn()>).0
-    }
-
-    /// Generic function to handle all the different types of pushes.
-    fn push_inner<const CLONE: bool, R>(
+    /// If a `share()`'ed `Writer` pushed first, see [`Writer::push_publish`],
+    /// nothing is pushed and [`PushInfo::conflict`] is `true`.
+    pub(crate) fn push_inner<const CLONE: bool, R>(
         &mut self,
         duration: Option<Duration>,
         function: Option<impl FnOnce() -> R>,
-    ) -> (PushInfo, Option<R>) {
-        // Early return if no commits.
-        if self.synced() {
-            let return_value = function.map(|f| f());
-            return (
-                PushInfo {
+        clone_fn: Option<fn(&T) -> T>,
+    ) -> (PushInfo, Option<R>, Option<CommitRef<T>>) {
+        #[cfg(feature = "stats")]
+        let start = std::time::Instant::now();
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("push", clone = CLONE, wait = ?duration).entered();
+
+        self.push_check();
+        let (push_info, return_value, old) = match self.push_publish() {
+            // No commits, nothing to reclaim.
+            Ok(None) => {
+                let return_value = function.map(|f| f());
+                let push_info = PushInfo {
                     timestamp: self.timestamp(),
                     commits: 0,
                     reclaimed: false,
-                },
-                return_value,
-            );
+                    compacted: false,
+                    conflict: false,
+                };
+                (push_info, return_value, None)
+            }
+
+            // A `share()`'ed `Writer` pushed first, our commits are kept.
+            Err(ConflictError) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(timestamp = self.timestamp(), "push conflict");
+                let return_value = function.map(|f| f());
+                let push_info = PushInfo {
+                    timestamp: self.timestamp_remote(),
+                    commits: 0,
+                    reclaimed: false,
+                    compacted: false,
+                    conflict: true,
+                };
+                (push_info, return_value, None)
+            }
+
+            // The user wants to deep-clone no matter what.
+            Ok(Some(old)) if CLONE && clone_fn.is_some() => {
+                let timestamp_diff = self.remote.timestamp - old.timestamp;
+                // INVARIANT: checked above.
+                self.local = Some(self.clone_remote(clone_fn.unwrap()));
+                self.patches_old.clear();
+                self.replayable = true;
+                self.rebased = false;
+                self.clone_sync = false;
+                self.sync_base = None;
+                let push_info = PushInfo {
+                    timestamp: self.remote.timestamp,
+                    commits: timestamp_diff,
+                    reclaimed: false,
+                    compacted: false,
+                    conflict: false,
+                };
+                (push_info, None, Some(old))
+            }
+
+            Ok(Some(old)) => {
+                // If the user wants to execute a function
+                // while waiting, do so and get the return value.
+                let return_value = function.map(|f| f());
+
+                let (push_info, old) = self.push_reclaim(old, duration, clone_fn);
+                (push_info, return_value, old)
+            }
+        };
+
+        #[cfg(feature = "stats")]
+        self.stats.push(&push_info, old.as_ref(), start.elapsed());
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            timestamp = push_info.timestamp,
+            commits = push_info.commits,
+            reclaimed = push_info.reclaimed,
+            cloned = push_info.commits != 0 && !push_info.reclaimed,
+            compacted = push_info.compacted,
+            "push"
+        );
+
+        (push_info, return_value, old)
+    }
+
+    /// Panic if the `Writer` cannot [`Writer::push_publish`].
+    ///
+    /// This is separate such that nothing is published
+    /// if one `Writer` of a `WriterGroup` cannot push.
+    pub(crate) fn push_check(&self) {
+        // Never let `Reader`'s see half-applied data.
+        assert!(
+            !self.poisoned,
+            "the `Writer` is poisoned, see `Writer::recover()`"
+        );
+
+        #[cfg(feature = "testing")]
+        if !self.synced() {
+            self.faults.writer_death();
         }
+    }
+
+    /// The first half of a `push()`, make our local `Commit` visible to `Reader`'s.
+    ///
+    /// This returns the old head `Reader`'s were seeing, or `None`
+    /// if there was nothing to push. If `Some`, the caller must
+    /// re-initialize `self.local` with [`Writer::push_reclaim`].
+    ///
+    /// Unless this `Writer` was [`Writer::share`]'ed, the head is just replaced.
+    ///
+    /// The caller must call [`Writer::push_check`] first.
+    ///
+    /// # Errors
+    /// This returns [`ConflictError`] if a `share()`'ed `Writer` pushed first and
+    /// the `RetryPolicy` ran out, in which case the `Writer` is still ahead.
+    pub(crate) fn push_publish(&mut self) -> Result<Option<CommitRef<T>>, ConflictError> {
+        let mut retries = 0;
+        loop {
+            if self.synced() {
+                return Ok(None);
+            }
 
-        // INVARIANT: we're temporarily "taking" our `self.local`.
-        // It will be uninitialized for the time being.
-        // We need to initialize it before returning.
-        let local = self.local.take().unwrap();
-        // Create the new `Reader` T.
-        let new = Arc::new(local);
+            // INVARIANT: we're temporarily "taking" our `self.local`.
+            // It will be uninitialized for the time being.
+            // We need to initialize it before returning.
+            let mut local = self.local.take().unwrap();
+            #[cfg(feature = "std")]
+            if let Some(meta) = local.header.meta.as_mut() {
+                meta.pushed = Some(crate::metadata::now());
+            }
+            local.header.lineage = self.lineage;
+            #[cfg(feature = "sha2")]
+            self.set_digest(&mut local);
 
-        // Update the `Reader` side with our new data.
-        self.remote = Arc::clone(&new);
-        let old = self.arc.swap(new);
+            // Create the new `Reader` T.
+            let new = Arc::new(local);
+
+            // Update the `Reader` side with our new data.
+            //
+            // If we were never `share()`'ed, we are the
+            // only one pushing, so there's no race to lose.
+            if !self.token.is_shared() {
+                self.remote = Arc::clone(&new);
+                let old = self.arc.swap(new);
+                #[cfg(feature = "testing")]
+                self.faults.reader_hold(&old);
+                return Ok(Some(old));
+            }
 
+            // Else, only if no `share()`'ed `Writer` pushed since our last push.
+            let old = self.arc.compare_and_swap(&self.remote, Arc::clone(&new));
+            if Arc::ptr_eq(&old, &self.remote) {
+                self.remote = new;
+                #[cfg(feature = "testing")]
+                self.faults.reader_hold(&old);
+                return Ok(Some(old));
+            }
+
+            // We lost the race, take our data back.
+            //
+            // INVARIANT: `new` was never stored, we are the only owner.
+            self.local = Arc::try_unwrap(new).ok();
+
+            if !self.retry.allows(retries) || !self.rebase_remote(old) {
+                return Err(ConflictError);
+            }
+            retries += 1;
+        }
+    }
+
+    /// The second half of a `push()`, re-initialize our local `Commit`.
+    ///
+    /// This attempts to reclaim `old` (the old head returned from
+    /// [`Writer::push_publish`]), and clones `remote` with `clone_fn`
+    /// otherwise. Without a `clone_fn`, this waits up to [`RECLAIM_TIMEOUT`]
+    /// until it is reclaimed, then gives up and poisons the `Writer`
+    /// until [`Writer::recover`] reclaims it.
+    ///
+    /// If it couldn't be reclaimed, the old head is returned
+    /// as well to be passed to the push hooks.
+    pub(crate) fn push_reclaim(
+        &mut self,
+        old: CommitRef<T>,
+        duration: Option<Duration>,
+        clone_fn: Option<fn(&T) -> T>,
+    ) -> (PushInfo, Option<CommitRef<T>>) {
         let timestamp_diff = self.remote.timestamp - old.timestamp;
 
-        // Return early if the user wants to deep-clone no matter what.
-        if CLONE {
-            self.local = Some((*self.remote).clone());
-            self.patches_old.clear();
-            return (
-                PushInfo {
-                    timestamp: self.remote.timestamp,
-                    commits: timestamp_diff,
-                    reclaimed: false,
-                },
-                None,
-            );
+        // Nothing can be merged with the `Writer` we were forked from
+        // anymore, so don't keep the base (which may be `old`) alive.
+        if self.fork_base.as_ref().is_some_and(ForkBase::orphaned) {
+            self.fork_base = None;
         }
 
-        // If the user wants to execute a function
-        // while waiting, do so and get the return value.
-        let return_value = function.map(|f| f());
+        // Reclaiming can be forced to fail, see `FaultInjector`.
+        #[cfg(feature = "testing")]
+        let fail = self.faults.reclaim_fail(duration);
+        #[cfg(not(feature = "testing"))]
+        let fail = false;
 
         // Try to reclaim data.
-        let (mut local, reclaimed) = match Arc::try_unwrap(old) {
+        let attempt = if fail { Err(old) } else { Arc::try_unwrap(old) };
+        let (mut local, reclaimed, old) = match attempt {
             // If there are no more dangling readers on the
             // old Arc we can cheaply reclaim the old data.
-            Ok(old) => (old, true),
+            Ok(old) => (old, true, None),
 
             // Else, if the user wants to
             // sleep and try again, do so.
             Err(old) => {
-                if let Some(duration) = duration {
-                    // Sleep.
-                    std::thread::sleep(duration);
-                    // Try again.
-                    if let Some(old) = Arc::into_inner(old) {
-                        (old, true)
-                    } else {
-                        ((*self.remote).clone(), false)
+                let attempt = match duration.filter(|_| !fail) {
+                    Some(duration) => {
+                        // Sleep.
+                        crate::sync::sleep(duration);
+                        // Try again.
+                        Arc::try_unwrap(old)
                     }
-                } else {
+                    None => Err(old),
+                };
+
+                match (attempt, clone_fn) {
+                    (Ok(old), _) => (old, true, None),
                     // Else, there are dangling readers left.
                     // As to not wait on them, just expensively clone
                     // the inner data to have a mutually exclusive
                     // up-to-date local copy.
-                    ((*self.remote).clone(), false)
+                    (Err(old), Some(clone_fn)) => (self.clone_remote(clone_fn), false, Some(old)),
+                    // If we can't clone, we have to wait on them.
+                    (Err(old), None) => match wait_reclaim(old) {
+                        Ok(old) => (old, true, None),
+                        // They never let go, keep it for `recover()`.
+                        Err(old) => {
+                            self.unreclaimed = Some(Arc::clone(&old));
+                            self.poisoned = true;
+                            let push_info = PushInfo {
+                                timestamp: self.remote.timestamp,
+                                commits: timestamp_diff,
+                                reclaimed: false,
+                                compacted: false,
+                                conflict: false,
+                            };
+                            return (push_info, Some(old));
+                        }
+                    },
                 }
             }
         };
 
+        let mut compacted = false;
         if reclaimed {
-            // Re-apply patches to this old data.
-            for mut patch in self.patches_old.drain(..) {
-                patch.apply(&mut local.data, &self.remote.data);
-            }
-            // Set proper timestamp if we're reusing old data.
+            // Re-apply patches to this old data (or compact them).
+            compacted = self.sync_reclaimed(&mut local.data, clone_fn);
+            // Set proper timestamp (and header) if we're reusing old data.
             local.timestamp = self.remote.timestamp;
+            local.header.clone_from(&self.remote.header);
         } else {
             // Clear old patches.
             self.patches_old.clear();
@@ -314,6 +482,10 @@ impl<T: Clone> Writer<T> {
 
         // Re-initialize `self.local`.
         self.local = Some(local);
+        self.replayable = true;
+        self.rebased = false;
+        self.clone_sync = false;
+        self.sync_base = None;
 
         // Output how many commits we pushed.
         (
@@ -321,8 +493,148 @@ impl<T: Clone> Writer<T> {
                 timestamp: self.remote.timestamp,
                 commits: timestamp_diff,
                 reclaimed,
+                compacted,
+                conflict: false,
             },
-            return_value,
+            old,
         )
     }
 }
+
+impl<T: Clone, P: Apply<T>> Writer<T, P> {
+    #[inline]
+    /// [`push()`](Writer::push), but check the validator first
+    ///
+    /// This checks the local data against the validator set with
+    /// [`Writer::set_validator`] before [`Reader`]'s can see it.
+    ///
+    /// If the data is valid, this is the same as [`Writer::push`].
+    ///
+    /// If the data is invalid, nothing is pushed, the local data is
+    /// rolled back to [`Writer::head_remote`] as if [`Writer::pull`]
+    /// was called (all committed `Patch`'s are discarded, staged
+    /// `Patch`'s are kept) and a [`PushError`] is returned.
+    ///
+    /// If there is no validator or nothing to
+    /// push, this is the same as [`Writer::push`].
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w) = someday::new::<usize>(0);
+    /// w.set_validator(|n| if n % 2 == 0 { Ok(()) } else { Err("odd") });
+    ///
+    /// // Commits are not validated, only the push.
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.commit();
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.commit();
+    /// let push_info = w.try_push().unwrap();
+    /// assert_eq!(push_info.commits, 2);
+    /// assert_eq!(r.head().data, 2);
+    ///
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.commit();
+    /// let Err(PushError::Validate(error)) = w.try_push() else {
+    ///     panic!();
+    /// };
+    /// assert_eq!(error.timestamp, 3);
+    ///
+    /// // Nothing was pushed, and the `Writer` was rolled back.
+    /// assert_eq!(r.head().data, 2);
+    /// assert_eq!(*w.data(), 2);
+    /// assert_eq!(w.timestamp(), 2);
+    /// ```
+    ///
+    /// # Errors
+    /// This returns:
+    /// - [`PushError::Poison`] if the `Writer` is [poisoned](Writer::is_poisoned)
+    /// - [`PushError::Validate`] if the validator rejects the local data
+    /// - [`PushError::Conflict`] if a [`Writer::share`]'ed `Writer` pushed first and
+    ///   the commits could not be re-applied (the `Writer` is not rolled back)
+    pub fn try_push(&mut self) -> Result<PushInfo, PushError> {
+        if self.is_poisoned() {
+            return Err(PushError::Poison(PoisonError));
+        }
+
+        if !self.synced() {
+            if let Err(error) = self.validate() {
+                drop(self.pull());
+                return Err(PushError::Validate(error));
+            }
+        }
+
+        let (push_info, _, old) = self.push_inner::<false, ()>(None, None::<fn()>, self.clone_fn);
+        if push_info.conflict {
+            return Err(PushError::Conflict(ConflictError));
+        }
+        self.check_reclaimed(&push_info);
+        self.call_push_hooks(&push_info, old.as_ref());
+        Ok(push_info)
+    }
+
+    #[inline]
+    /// This function is the same as [`Writer::push()`]
+    /// but it will **always** clone the data
+    /// and not attempt to reclaim any old data.
+    ///
+    /// This is useful if you know reclaiming old data
+    /// and re-applying your commits would take longer and/or
+    /// be more expensive than cloning the data itself.
+    ///
+    /// Or if you know your `Reader`'s will be holding
+    /// onto the data for a long time, and reclaiming data
+    /// will be unlikely.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// # use std::{thread::*,time::*};
+    /// let (r, mut w) = someday::new::<String>("".into());
+    /// w.add(Patch::Ptr(|w, _| w.push_str("abc")));
+    /// w.commit();
+    ///
+    /// let commit = r.head();
+    /// spawn(move || {
+    ///     // This `Reader` will hold onto the old data forever.
+    ///     let moved = commit;
+    ///     loop { std::thread::park(); }
+    /// });
+    ///
+    /// // Always clone data, don't wait.
+    /// let push_info = w.push_clone();
+    /// // We pushed 1 commit.
+    /// assert_eq!(push_info.commits, 1);
+    /// assert_eq!(push_info.reclaimed, false);
+    /// ```
+    pub fn push_clone(&mut self) -> PushInfo {
+        let (push_info, _, old) = self.push_inner::<true, ()>(None, None::<fn()>, Some(T::clone));
+        self.call_push_hooks(&push_info, old.as_ref());
+        push_info
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Free
+/// How long a `push()` without a `clone_fn` waits for [`Reader`]'s to let go of the old head.
+pub(crate) const RECLAIM_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often [`wait_reclaim`] tries to reclaim the old head.
+const RECLAIM_INTERVAL: Duration = Duration::from_micros(100);
+
+/// Wait until all [`Reader`]'s let go of `old`, then reclaim it.
+///
+/// This gives up and returns `old` after [`RECLAIM_TIMEOUT`], without
+/// the `std` feature (no sleeping), after as many attempts instead.
+fn wait_reclaim<T>(mut old: CommitRef<T>) -> Result<Commit<T>, CommitRef<T>> {
+    let mut waited = Duration::ZERO;
+    loop {
+        match Arc::try_unwrap(old) {
+            Ok(old) => return Ok(old),
+            Err(arc) => old = arc,
+        }
+        if waited >= RECLAIM_TIMEOUT {
+            return Err(old);
+        }
+        crate::sync::yield_now();
+        crate::sync::sleep(RECLAIM_INTERVAL);
+        waited += RECLAIM_INTERVAL;
+    }
+}
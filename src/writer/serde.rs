@@ -3,9 +3,6 @@
 //---------------------------------------------------------------------------------------------------- Use
 #[cfg(any(feature = "serde", feature = "bincode", feature = "borsh",))]
 use crate::{Commit, Writer};
-#[allow(unused_imports)]
-// docs
-// use crate::Commit;
 
 //---------------------------------------------------------------------------------------------------- Writer
 #[cfg(feature = "serde")]
@@ -96,7 +93,7 @@ where
     /// // Decode into a `Commit`.
     /// let encoded = bincode::encode_to_vec(&w, config).unwrap();
     /// let decoded: Commit<String> = bincode::decode_from_slice(&encoded, config).unwrap().0;
-    /// assert_eq!(decoded, Commit { timestamp: 1, data: String::from("hello world!") });
+    /// assert_eq!(decoded, Commit { timestamp: 1, data: String::from("hello world!"), header: CommitHeader::new() });
     ///
     /// // Decode directly into a `Writer<T>`.
     /// let writer: Writer<String> = bincode::decode_from_slice(&encoded, config).unwrap().0;
@@ -116,7 +113,7 @@ where
     T: Clone + borsh::BorshSerialize,
 {
     /// This will serialize the latest [`Commit`] of the [`Writer`].
-    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
         Commit::serialize(self.head(), writer)
     }
 }
@@ -142,14 +139,14 @@ where
     /// // Decode into a `Commit`.
     /// let encoded = borsh::to_vec(&w).unwrap();
     /// let decoded: Commit<String> = borsh::from_slice(&encoded).unwrap();
-    /// assert_eq!(decoded, Commit { timestamp: 1, data: String::from("hello world!") });
+    /// assert_eq!(decoded, Commit { timestamp: 1, data: String::from("hello world!"), header: CommitHeader::new() });
     ///
     /// // Decode directly into a `Writer<T>`.
     /// let writer: Writer<String> = borsh::from_slice(&encoded).unwrap();
     /// assert_eq!(writer.timestamp(), 1);
     /// assert_eq!(writer.data(), "hello world!");
     /// ```
-    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
         Commit::deserialize_reader(reader).map(Self::from)
     }
 }
@@ -0,0 +1,315 @@
+//! `Writer<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::sync::Arc;
+
+use crate::{
+    apply::Apply,
+    commit::{Commit, CommitRef},
+    writer::Writer,
+};
+
+#[allow(unused_imports)] // docs
+use crate::{Patch, PushError, PushInfo, Reader};
+
+//---------------------------------------------------------------------------------------------------- RetryPolicy
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// How many times a [`Writer`] retries a lost `push()`
+///
+/// With [`Writer::share`], multiple `Writer`'s push to the same [`Reader`]'s.
+/// If another `Writer` pushed first, the `Writer`'s committed [`Patch`]'s
+/// are re-applied on top of the new head and the push is retried.
+///
+/// The default is [`RetryPolicy::Limit`] of `3`.
+pub enum RetryPolicy {
+    /// Never retry, give up on the first lost push.
+    Never,
+    /// Retry at most this many times.
+    Limit(usize),
+    /// Retry until the push succeeds.
+    Forever,
+}
+
+impl RetryPolicy {
+    #[must_use]
+    /// If another retry is allowed after `retries` retries.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// assert!(!RetryPolicy::Never.allows(0));
+    /// assert!(RetryPolicy::Limit(1).allows(0));
+    /// assert!(!RetryPolicy::Limit(1).allows(1));
+    /// assert!(RetryPolicy::Forever.allows(usize::MAX));
+    /// ```
+    pub const fn allows(self, retries: usize) -> bool {
+        match self {
+            Self::Never => false,
+            Self::Limit(limit) => retries < limit,
+            Self::Forever => true,
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Same as `RetryPolicy::Limit(3)`.
+    fn default() -> Self {
+        Self::Limit(3)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Writer
+impl<T: Clone, P: Apply<T>> Writer<T, P> {
+    #[must_use]
+    /// Create another [`Writer`] that pushes to the same [`Reader`]'s
+    ///
+    /// Unlike [`Writer::fork`], the new `Writer` is not disconnected,
+    /// both `Writer`'s (and any other `share()`'ed `Writer`'s) can
+    /// [`push()`](Writer::push) to the same `Reader`'s.
+    ///
+    /// The new `Writer` starts at the current [`Reader::head`],
+    /// and inherits the [`RetryPolicy`], [`DropPolicy`](crate::DropPolicy)
+    /// and [`CompactPolicy`](crate::CompactPolicy) of this `Writer`.
+    ///
+    /// It starts without [`Metadata`](crate::Metadata), the head's `Metadata`
+    /// belongs to the `Writer` that pushed it, see [`Writer::commit_with`].
+    ///
+    /// ## Optimistic pushes
+    /// Once `share()`'ed, `push()` only succeeds if the `Reader`'s head is
+    /// still the head the `Writer` last saw, i.e. [`Writer::head_remote`].
+    /// This applies to both `Writer`'s, even after the other one is dropped.
+    ///
+    /// If another `Writer` pushed first, the committed [`Patch`]'s
+    /// are re-applied on top of the new head (like a `git pull --rebase`),
+    /// and the push is retried according to the [`RetryPolicy`].
+    ///
+    /// If the `Writer` runs out of retries, or the `Patch`'s cannot be
+    /// re-applied (e.g. after [`Writer::overwrite`], [`Writer::add_commit_push`]
+    /// or a failing [`Patch::Try`]), nothing is pushed and the commits are kept
+    /// locally, [`Writer::try_push`] returns [`PushError::Conflict`] in this case.
+    ///
+    /// `push()` and the other pushing functions return a [`PushInfo`]
+    /// with [`PushInfo::conflict`] set instead.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w1) = someday::new::<usize>(0);
+    /// let mut w2 = w1.share();
+    /// w2.set_retry_policy(RetryPolicy::Never);
+    ///
+    /// w1.add_commit(|w, _| *w += 1);
+    /// w2.add_commit(|w, _| *w += 2);
+    /// w1.push();
+    ///
+    /// // `w2` gave up, it is still ahead.
+    /// let error = w2.try_push().unwrap_err();
+    /// assert!(matches!(error, PushError::Conflict(_)));
+    /// assert_eq!(r.head().data, 1);
+    /// assert!(w2.ahead());
+    ///
+    /// // `push()` reports it instead.
+    /// let push_info = w2.push();
+    /// assert!(push_info.conflict);
+    /// assert_eq!(push_info.commits, 0);
+    /// assert!(w2.ahead());
+    /// ```
+    ///
+    /// Note that re-applied data is not checked by [`Writer::set_validator`].
+    ///
+    /// ## Dropping
+    /// [`Reader::writer_dropped`] only returns `true` once all `Writer`'s are dropped.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w1) = someday::new::<Vec<usize>>(vec![]);
+    /// let mut w2 = w1.share();
+    ///
+    /// w1.add_commit(|w, _| w.push(1));
+    /// w2.add_commit(|w, _| w.push(2));
+    ///
+    /// // `w1` pushes first.
+    /// w1.push();
+    /// assert_eq!(r.head().data, [1]);
+    ///
+    /// // `w2` lost the race, so its commit is
+    /// // re-applied on top of `w1`'s and retried.
+    /// let push_info = w2.push();
+    /// assert_eq!(push_info.commits, 1);
+    /// assert_eq!(r.head().data, [1, 2]);
+    /// assert_eq!(r.head().timestamp, 2);
+    ///
+    /// // `w1` can catch up.
+    /// assert!(w1.fetch());
+    /// assert_eq!(*w1.data(), [1, 2]);
+    ///
+    /// drop(w1);
+    /// assert!(!r.writer_dropped());
+    /// drop(w2);
+    /// assert!(r.writer_dropped());
+    /// ```
+    ///
+    /// ## `new_with()`
+    /// Each `Writer` holds onto the head it last pushed or pulled, so the
+    /// old head may never be reclaimed, waiting for it could hang forever.
+    ///
+    /// Instead of waiting, a `Writer` created with [`new_with()`](crate::new_with)
+    /// clones `T` after it was `share()`'ed, like any other `Writer`.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w1) = someday::new_with(|| 0_usize);
+    /// let mut w2 = w1.share();
+    ///
+    /// // `w2` holds onto the old head, so this clones.
+    /// w1.add_commit(|w, _| *w += 1);
+    /// assert!(!w1.push().reclaimed);
+    /// assert_eq!(r.head().data, 1);
+    /// ```
+    pub fn share(&mut self) -> Self {
+        // Our old head is held by the new `Writer`, don't wait on it.
+        let clone_fn = *self.clone_fn.get_or_insert(T::clone);
+
+        let remote = self.arc.load_full();
+        let local = (*remote).clone();
+
+        let mut writer = Self::from_parts(
+            self.token.share(),
+            local,
+            remote,
+            Arc::clone(&self.arc),
+            Some(clone_fn),
+        );
+        // The new `Writer` has not committed anything, so it has no `Metadata` yet.
+        writer.lineage = self.lineage;
+        writer.retry = self.retry;
+        writer.drop_policy = self.drop_policy;
+        writer.compact = self.compact;
+        #[cfg(feature = "std")]
+        writer.costs.clone_from(&self.costs);
+        writer.determinism = self.determinism;
+        #[cfg(feature = "sha2")]
+        writer.encode.clone_from(&self.encode);
+        #[cfg(feature = "testing")]
+        writer.faults.injector.clone_from(&self.faults.injector);
+        writer
+    }
+}
+
+impl<T, P: Apply<T>> Writer<T, P> {
+    /// Set the [`RetryPolicy`] used when a `push()` loses to a [`Writer::share`]'ed `Writer`
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w1) = someday::new::<usize>(0);
+    /// let mut w2 = w1.share();
+    /// w2.set_retry_policy(RetryPolicy::Never);
+    ///
+    /// w1.add_commit(|w, _| *w += 1);
+    /// w2.add_commit(|w, _| *w += 1);
+    /// w1.push();
+    ///
+    /// // `w2` gives up immediately, its commit is kept.
+    /// assert!(w2.try_push().is_err());
+    /// assert_eq!(w2.timestamp(), 1);
+    /// assert!(w2.ahead());
+    /// assert_eq!(r.head().data, 1);
+    /// ```
+    pub fn set_retry_policy(&mut self, retry: RetryPolicy) {
+        self.retry = retry;
+    }
+
+    #[must_use]
+    /// The current [`RetryPolicy`]
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, w) = someday::new(());
+    /// assert_eq!(w.retry_policy(), RetryPolicy::default());
+    /// ```
+    pub const fn retry_policy(&self) -> RetryPolicy {
+        self.retry
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Catch up with a push from a [`Writer::share`]'ed `Writer`
+    ///
+    /// If another `Writer` pushed since this `Writer`'s last
+    /// `push()`, this moves the `Writer` onto the new [`Reader`] head,
+    /// re-applying any committed [`Patch`]'s on top of it.
+    ///
+    /// Staged `Patch`'s are kept as is.
+    ///
+    /// This returns `true` if the `Writer` moved onto the new head.
+    ///
+    /// This returns `false` if nothing was pushed, or if the committed
+    /// `Patch`'s cannot be re-applied, in which case nothing is changed.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (r, mut w1) = someday::new::<String>("".into());
+    /// let mut w2 = w1.share();
+    /// assert!(!w2.fetch());
+    ///
+    /// w1.add_commit_push(|w, _| w.push_str("hello"));
+    ///
+    /// w2.add_commit(|w, _| w.push_str(" world"));
+    /// assert!(w2.fetch());
+    /// assert_eq!(w2.data(), "hello world");
+    /// assert_eq!(w2.timestamp(), 2);
+    /// ```
+    pub fn fetch(&mut self) -> bool {
+        let head = self.arc.load_full();
+        if Arc::ptr_eq(&head, &self.remote) {
+            return false;
+        }
+        self.rebase_remote(head)
+    }
+
+    /// Re-apply the committed `Patch`'s on top of `head`, and make it our `remote`.
+    ///
+    /// This is used after losing a push race to
+    /// a `share()`'ed `Writer`, and in `fetch()`.
+    ///
+    /// This returns `false` and does nothing if the
+    /// committed `Patch`'s cannot be re-applied,
+    /// or if `T` cannot be cloned (see `new_with()`).
+    pub(crate) fn rebase_remote(&mut self, head: CommitRef<T>) -> bool {
+        // The "sync" `Patch` from e.g. `overwrite()` relies
+        // on the _next_ pushed data, it cannot be re-applied.
+        if !self.replayable {
+            return false;
+        }
+        let Some(clone_fn) = self.clone_fn else {
+            return false;
+        };
+
+        // Re-apply onto a separate copy, if a `Patch`
+        // panics, the `Writer` is left untouched.
+        let mut data = clone_fn(&head.data);
+        for patch in &mut self.patches_old {
+            if patch.try_apply(&mut data, &head.data).is_err() {
+                return false;
+            }
+        }
+
+        let timestamp = head.timestamp + self.timestamp_diff();
+        let header = self
+            .local
+            .take()
+            .map(|local| local.header)
+            .unwrap_or_default();
+        self.local = Some(Commit {
+            timestamp,
+            data,
+            header,
+        });
+        self.remote = head;
+
+        true
+    }
+}
@@ -0,0 +1,386 @@
+//! `Writer<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::{
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+use core::time::Duration;
+use std::time::Instant;
+
+use crate::{
+    apply::Apply,
+    commit::{Commit, CommitRef},
+    info::{CommitInfo, PushInfo},
+    writer::Writer,
+};
+
+#[allow(unused_imports)] // docs
+use crate::{CompactPolicy, Reader, Transaction};
+
+//---------------------------------------------------------------------------------------------------- Histogram
+/// How many buckets a [`Histogram`] has.
+const BUCKETS: usize = 32;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+/// A histogram of [`Duration`]'s
+///
+/// The buckets are powers of 2 in nanoseconds, bucket `0` holds
+/// `0ns`, bucket `i` holds `[2^(i-1), 2^i)ns`, and the last
+/// bucket holds everything at or above `2^30ns` (~1 second).
+///
+/// ```rust
+/// # use someday::*;
+/// let (_, mut w) = someday::new::<usize>(0);
+/// w.add(Patch::Ptr(|w, _| *w += 1));
+/// w.commit();
+/// w.push();
+///
+/// let latency = w.stats().push_latency;
+/// assert_eq!(latency.count, 1);
+/// assert_eq!(latency.buckets.iter().sum::<usize>(), 1);
+/// assert_eq!(latency.mean(), Some(latency.total));
+/// assert!(latency.percentile(50).unwrap() <= latency.max);
+/// ```
+pub struct Histogram {
+    /// How many [`Duration`]'s were recorded.
+    pub count: usize,
+    /// The sum of all recorded [`Duration`]'s.
+    pub total: Duration,
+    /// The longest recorded [`Duration`].
+    pub max: Duration,
+    /// How many [`Duration`]'s fell into each bucket.
+    pub buckets: [usize; BUCKETS],
+}
+
+impl Histogram {
+    #[must_use]
+    /// The average recorded [`Duration`]
+    ///
+    /// This returns `None` if nothing was recorded.
+    pub fn mean(&self) -> Option<Duration> {
+        let Ok(count) = u32::try_from(self.count) else {
+            return None;
+        };
+        self.total.checked_div(count)
+    }
+
+    #[must_use]
+    /// An upper bound on the `p`th percentile recorded [`Duration`]
+    ///
+    /// This is the end of the bucket the percentile falls
+    /// into, capped at [`Histogram::max`], `p` is capped at `100`.
+    ///
+    /// This returns `None` if nothing was recorded.
+    pub fn percentile(&self, p: u8) -> Option<Duration> {
+        // The amount of `Duration`'s at or below the percentile, times 100.
+        let target = self.count.saturating_mul(usize::from(p.min(100)));
+
+        let mut seen = 0;
+        for (i, n) in (0..).zip(self.buckets) {
+            seen += n;
+            if seen != 0 && seen.saturating_mul(100) >= target {
+                let end = Duration::from_nanos(u64::pow(2, i));
+                return Some(end.min(self.max));
+            }
+        }
+
+        None
+    }
+
+    /// Record a [`Duration`].
+    fn record(&mut self, duration: Duration) {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        let bucket = usize::try_from(u64::BITS - nanos.leading_zeros()).unwrap_or(BUCKETS);
+
+        if let Some(n) = self.buckets.get_mut(bucket.min(BUCKETS - 1)) {
+            *n += 1;
+        }
+        self.count += 1;
+        self.total = self.total.saturating_add(duration);
+        self.max = self.max.max(duration);
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- WriterStats
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+/// Runtime statistics of a [`Writer`]
+///
+/// This is returned by [`Writer::stats`], the counters accumulate
+/// since the `Writer` was created, or since [`Writer::reset_stats`].
+///
+/// Only `push()`'s that pushed commits are counted.
+///
+/// With the `metrics` feature, the same statistics are also
+/// emitted through the [`metrics`](https://docs.rs/metrics) facade as they happen:
+///
+/// | Name                     | Kind      | Field |
+/// |--------------------------|-----------|-------|
+/// | `someday.commits`        | counter   | [`WriterStats::commits`]
+/// | `someday.patches`        | counter   | [`WriterStats::patches`]
+/// | `someday.transactions`   | counter   | [`WriterStats::transactions`]
+/// | `someday.pushes`         | counter   | [`WriterStats::pushes`]
+/// | `someday.reclaimed`      | counter   | [`WriterStats::reclaimed`]
+/// | `someday.cloned`         | counter   | [`WriterStats::cloned`]
+/// | `someday.compacted`      | counter   | [`WriterStats::compacted`]
+/// | `someday.pulls`          | counter   | [`WriterStats::pulls`]
+/// | `someday.live_versions`  | gauge     | [`WriterStats::live_versions`], set on `push()`
+/// | `someday.push_latency`   | histogram | [`WriterStats::push_latency`], in seconds
+/// | `someday.replay_time`    | histogram | [`WriterStats::replay_time`], in seconds
+///
+/// ```rust
+/// # use someday::*;
+/// let (r, mut w) = someday::new::<usize>(0);
+///
+/// w.add(Patch::Ptr(|w, _| *w += 1));
+/// w.add(Patch::Ptr(|w, _| *w += 1));
+/// w.commit();
+/// w.push();
+///
+/// // A `Reader` holds onto the head, so it gets cloned.
+/// let head = r.head();
+/// w.add(Patch::Ptr(|w, _| *w += 1));
+/// w.commit();
+/// w.push();
+///
+/// let stats = w.stats();
+/// assert_eq!(stats.commits, 2);
+/// assert_eq!(stats.patches, 3);
+/// assert_eq!(stats.pushes, 2);
+/// assert_eq!(stats.reclaimed, 1);
+/// assert_eq!(stats.cloned, 1);
+/// assert_eq!(stats.reclaim_rate(), Some(0.5));
+/// assert_eq!(stats.replay_time.count, 1);
+///
+/// // The current head, and the one `head` holds.
+/// assert_eq!(stats.live_versions, 2);
+/// drop(head);
+/// assert_eq!(w.stats().live_versions, 1);
+/// ```
+pub struct WriterStats {
+    /// How long the statistics have been accumulating.
+    pub elapsed: Duration,
+    /// How many times [`Writer::commit`] (or the like) committed.
+    pub commits: usize,
+    /// How many `Patch`'s were applied in those commits.
+    pub patches: usize,
+    /// How many [`Transaction`]'s made changes.
+    pub transactions: usize,
+    /// How many `push()`'s pushed commits.
+    pub pushes: usize,
+    /// How many `push()`'s reclaimed the old data.
+    pub reclaimed: usize,
+    /// How many `push()`'s cloned the data instead, because
+    /// [`Reader`]'s were still holding onto the old data.
+    pub cloned: usize,
+    /// How many `push()`'s compacted the `Patch`'s, see [`CompactPolicy`].
+    pub compacted: usize,
+    /// How many times [`Writer::pull`] pulled commits.
+    pub pulls: usize,
+    /// How many pushed `Commit`'s are still alive.
+    ///
+    /// This is the [`Reader`]'s current head, plus the old heads the
+    /// `Writer` could not reclaim which are still being held onto.
+    pub live_versions: usize,
+    /// How long each `push()` took.
+    pub push_latency: Histogram,
+    /// How long re-applying the `Patch`'s onto reclaimed data took.
+    pub replay_time: Histogram,
+}
+
+impl WriterStats {
+    #[must_use]
+    #[allow(
+        clippy::as_conversions,
+        clippy::cast_precision_loss,
+        clippy::float_arithmetic
+    )] // ratio
+    /// The fraction of `push()`'s that reclaimed the old data
+    ///
+    /// This returns `None` if nothing was pushed.
+    pub fn reclaim_rate(&self) -> Option<f64> {
+        (self.pushes != 0).then(|| self.reclaimed as f64 / self.pushes as f64)
+    }
+
+    #[must_use]
+    #[allow(
+        clippy::as_conversions,
+        clippy::cast_precision_loss,
+        clippy::float_arithmetic
+    )] // ratio
+    /// How many commits happened per second on average
+    ///
+    /// This returns `None` if no time has passed.
+    pub fn commit_rate(&self) -> Option<f64> {
+        let secs = self.elapsed.as_secs_f64();
+        (secs != 0.0).then(|| self.commits as f64 / secs)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Stats
+/// The [`WriterStats`] a [`Writer`] accumulates.
+pub(crate) struct Stats<T> {
+    /// The counters and histograms, `elapsed` and `live_versions` are set on read.
+    counters: WriterStats,
+    /// When the statistics started accumulating.
+    started: Instant,
+    /// Old heads that could not be reclaimed, for `live_versions`.
+    old: Vec<Weak<Commit<T>>>,
+}
+
+impl<T> Stats<T> {
+    /// Start accumulating new statistics.
+    pub(crate) fn new() -> Self {
+        Self {
+            counters: WriterStats::default(),
+            started: Instant::now(),
+            old: Vec::new(),
+        }
+    }
+
+    /// Return a snapshot of the statistics.
+    fn snapshot(&self) -> WriterStats {
+        WriterStats {
+            elapsed: self.started.elapsed(),
+            live_versions: self.live_versions(),
+            ..self.counters
+        }
+    }
+
+    /// The current head, plus the old heads still alive.
+    fn live_versions(&self) -> usize {
+        1 + self
+            .old
+            .iter()
+            .filter(|old| old.strong_count() != 0)
+            .count()
+    }
+
+    /// Record a commit.
+    pub(crate) fn commit(&mut self, commit_info: &CommitInfo) {
+        self.counters.commits += 1;
+        self.counters.patches += commit_info.patches;
+
+        #[cfg(feature = "metrics")]
+        {
+            metrics::counter!("someday.commits").increment(1);
+            metrics::counter!("someday.patches").increment(to_u64(commit_info.patches));
+        }
+    }
+
+    /// Record a [`Transaction`] that made changes.
+    pub(crate) fn transaction(&mut self) {
+        self.counters.transactions += 1;
+
+        #[cfg(feature = "metrics")]
+        metrics::counter!("someday.transactions").increment(1);
+    }
+
+    /// Record a `push()` that took `latency`, `old` is the old head if it wasn't reclaimed.
+    pub(crate) fn push(
+        &mut self,
+        push_info: &PushInfo,
+        old: Option<&CommitRef<T>>,
+        latency: Duration,
+    ) {
+        if push_info.commits == 0 {
+            return;
+        }
+
+        self.old.retain(|old| old.strong_count() != 0);
+        if let Some(old) = old {
+            self.old.push(Arc::downgrade(old));
+        }
+
+        self.counters.pushes += 1;
+        self.counters.reclaimed += usize::from(push_info.reclaimed);
+        self.counters.cloned += usize::from(!push_info.reclaimed);
+        self.counters.compacted += usize::from(push_info.compacted);
+        self.counters.push_latency.record(latency);
+
+        #[cfg(feature = "metrics")]
+        {
+            metrics::counter!("someday.pushes").increment(1);
+            metrics::counter!("someday.reclaimed").increment(u64::from(push_info.reclaimed));
+            metrics::counter!("someday.cloned").increment(u64::from(!push_info.reclaimed));
+            metrics::counter!("someday.compacted").increment(u64::from(push_info.compacted));
+            metrics::histogram!("someday.push_latency").record(latency);
+            let live_versions = u32::try_from(self.live_versions()).unwrap_or(u32::MAX);
+            metrics::gauge!("someday.live_versions").set(live_versions);
+        }
+    }
+
+    /// Record re-applying `Patch`'s onto reclaimed data.
+    pub(crate) fn replay(&mut self, elapsed: Duration) {
+        self.counters.replay_time.record(elapsed);
+
+        #[cfg(feature = "metrics")]
+        metrics::histogram!("someday.replay_time").record(elapsed);
+    }
+
+    /// Record a `pull()` that pulled commits.
+    pub(crate) fn pull(&mut self) {
+        self.counters.pulls += 1;
+
+        #[cfg(feature = "metrics")]
+        metrics::counter!("someday.pulls").increment(1);
+    }
+}
+
+#[cfg(feature = "metrics")]
+/// Saturating `usize` to `u64` conversion for `metrics` counters.
+fn to_u64(n: usize) -> u64 {
+    u64::try_from(n).unwrap_or(u64::MAX)
+}
+
+//---------------------------------------------------------------------------------------------------- Writer
+impl<T, P: Apply<T>> Writer<T, P> {
+    #[must_use]
+    /// Return the [`WriterStats`] accumulated so far
+    ///
+    /// This requires the `stats` feature.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, mut w) = someday::new::<usize>(0);
+    ///
+    /// let mut tx = w.tx();
+    /// *tx += 1;
+    /// drop(tx);
+    /// w.push();
+    /// w.pull();
+    ///
+    /// let stats = w.stats();
+    /// assert_eq!(stats.transactions, 1);
+    /// assert_eq!(stats.commits, 1);
+    /// assert_eq!(stats.pushes, 1);
+    /// assert_eq!(stats.pulls, 0);
+    /// ```
+    pub fn stats(&self) -> WriterStats {
+        self.stats.snapshot()
+    }
+
+    /// Reset the [`WriterStats`] accumulated so far
+    ///
+    /// Old heads that are still alive are still counted
+    /// in [`WriterStats::live_versions`].
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, mut w) = someday::new::<usize>(0);
+    ///
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// w.commit();
+    /// assert_eq!(w.stats().commits, 1);
+    ///
+    /// w.reset_stats();
+    /// assert_eq!(w.stats().commits, 0);
+    /// ```
+    pub fn reset_stats(&mut self) {
+        let old = core::mem::take(&mut self.stats.old);
+        self.stats = Stats {
+            old,
+            ..Stats::new()
+        };
+    }
+}
@@ -2,13 +2,13 @@
 
 //---------------------------------------------------------------------------------------------------- Use
 
-use crate::{writer::Writer, Timestamp};
+use crate::{apply::Apply, writer::Writer, Timestamp};
 
 #[allow(unused_imports)] // docs
 use crate::{Commit, CommitRef, Reader};
 
 //---------------------------------------------------------------------------------------------------- Writer
-impl<T: Clone> Writer<T> {
+impl<T, P: Apply<T>> Writer<T, P> {
     #[inline]
     #[allow(clippy::missing_panics_doc)]
     /// If the [`Writer`]'s local [`Commit`] is different than the [`Reader`]'s
@@ -94,7 +94,7 @@ impl<T: Clone> Writer<T> {
     /// assert!(w.ahead());
     /// ```
     pub fn ahead(&self) -> bool {
-        self.local_as_ref().ahead(&*self.remote)
+        self.timestamp() > self.remote.timestamp
     }
 
     #[inline]
@@ -103,6 +103,8 @@ impl<T: Clone> Writer<T> {
     ///
     /// This takes any type of `Commit`, so either [`CommitRef`] or [`Commit`] can be used as input.
     ///
+    /// This is always `false` if they are not [`Commit::comparable`].
+    ///
     /// ```rust
     /// # use someday::*;
     /// let (_, mut w) = someday::new::<String>("".into());
@@ -119,12 +121,13 @@ impl<T: Clone> Writer<T> {
     /// let fake_commit = Commit {
     ///     timestamp: 1,
     ///     data: String::new(),
+    ///     header: CommitHeader::new(),
     /// };
     ///
     /// // Writer is ahead of that commit.
     /// assert!(w.ahead_of(&fake_commit));
     /// ```
-    pub const fn ahead_of(&self, commit: &Commit<T>) -> bool {
+    pub fn ahead_of(&self, commit: &Commit<T>) -> bool {
         self.local_as_ref().ahead(commit)
     }
 
@@ -134,6 +137,8 @@ impl<T: Clone> Writer<T> {
     ///
     /// This takes any type of `Commit`, so either [`CommitRef`] or [`Commit`] can be used as input.
     ///
+    /// This is always `false` if they are not [`Commit::comparable`].
+    ///
     /// ```rust
     /// # use someday::*;
     /// let (_, mut w) = someday::new::<String>("".into());
@@ -145,12 +150,13 @@ impl<T: Clone> Writer<T> {
     /// let fake_commit = Commit {
     ///     timestamp: 1000,
     ///     data: String::new(),
+    ///     header: CommitHeader::new(),
     /// };
     ///
     /// // Writer is behind that commit.
     /// assert!(w.behind(&fake_commit));
     /// ```
-    pub const fn behind(&self, commit: &Commit<T>) -> bool {
+    pub fn behind(&self, commit: &Commit<T>) -> bool {
         self.local_as_ref().behind(commit)
     }
 
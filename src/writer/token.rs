@@ -1,41 +1,151 @@
 //! `Writer<T>`
 
 //---------------------------------------------------------------------------------------------------- Use
-use std::{
-    sync::atomic::{AtomicBool, Ordering},
-    sync::Arc,
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+#[cfg(any(loom, feature = "std"))]
+use crate::sync::Condvar;
+use crate::{
+    sync::{lock, Arc, AtomicBool, AtomicUsize, Mutex, MutexGuard, Ordering},
+    writer::WriterState,
 };
 
+//---------------------------------------------------------------------------------------------------- Shared
+/// A function called once the `Writer` is dead.
+pub(crate) type DeadCallback = Box<dyn FnOnce() + Send + 'static>;
+
+/// The state shared between all copies of a `WriterToken`.
+struct Shared {
+    /// How many `Writer`'s are alive?
+    ///
+    /// This is usually `1`, more with `Writer::share()`.
+    /// The `Writer` is dead once this reaches `0`.
+    writers: AtomicUsize,
+    /// Has a `Writer` ever been `share()`'ed?
+    ///
+    /// This is never unset, a `share()`'ed `Writer` may
+    /// have pushed before being dropped.
+    sharing: AtomicBool,
+    /// State that changes when the `Writer` dies.
+    ///
+    /// This lock is also held when waiting on/notifying `condvar`.
+    inner: Mutex<Inner>,
+    /// Notified each time the `Writer` dies.
+    #[cfg(any(loom, feature = "std"))]
+    condvar: Condvar,
+}
+
+/// The locked part of `Shared`.
+#[derive(Default)]
+struct Inner {
+    /// Functions to call the next time the `Writer` dies.
+    callbacks: Vec<DeadCallback>,
+    /// The reason passed to `Writer::close()` by the last `Writer` that died.
+    ///
+    /// `None` if it was dropped without closing.
+    closed: Option<String>,
+}
+
+impl Shared {
+    /// Lock `inner`, ignoring poison (it cannot be left invalid).
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        lock(&self.inner)
+    }
+
+    /// The `Writer` just died, wake waiters and call the callbacks.
+    fn notify_dead(&self, mut inner: MutexGuard<'_, Inner>) {
+        let callbacks = core::mem::take(&mut inner.callbacks);
+        drop(inner);
+        #[cfg(any(loom, feature = "std"))]
+        self.condvar.notify_all();
+
+        // INVARIANT: called without the lock, such
+        // that callbacks can use the token themselves.
+        for callback in callbacks {
+            callback();
+        }
+    }
+}
+
+impl core::fmt::Debug for Shared {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Shared")
+            .field("writers", &self.writers)
+            .finish_non_exhaustive()
+    }
+}
+
 //---------------------------------------------------------------------------------------------------- Writer
-#[derive(Clone, Debug)]
-#[repr(transparent)]
+#[derive(Debug)]
 /// Token representing a certain `Writer`, and if it has been dropped.
+///
+/// Cloning this token creates a _non-owning_ copy (for `Reader`'s),
+/// dropping it will not affect whether the `Writer` is dead or not.
 pub(crate) struct WriterToken {
-    /// Is the `Writer` dead?
+    /// State shared with all other copies of this token.
+    shared: Arc<Shared>,
+    /// Is this token owned by a `Writer`?
     ///
-    /// Only set to `true` when we are `drop()`'ed.
-    dead: Arc<AtomicBool>,
+    /// Only owning tokens decrement `writers` when `drop()`'ed.
+    owner: bool,
+    /// The reason to set when this token is dropped, see `Writer::close()`.
+    closed: Option<String>,
 }
 
 impl WriterToken {
-    /// Return a new `Self` with a new `Arc(false)`.
+    /// Return a new owning `Self` with 1 live `Writer`.
     pub(crate) fn new() -> Self {
+        let shared = Shared {
+            writers: AtomicUsize::new(1),
+            sharing: AtomicBool::new(false),
+            inner: Mutex::new(Inner::default()),
+            #[cfg(any(loom, feature = "std"))]
+            condvar: Condvar::new(),
+        };
+
+        Self {
+            shared: Arc::new(shared),
+            owner: true,
+            closed: None,
+        }
+    }
+
+    /// Return a new owning `Self` for another live `Writer`.
+    ///
+    /// Relaxed ordering, like `Arc::clone()`.
+    pub(crate) fn share(&self) -> Self {
+        self.shared.writers.fetch_add(1, Ordering::Relaxed);
+        self.shared.sharing.store(true, Ordering::Relaxed);
         Self {
-            dead: Arc::new(AtomicBool::new(false)),
+            shared: Arc::clone(&self.shared),
+            owner: true,
+            closed: None,
         }
     }
 
+    /// Turn a non-owning token into an owning one.
+    ///
+    /// This must only be called after a successful `try_revive()`.
+    pub(crate) const fn into_owner(mut self) -> Self {
+        self.owner = true;
+        self
+    }
+
     /// If the `Writer` is dead, try reviving it.
     ///
     /// If this returns `true`, if means the `Writer` is revived,
     /// and the caller has exclusive access, they can "become" the Writer.
     ///
     /// Acquire + Relaxed ordering.
-    pub(crate) fn try_revive(&self) -> Option<WriterReviveToken> {
+    pub(crate) fn try_revive(&self) -> Option<WriterReviveToken<'_>> {
         if self
-            .dead
-            .compare_exchange(true, false, Ordering::Acquire, Ordering::Relaxed)
-            == Ok(true)
+            .shared
+            .writers
+            .compare_exchange(0, 1, Ordering::Acquire, Ordering::Relaxed)
+            == Ok(0)
         {
             Some(WriterReviveToken::new(self))
         } else {
@@ -44,23 +154,118 @@ impl WriterToken {
     }
 
     #[must_use]
-    /// Is the `Writer` who held onto this token dead?
+    /// Has this `Writer` ever been `share()`'ed?
+    ///
+    /// Relaxed ordering, a `Writer` only exists after the
+    /// `share()` that created it, which set this first.
+    pub(crate) fn is_shared(&self) -> bool {
+        self.shared.sharing.load(Ordering::Relaxed)
+    }
+
+    #[must_use]
+    /// Are all the `Writer`'s who held onto this token dead?
     ///
     /// Acquire ordering.
     pub(crate) fn is_dead(&self) -> bool {
-        self.dead.load(Ordering::Acquire)
+        self.shared.writers.load(Ordering::Acquire) == 0
+    }
+
+    /// Call `callback` the next time the `Writer` dies.
+    ///
+    /// If the `Writer` is already dead, it is called immediately.
+    pub(crate) fn on_dead(&self, callback: DeadCallback) {
+        let mut inner = self.shared.lock();
+        if self.is_dead() {
+            drop(inner);
+            callback();
+        } else {
+            inner.callbacks.push(callback);
+        }
+    }
+
+    /// Mark the `Writer` as closed with `reason` once this token is dropped.
+    pub(crate) fn close(&mut self, reason: String) {
+        self.closed = Some(reason);
+    }
+
+    #[must_use]
+    /// The current state of the `Writer`.
+    pub(crate) fn state(&self) -> WriterState {
+        let inner = self.shared.lock();
+        if !self.is_dead() {
+            return WriterState::Open;
+        }
+        inner
+            .closed
+            .clone()
+            .map_or(WriterState::Abandoned, WriterState::Closed)
+    }
+
+    #[cfg(feature = "std")]
+    /// Block until the `Writer` is dead.
+    pub(crate) fn wait_dead(&self) {
+        let mut inner = self.shared.lock();
+        while !self.is_dead() {
+            inner = self
+                .shared
+                .condvar
+                .wait(inner)
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+        }
+        drop(inner);
+    }
+
+    #[cfg(feature = "std")]
+    /// Block until the `Writer` is dead, or `timeout` has passed.
+    ///
+    /// Returns `true` if the `Writer` is dead.
+    pub(crate) fn wait_dead_timeout(&self, timeout: Duration) -> bool {
+        // Practically forever.
+        let Some(deadline) = Instant::now().checked_add(timeout) else {
+            self.wait_dead();
+            return true;
+        };
+
+        let mut inner = self.shared.lock();
+        while !self.is_dead() {
+            let Some(timeout) = deadline.checked_duration_since(Instant::now()) else {
+                break;
+            };
+            inner = self
+                .shared
+                .condvar
+                .wait_timeout(inner, timeout)
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .0;
+        }
+        drop(inner);
+        self.is_dead()
     }
 }
 
-impl From<Arc<AtomicBool>> for WriterToken {
-    fn from(dead: Arc<AtomicBool>) -> Self {
-        Self { dead }
+impl Clone for WriterToken {
+    /// Create a non-owning copy.
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+            owner: false,
+            closed: None,
+        }
     }
 }
 
 impl Drop for WriterToken {
     fn drop(&mut self) {
-        self.dead.store(true, Ordering::Release);
+        if !self.owner {
+            return;
+        }
+
+        // Locked such that `state()` sees `writers` and `closed` change together.
+        let mut inner = self.shared.lock();
+        if self.shared.writers.fetch_sub(1, Ordering::AcqRel) == 1 {
+            inner.closed = self.closed.take();
+            self.shared.notify_dead(inner);
+        }
     }
 }
 
@@ -81,7 +286,7 @@ pub(crate) struct WriterReviveToken<'a> {
     /// call its `drop()` when _we_ drop.
     writer_token: &'a WriterToken,
     /// If this is `true`, it will set the `Writer`
-    /// to dead `drop()`, it must manually be set
+    /// to dead on `drop()`, it must manually be set
     /// to `false` to avoid this.
     dead: bool,
 }
@@ -103,7 +308,13 @@ impl<'a> WriterReviveToken<'a> {
 
 impl Drop for WriterReviveToken<'_> {
     fn drop(&mut self) {
-        self.writer_token.dead.store(self.dead, Ordering::Release);
+        if self.dead {
+            let shared = &self.writer_token.shared;
+            let inner = shared.lock();
+            shared.writers.store(0, Ordering::Release);
+            // Waiters may have seen the `Writer` alive in the meanwhile.
+            shared.notify_dead(inner);
+        }
     }
 }
 
@@ -163,4 +374,47 @@ mod test {
         WriterReviveToken::revived(revive_token);
         assert!(!r.is_dead());
     }
+
+    #[test]
+    /// Assure non-owning copies do not set `dead` on drop.
+    fn non_owner_drop() {
+        let w = WriterToken::new();
+        let r = w.clone();
+
+        drop(r.clone());
+        assert!(!r.is_dead());
+        assert!(r.try_revive().is_none());
+
+        drop(w);
+        assert!(r.is_dead());
+    }
+
+    #[test]
+    /// Assure shared tokens are only dead once all are dropped.
+    fn share() {
+        let w1 = WriterToken::new();
+        assert!(!w1.is_shared());
+        let w2 = w1.share();
+        assert!(w1.is_shared());
+        assert!(w2.is_shared());
+        let r = w1.clone();
+
+        drop(w1);
+        assert!(!r.is_dead());
+        assert!(r.try_revive().is_none());
+
+        drop(w2);
+        assert!(r.is_dead());
+
+        let revive_token = r.try_revive().unwrap();
+        WriterReviveToken::revived(revive_token);
+        let w3 = r.into_owner();
+        let r = w3.clone();
+        assert!(!r.is_dead());
+        // Still shared, `w2` may have pushed.
+        assert!(w3.is_shared());
+
+        drop(w3);
+        assert!(r.is_dead());
+    }
 }
@@ -0,0 +1,107 @@
+//! `Writer<T>`
+
+//---------------------------------------------------------------------------------------------------- Use
+use alloc::boxed::Box;
+
+use crate::{
+    apply::Apply,
+    error::{BoxError, ValidateError},
+    writer::Writer,
+};
+
+#[allow(unused_imports)] // docs
+use crate::{Commit, CommitError, Patch, PushError, Reader};
+
+//---------------------------------------------------------------------------------------------------- Validator
+/// A function that checks if the data `T` is valid.
+pub(crate) type Validator<T> = Box<dyn Fn(&T) -> Result<(), BoxError> + Send + 'static>;
+
+//---------------------------------------------------------------------------------------------------- Writer
+impl<T, P: Apply<T>> Writer<T, P> {
+    /// Set a function that checks the invariants of the data `T`
+    ///
+    /// The validator is checked by the validated
+    /// operations, before their changes are kept:
+    /// - [`Writer::try_commit`] checks the data after applying the staged [`Patch`]'s
+    /// - [`Writer::try_push`] checks the data before [`Reader`]'s can see it
+    ///
+    /// If the validator returns an error, the changes are rolled back and a
+    /// [`CommitError::Validate`] or [`PushError::Validate`] is returned instead.
+    ///
+    /// The non-validated operations, e.g. [`Writer::commit`]
+    /// and [`Writer::push`], do not check the validator.
+    ///
+    /// Setting a new validator replaces the old one.
+    ///
+    /// The validator is not carried over to [`Writer::fork`]'s.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// // `0` must always be less than `1`.
+    /// let (r, mut w) = someday::new((0, 1));
+    /// w.set_validator(|(a, b)| {
+    ///     if a < b {
+    ///         Ok(())
+    ///     } else {
+    ///         Err(format!("{a} >= {b}"))
+    ///     }
+    /// });
+    ///
+    /// // This is okay.
+    /// w.add(Patch::Ptr(|w, _| w.1 += 10));
+    /// w.try_commit().unwrap();
+    /// w.try_push().unwrap();
+    /// assert_eq!(r.head().data, (0, 11));
+    ///
+    /// // This is not.
+    /// w.add(Patch::Ptr(|w, _| w.0 += 100));
+    /// let Err(CommitError::Validate(error)) = w.try_commit() else {
+    ///     panic!();
+    /// };
+    /// assert_eq!(error.timestamp, 2);
+    /// assert_eq!(error.error.to_string(), "100 >= 11");
+    ///
+    /// // The data was rolled back.
+    /// assert_eq!(*w.data(), (0, 11));
+    /// assert_eq!(w.timestamp(), 1);
+    /// ```
+    pub fn set_validator<F, E>(&mut self, validator: F)
+    where
+        F: Fn(&T) -> Result<(), E> + Send + 'static,
+        E: Into<BoxError>,
+    {
+        self.validator = Some(Box::new(move |data| validator(data).map_err(Into::into)));
+    }
+
+    /// Remove the validator set with [`Writer::set_validator`].
+    ///
+    /// This returns `true` if there was a validator.
+    ///
+    /// ```rust
+    /// # use someday::*;
+    /// let (_, mut w) = someday::new(0);
+    /// assert!(!w.clear_validator());
+    ///
+    /// w.set_validator(|_| Err("always invalid"));
+    /// assert!(w.clear_validator());
+    ///
+    /// w.add(Patch::Ptr(|w, _| *w += 1));
+    /// assert!(w.try_commit().is_ok());
+    /// ```
+    pub fn clear_validator(&mut self) -> bool {
+        self.validator.take().is_some()
+    }
+
+    /// Check the local data `T` against the validator, if there is one.
+    pub(crate) fn validate(&self) -> Result<(), ValidateError> {
+        let Some(validator) = self.validator.as_ref() else {
+            return Ok(());
+        };
+
+        let local = self.local_as_ref();
+        validator(&local.data).map_err(|error| ValidateError {
+            timestamp: local.timestamp,
+            error,
+        })
+    }
+}
@@ -1,15 +1,33 @@
 //! `Writer<T>`
 
 //---------------------------------------------------------------------------------------------------- Use
-use std::{borrow::Borrow, sync::Arc};
+use alloc::{sync::Arc, vec::Vec};
+use core::borrow::Borrow;
 
+#[cfg(feature = "std")]
+use crate::writer::Costs;
+#[cfg(feature = "stats")]
+use crate::writer::Stats;
 use crate::{
+    apply::Apply,
     commit::{Commit, CommitRef},
+    free::INIT_VEC_CAP,
+    header::CommitHeader,
+    lineage::Lineage,
+    metadata::Metadata,
     patch::Patch,
     reader::Reader,
-    writer::WriterToken,
+    sync::ArcSwap,
+    transaction::SyncBase,
+    writer::{
+        determinism::Determinism,
+        hook::{CommitHook, PushHook},
+        validate::Validator,
+        CompactPolicy, DropPolicy, ForkBase, RetryPolicy, WriterToken,
+    },
 };
 
+#[cfg(feature = "std")]
 #[allow(unused_imports)] // docs
 use std::sync::Mutex;
 
@@ -33,6 +51,16 @@ use std::sync::Mutex;
 /// | 1 Writer            | There can only ever be 1 `Writer` at any given moment (at least, without shared mutual exclusion like [`Arc`] + [`Mutex`]).
 /// | Poison              | If a `Writer` panics mid-[`push()`](Writer::push), the data can only be poisoned on the `Writer` side - i.e. `Reader`'s will be completely fine if the `Writer` panics, other `Writer`'s (e.g [`Arc<Mutex<Writer<T>>>`]) _may_ panic as well on any function that accesses `T`.
 ///
+/// ## Patch type
+/// `Writer<T, P>` stores `P`'s as its `Patch`'s, which is [`Patch<T>`] by default.
+///
+/// Any `P` that implements [`Apply<T>`](crate::Apply) can be used with
+/// [`someday::new_static()`](crate::new_static), e.g. an `enum` of operations
+/// or a function pointer, which avoids the allocation and dynamic dispatch of `Patch<T>`.
+///
+/// Functions that create `Patch<T>`'s internally (e.g. [`Writer::add_commit`],
+/// [`Writer::overwrite`] or [`Writer::tx`]) are only available for `Writer<T>`.
+///
 /// ## Usage
 /// This example covers the typical usage of a `Writer`:
 /// - Creating some `Reader`'s
@@ -114,7 +142,8 @@ use std::sync::Mutex;
 /// assert_eq!(w.data(), "abcdefghi");
 /// assert_eq!(r.head().data, "abcdefghi");
 /// ```
-pub struct Writer<T: Clone> {
+#[allow(clippy::struct_excessive_bools)] // 1 per `patches_old`/`local` state
+pub struct Writer<T, P: Apply<T> = Patch<T>> {
     /// Only set to `false` when we are `drop()`'ed.
     pub(crate) token: WriterToken,
 
@@ -147,18 +176,149 @@ pub struct Writer<T: Clone> {
 
     /// The AtomicPtr that `Reader`'s enter through.
     /// Calling `.load()` would load the `remote` above.
-    pub(crate) arc: Arc<arc_swap::ArcSwap<Commit<T>>>,
+    pub(crate) arc: Arc<ArcSwap<Commit<T>>>,
 
     /// Patches that have not yet been applied.
-    pub(crate) patches: Vec<Patch<T>>,
+    pub(crate) patches: Vec<P>,
 
     /// Patches that were already applied,
     /// that must be re-applied to the old `T`.
-    pub(crate) patches_old: Vec<Patch<T>>,
+    pub(crate) patches_old: Vec<P>,
+
+    /// Functions called after a `push()`.
+    pub(crate) on_push: Vec<PushHook<T>>,
+
+    /// Functions called after a `commit()`.
+    pub(crate) on_commit: Vec<CommitHook<T>>,
+
+    /// Function that checks the data in `try_commit()` and `try_push()`.
+    pub(crate) validator: Option<Validator<T>>,
+
+    /// If re-applying `patches_old` onto `remote` re-creates `local`.
+    ///
+    /// This is `false` if `patches_old` contains a "sync" `Patch`
+    /// (e.g. from `overwrite()` or a `Transaction`), as those rely
+    /// on `r` being the _next_ pushed data, e.g. `*w = r.clone()`.
+    pub(crate) replayable: bool,
+
+    /// If the first `Patch` in `patches_old` is a known full sync, i.e.
+    /// `*w = r.clone()` from `overwrite()`, `just_clone()`, or a `Transaction`
+    /// that kept the default `Patch::CLONE` as its `sync_patch`.
+    ///
+    /// Those turn reclaimed data into exactly the pushed data, so the
+    /// `Patch`'s committed after it must not be re-applied on top.
+    ///
+    /// A custom `sync_patch` is replayed like any other `Patch`.
+    pub(crate) clone_sync: bool,
+
+    /// If the first `Patch` in `patches_old` is a custom `Transaction::sync_patch`.
+    pub(crate) sync_base: Option<SyncBase<T>>,
+
+    /// If the first `Patch` in `patches_old` sets the base from `rebase_onto()`.
+    ///
+    /// Unlike other "sync" `Patch`'s, it can be replaced by another base.
+    pub(crate) rebased: bool,
+
+    /// If user code (e.g. a `Patch`) panicked while `local` was
+    /// being modified, leaving it in an unknown state.
+    ///
+    /// `local` being `None` also means the `Writer` is poisoned.
+    pub(crate) poisoned: bool,
+
+    /// The `Lineage` of our `Commit`'s, see `Writer::track_lineage()`.
+    pub(crate) lineage: Option<Lineage>,
+
+    /// The `Commit` this `Writer` was forked from, if it was forked.
+    pub(crate) fork_base: Option<ForkBase<T>>,
+
+    /// How many times `push()` retries after losing a race to a `Writer::share()`'ed `Writer`.
+    pub(crate) retry: RetryPolicy,
+
+    /// What to do with unpushed commits on `drop()`.
+    pub(crate) drop_policy: DropPolicy,
+
+    /// When to compact `patches_old` into a clone after reclaiming data.
+    pub(crate) compact: CompactPolicy,
+
+    /// The measured costs for `CompactPolicy::Measure`.
+    #[cfg(feature = "std")]
+    pub(crate) costs: Costs,
+
+    /// The statistics returned by `Writer::stats()`.
+    #[cfg(feature = "stats")]
+    pub(crate) stats: Stats<T>,
+
+    /// `T`'s `Clone::clone`, used when the old head can't be reclaimed.
+    ///
+    /// `None` for `Writer`'s created with `new_with()`, which wait instead.
+    pub(crate) clone_fn: Option<fn(&T) -> T>,
+
+    /// The old head a `push()` without a `clone_fn` gave up waiting on.
+    ///
+    /// `local` is `None` (and the `Writer` poisoned) until
+    /// `Writer::recover()` reclaims it.
+    pub(crate) unreclaimed: Option<CommitRef<T>>,
+
+    /// `T`'s `PartialEq::eq` and `Clone::clone`, if `Patch`'s are checked for determinism.
+    pub(crate) determinism: Option<Determinism<T>>,
+
+    /// Function that encodes the data for `Digest`'s, computed on `push()`.
+    #[cfg(feature = "sha2")]
+    pub(crate) encode: Option<fn(&T) -> Vec<u8>>,
+
+    /// Faults forced by a `FaultInjector`, see `Writer::set_fault_injector()`.
+    #[cfg(feature = "testing")]
+    pub(crate) faults: crate::testing::Faults<T>,
 }
 
 //---------------------------------------------------------------------------------------------------- Private writer functions
-impl<T: Clone> Writer<T> {
+impl<T, P: Apply<T>> Writer<T, P> {
+    /// Create a `Writer` with no `Patch`'s and all the default settings.
+    ///
+    /// `remote` must be the `CommitRef` stored in `arc`, and `local` a copy of it,
+    /// the `Writer` continues in `local`'s `Lineage`.
+    pub(crate) fn from_parts(
+        token: WriterToken,
+        local: Commit<T>,
+        remote: CommitRef<T>,
+        arc: Arc<ArcSwap<Commit<T>>>,
+        clone_fn: Option<fn(&T) -> T>,
+    ) -> Self {
+        let lineage = local.header.lineage;
+        Self {
+            token,
+            local: Some(local),
+            remote,
+            arc,
+            patches: Vec::with_capacity(INIT_VEC_CAP),
+            patches_old: Vec::with_capacity(INIT_VEC_CAP),
+            on_push: Vec::new(),
+            on_commit: Vec::new(),
+            validator: None,
+            replayable: true,
+            clone_sync: false,
+            sync_base: None,
+            rebased: false,
+            poisoned: false,
+            lineage,
+            fork_base: None,
+            retry: RetryPolicy::default(),
+            drop_policy: DropPolicy::default(),
+            compact: CompactPolicy::default(),
+            #[cfg(feature = "std")]
+            costs: Costs::default(),
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
+            clone_fn,
+            unreclaimed: None,
+            determinism: None,
+            #[cfg(feature = "sha2")]
+            encode: None,
+            #[cfg(feature = "testing")]
+            faults: crate::testing::Faults::default(),
+        }
+    }
+
     #[allow(clippy::option_if_let_else, clippy::inline_always)]
     #[inline(always)]
     /// Borrow `self.local`.
@@ -180,14 +340,74 @@ impl<T: Clone> Writer<T> {
             None => panic!("the `Writer`'s local data <T> was not initialized (poisoned)"),
         }
     }
+
+    /// The `CommitHeader` of a new local `Commit` with `meta`.
+    pub(crate) const fn new_header(&self, meta: Option<Metadata>) -> CommitHeader {
+        CommitHeader {
+            lineage: self.lineage,
+            digest: None,
+            meta,
+        }
+    }
+
+    /// Start a new local `Commit`, i.e. increment
+    /// the local `timestamp` and replace its header.
+    pub(crate) fn bump_local(&mut self, meta: Option<Metadata>) {
+        let header = self.new_header(meta);
+        let local = self.local_as_mut();
+        local.timestamp += 1;
+        local.header = header;
+    }
+
+    /// Keep a copy of the data as of a custom `Transaction::sync_patch`
+    /// before something is committed on top of it, see [`SyncBase`].
+    pub(crate) fn sync_snapshot(&mut self) {
+        if let Some(SyncBase::Pending(clone_fn)) = self.sync_base {
+            self.sync_base = Some(SyncBase::Data(clone_fn(&self.local_as_ref().data)));
+        }
+    }
+
+    /// Copy `remote` into a new `Commit`, cloning the data with `clone_fn`.
+    pub(crate) fn clone_remote(&self, clone_fn: fn(&T) -> T) -> Commit<T> {
+        let remote = &*self.remote;
+        Commit {
+            data: clone_fn(&remote.data),
+            timestamp: remote.timestamp,
+            header: remote.header.clone(),
+        }
+    }
+}
+
+impl<T: Clone, P: Apply<T>> Writer<T, P> {
+    /// Re-create `local` by re-applying `patches_old` onto `remote`,
+    /// then set the local `timestamp`, the local header is kept.
+    ///
+    /// INVARIANT: `self.replayable` must be `true`.
+    pub(crate) fn replay_local(&mut self, timestamp: crate::Timestamp) {
+        let header = self
+            .local
+            .take()
+            .map(|local| local.header)
+            .unwrap_or_default();
+        let mut data = self.remote.data.clone();
+        for patch in &mut self.patches_old {
+            patch.apply(&mut data, &self.remote.data);
+        }
+
+        self.local = Some(Commit {
+            timestamp,
+            data,
+            header,
+        });
+    }
 }
 
 //---------------------------------------------------------------------------------------------------- Writer trait impl
-impl<T> std::fmt::Debug for Writer<T>
+impl<T, P: Apply<T>> core::fmt::Debug for Writer<T, P>
 where
-    T: Clone + std::fmt::Debug,
+    T: core::fmt::Debug,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Writer")
             .field("local", &self.local)
             .field("remote", &self.remote)
@@ -238,7 +458,7 @@ where
     }
 }
 
-impl<T: Clone> std::ops::Deref for Writer<T> {
+impl<T, P: Apply<T>> core::ops::Deref for Writer<T, P> {
     type Target = T;
 
     #[inline]
@@ -247,14 +467,14 @@ impl<T: Clone> std::ops::Deref for Writer<T> {
     }
 }
 
-impl<T: Clone> Borrow<T> for Writer<T> {
+impl<T, P: Apply<T>> Borrow<T> for Writer<T, P> {
     #[inline]
     fn borrow(&self) -> &T {
         &self.local_as_ref().data
     }
 }
 
-impl<T: Clone> AsRef<T> for Writer<T> {
+impl<T, P: Apply<T>> AsRef<T> for Writer<T, P> {
     #[inline]
     fn as_ref(&self) -> &T {
         &self.local_as_ref().data
@@ -270,7 +490,7 @@ impl<T: Clone> TryFrom<Reader<T>> for Writer<T> {
     }
 }
 
-impl<T: Clone> Clone for Writer<T> {
+impl<T: Clone, P: Apply<T>> Clone for Writer<T, P> {
     /// This is the exact same as [`Writer::fork`].
     ///
     /// Note that this means cloning a [`Writer`] completely
@@ -299,3 +519,23 @@ impl<T: Clone> Clone for Writer<T> {
         self.fork()
     }
 }
+
+impl<T, P: Apply<T>> Drop for Writer<T, P> {
+    /// Applies the [`DropPolicy`] if there are unpushed [`Commit`]'s.
+    fn drop(&mut self) {
+        // Nothing to push, or the data cannot be trusted.
+        if self.local.is_none() || self.poisoned || crate::sync::panicking() || self.synced() {
+            return;
+        }
+
+        match self.drop_policy {
+            DropPolicy::Discard => (),
+            DropPolicy::Push => drop(self.push()),
+            DropPolicy::Panic => debug_assert!(
+                self.synced(),
+                "a `Writer` with {} unpushed commit(s) was dropped",
+                self.timestamp_diff()
+            ),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! `loom` tests of the `Reader`/`Writer` protocol.
+//!
+//! Run with:
+//! ```bash
+//! RUSTFLAGS="--cfg loom" cargo test --test loom --release
+//! ```
+//!
+//! `loom` models the `WriterToken` (writer count, revival, death
+//! notifications), and `push()`/`head()` racing on the head `Arc`,
+//! i.e. `Reader`'s holding onto the old head while it is reclaimed.
+
+#![cfg(loom)]
+
+//---------------------------------------------------------------------------------------------------- Use
+use loom::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+// Not `loom`'s, these are shared across executions.
+use std::sync::atomic::AtomicBool;
+
+use someday::WriterState;
+
+//---------------------------------------------------------------------------------------------------- Push
+#[test]
+/// `Reader`'s see a pushed head, never anything
+/// else, whether `push()` reclaims or clones.
+fn push_reclaim() {
+    // Whether any execution reclaimed/cloned the old head.
+    static RECLAIMED: AtomicBool = AtomicBool::new(false);
+    static CLONED: AtomicBool = AtomicBool::new(false);
+
+    loom::model(|| {
+        let (r, mut w) = someday::new::<Vec<usize>>(vec![]);
+
+        let reader = thread::spawn(move || {
+            let head = r.head();
+            match head.timestamp {
+                0 => assert!(head.data.is_empty()),
+                1 => assert_eq!(head.data, [1]),
+                2 => assert_eq!(head.data, [1, 2]),
+                _ => unreachable!(),
+            }
+            // Hold onto the head, the `Writer` may push meanwhile.
+            thread::yield_now();
+        });
+
+        for i in 1..=2 {
+            let (push_info, (), _) = w.add_commit_push(move |w, _| w.push(i));
+            assert_eq!(w.data().last(), Some(&i));
+
+            let seen = if push_info.reclaimed {
+                &RECLAIMED
+            } else {
+                &CLONED
+            };
+            seen.store(true, Ordering::Relaxed);
+        }
+
+        reader.join().unwrap();
+        assert_eq!(w.reader().head().data, [1, 2]);
+    });
+
+    // `loom` explored the `Reader` holding onto the
+    // old head during `push()`, and releasing it before.
+    assert!(RECLAIMED.load(Ordering::Relaxed));
+    assert!(CLONED.load(Ordering::Relaxed));
+}
+
+//---------------------------------------------------------------------------------------------------- Revival
+#[test]
+/// At most 1 `Reader` revives the `Writer`, and
+/// exactly 1 if both try after it was dropped.
+fn try_into_writer_race() {
+    loom::model(|| {
+        let (r1, w) = someday::new::<usize>(0);
+        let r2 = r1.clone();
+        let r3 = r1.clone();
+        let r4 = r1.clone();
+
+        let t1 = thread::spawn(move || r1.try_into_writer().ok());
+        let t2 = thread::spawn(move || r2.try_into_writer().ok());
+
+        drop(w);
+
+        // The revived `Writer`'s are kept alive until both threads
+        // are done, else the `Writer` could die and be revived again.
+        let w1 = t1.join().unwrap();
+        let w2 = t2.join().unwrap();
+        assert!(w1.is_none() || w2.is_none());
+
+        // Both try after the `Writer` was dropped (again).
+        drop((w1, w2));
+        let t3 = thread::spawn(move || r3.try_into_writer().ok());
+        let w4 = r4.try_into_writer().ok();
+        let w3 = t3.join().unwrap();
+        assert_eq!(usize::from(w3.is_some()) + usize::from(w4.is_some()), 1);
+    });
+}
+
+#[test]
+/// A revived `Writer` is not dead, and dies again when dropped.
+fn revive_and_drop() {
+    loom::model(|| {
+        let (r, w) = someday::new::<usize>(0);
+        let r2 = r.clone();
+
+        let t = thread::spawn(move || {
+            let Ok(mut w) = r2.try_into_writer() else {
+                return false;
+            };
+            w.add_commit_push(|w, _| *w += 1);
+            true
+        });
+
+        drop(w);
+        let revived = t.join().unwrap();
+
+        assert!(r.writer_dropped());
+        assert_eq!(r.head().data, usize::from(revived));
+    });
+}
+
+//---------------------------------------------------------------------------------------------------- Drop
+#[test]
+/// With `share()`'d `Writer`'s dropped concurrently, waiters
+/// wake up and callbacks run exactly once, after the last drop.
+fn writer_drop() {
+    loom::model(|| {
+        let (r, mut w1) = someday::new::<usize>(0);
+        let w2 = w1.share();
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let c = Arc::clone(&calls);
+        r.on_writer_dropped(move || {
+            c.fetch_add(1, Ordering::Relaxed);
+        });
+
+        let r2 = r.clone();
+        let waiter = thread::spawn(move || {
+            r2.wait_writer_dropped();
+            assert!(r2.writer_dropped());
+        });
+        let t = thread::spawn(move || drop(w2));
+
+        drop(w1);
+        t.join().unwrap();
+        waiter.join().unwrap();
+
+        assert!(r.writer_dropped());
+        assert_eq!(r.state(), WriterState::Abandoned);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    });
+}
+
+#[test]
+/// `close()` racing with a drop, the state is
+/// only `Closed` if the closing `Writer` was last.
+fn close_drop() {
+    loom::model(|| {
+        let (r, mut w1) = someday::new::<usize>(0);
+        let w2 = w1.share();
+
+        let t = thread::spawn(move || drop(w2));
+        w1.close("done");
+        t.join().unwrap();
+
+        match r.state() {
+            WriterState::Closed(reason) => assert_eq!(reason, "done"),
+            WriterState::Abandoned => {}
+            WriterState::Open => unreachable!(),
+        }
+    });
+}